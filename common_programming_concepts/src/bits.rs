@@ -0,0 +1,213 @@
+// SEE: ch03-02-data-types.html#integer-types
+//
+// every integer is just a row of bits, the type decides how the row
+// is read. signed types use two's complement: the top bit counts as
+// -2^(n-1) instead of +2^(n-1), so -1i8 is 1111_1111 and -128i8 is
+// 1000_0000. to negate a number, invert every bit and add 1.
+//
+// `as` between integer types never fails, it works on the bits:
+//
+//   same width       the bits are kept and read the other way
+//   to a narrower    the high bits are cut off (truncation)
+//   to a wider       the new high bits are copies of the sign bit if
+//                    the source is signed (sign extension), else 0
+
+use std::fmt;
+
+use crate::literal::{Literal, Type};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Bits {
+    pub ty: Type,
+    // the low ty.bits() bits are the value, the rest are always 0
+    raw: u128,
+}
+
+impl Bits {
+    // None for floats, `as` between ints and floats converts the
+    // value instead of the bits
+    pub fn new(lit: &Literal) -> Option<Bits> {
+        let raw = match *lit {
+            Literal::I8(v) => v as u128,
+            Literal::I16(v) => v as u128,
+            Literal::I32(v) => v as u128,
+            Literal::I64(v) => v as u128,
+            Literal::I128(v) => v as u128,
+            Literal::Isize(v) => v as u128,
+            Literal::U8(v) => v.into(),
+            Literal::U16(v) => v.into(),
+            Literal::U32(v) => v.into(),
+            Literal::U64(v) => v.into(),
+            Literal::U128(v) => v,
+            Literal::Usize(v) => v as u128,
+            Literal::F32(_) | Literal::F64(_) => return None,
+        };
+        Some(Bits::from_raw(lit.ty(), raw))
+    }
+
+    fn from_raw(ty: Type, raw: u128) -> Bits {
+        Bits { ty, raw: raw & mask(ty.bits()) }
+    }
+
+    pub fn raw(&self) -> u128 {
+        self.raw
+    }
+
+    pub fn width(&self) -> u32 {
+        self.ty.bits()
+    }
+
+    pub fn sign_bit(&self) -> bool {
+        self.raw >> (self.width() - 1) & 1 == 1
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.ty.is_signed() && self.sign_bit()
+    }
+
+    // the value as 128 bits, sign extended when negative. together
+    // with the sign this tells whether two Bits hold the same number
+    fn extended(&self) -> u128 {
+        if self.is_negative() { self.raw | !mask(self.width()) } else { self.raw }
+    }
+
+    // -5i8 is 1111_1011, most significant bit first
+    pub fn binary(&self) -> String {
+        group(&format!("{:0w$b}", self.raw, w = self.width() as usize), 4)
+    }
+
+    pub fn octal(&self) -> String {
+        let digits = (self.width() as usize).div_ceil(3);
+        group(&format!("{:0w$o}", self.raw, w = digits), 3)
+    }
+
+    pub fn hex(&self) -> String {
+        group(&format!("{:0w$x}", self.raw, w = self.width() as usize / 4), 4)
+    }
+
+    // what to_be_bytes() and to_le_bytes() would give
+    pub fn be_bytes(&self) -> Vec<u8> {
+        let n = self.width() as usize / 8;
+        self.raw.to_be_bytes()[16 - n..].to_vec()
+    }
+
+    pub fn le_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.be_bytes();
+        bytes.reverse();
+        bytes
+    }
+
+    // for a negative number, the steps that build it from its
+    // magnitude: the magnitude, every bit inverted, then plus 1
+    pub fn twos_complement(&self) -> Option<[Bits; 3]> {
+        if !self.is_negative() {
+            return None;
+        }
+        let magnitude = Bits::from_raw(self.ty, self.raw.wrapping_neg());
+        let inverted = Bits::from_raw(self.ty, !magnitude.raw);
+        let plus_one = Bits::from_raw(self.ty, inverted.raw.wrapping_add(1));
+        Some([magnitude, inverted, plus_one])
+    }
+
+    pub fn cast(&self, to: Type) -> Cast {
+        let (from_w, to_w) = (self.width(), to.bits());
+        let kind = if to_w < from_w {
+            CastKind::Truncate
+        } else if to_w == from_w {
+            CastKind::Reinterpret
+        } else if self.ty.is_signed() {
+            CastKind::SignExtend
+        } else {
+            CastKind::ZeroExtend
+        };
+        let raw = match kind {
+            CastKind::SignExtend if self.sign_bit() => self.raw | !mask(from_w),
+            _ => self.raw,
+        };
+        Cast { from: *self, to: Bits::from_raw(to, raw), kind }
+    }
+}
+
+// the value with its type, like the literal you would write
+impl fmt::Display for Bits {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_negative() {
+            write!(f, "{}{}", self.extended() as i128, self.ty)
+        } else {
+            write!(f, "{}{}", self.raw, self.ty)
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CastKind {
+    Reinterpret,
+    Truncate,
+    SignExtend,
+    ZeroExtend,
+}
+
+impl fmt::Display for CastKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            CastKind::Reinterpret => "same bits",
+            CastKind::Truncate => "truncated",
+            CastKind::SignExtend => "sign extended",
+            CastKind::ZeroExtend => "zero extended",
+        })
+    }
+}
+
+// what happened to one bit position in a cast
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mark {
+    Kept,
+    Dropped,
+    SignCopy,
+    ZeroFill,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cast {
+    pub from: Bits,
+    pub to: Bits,
+    pub kind: CastKind,
+}
+
+impl Cast {
+    // did the number we meant survive the cast?
+    pub fn same_value(&self) -> bool {
+        self.from.is_negative() == self.to.is_negative() && self.from.extended() == self.to.extended()
+    }
+
+    // one mark per bit of the wider of the two types, most
+    // significant first
+    pub fn marks(&self) -> Vec<Mark> {
+        let (from_w, to_w) = (self.from.width(), self.to.width());
+        (0..from_w.max(to_w))
+            .rev()
+            .map(|i| match self.kind {
+                CastKind::Truncate if i >= to_w => Mark::Dropped,
+                CastKind::SignExtend if i >= from_w => Mark::SignCopy,
+                CastKind::ZeroExtend if i >= from_w => Mark::ZeroFill,
+                _ => Mark::Kept,
+            })
+            .collect()
+    }
+}
+
+fn mask(bits: u32) -> u128 {
+    if bits == 128 { u128::MAX } else { (1 << bits) - 1 }
+}
+
+// "11111111" -> "1111_1111", grouping from the right like a literal
+pub fn group(digits: &str, size: usize) -> String {
+    let mut out = String::new();
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(size) {
+            out.push('_');
+        }
+        out.push(c);
+    }
+    out
+}
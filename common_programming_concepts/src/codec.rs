@@ -0,0 +1,356 @@
+// a compact binary format for the scalar types and for the tuples
+// and arrays built from them, like (i32, f64, bool) or [f32; 3].
+//
+// there is no header and no type information, the bytes are just the
+// fields one after the other. that works because the type is fixed
+// at compile time: both sides know a (i32, f64, bool) is 4 + 8 + 1
+// bytes, the same way the compiler knows its fields.
+//
+//   integers   fixed width in the chosen byte order, or a varint
+//              (7 bits per byte, high bit = more bytes follow) with
+//              signed values zigzag encoded so -1 is small too.
+//              isize and usize are always written as 64 bits so the
+//              bytes don't depend on the machine
+//   floats     their bits, fixed width, never varint
+//   bool       one byte, 0 or 1
+//   char       its code point, as a u32
+//
+// decoding never trusts the input: running out of bytes, a bool
+// that isn't 0 or 1, a number that doesn't fit, or bytes left over
+// at the end are all errors.
+
+use std::convert::TryFrom;
+use std::error;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Endian {
+    Little,
+    Big,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Config {
+    pub endian: Endian,
+    pub varint: bool,
+}
+
+impl Default for Config {
+    // what to_le_bytes() gives, the byte order of most machines
+    fn default() -> Config {
+        Config { endian: Endian::Little, varint: false }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ErrorKind {
+    // the input ended in the middle of a value
+    UnexpectedEnd { needed: usize },
+    InvalidBool(u8),
+    InvalidChar(u32),
+    // a varint (or a 64 bit isize/usize) too big for its type
+    TooLarge(&'static str),
+    TrailingBytes(usize),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Error {
+    pub kind: ErrorKind,
+    // where in the input the bad value starts
+    pub offset: usize,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.kind {
+            ErrorKind::UnexpectedEnd { needed } => {
+                write!(f, "input ends early, {} more byte(s) needed", needed)
+            }
+            ErrorKind::InvalidBool(b) => write!(f, "{:#04x} is not a bool, only 0 and 1 are", b),
+            ErrorKind::InvalidChar(c) => write!(f, "{:#x} is not a char", c),
+            ErrorKind::TooLarge(ty) => write!(f, "number too large for {}", ty),
+            ErrorKind::TrailingBytes(n) => write!(f, "{} byte(s) left over after the value", n),
+        }?;
+        write!(f, " (at byte {})", self.offset)
+    }
+}
+
+impl error::Error for Error {}
+
+// the input being decoded and how far we've got
+pub struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+    config: Config,
+}
+
+impl<'a> Reader<'a> {
+    pub fn new(bytes: &'a [u8], config: Config) -> Reader<'a> {
+        Reader { bytes, pos: 0, config }
+    }
+
+    pub fn config(&self) -> Config {
+        self.config
+    }
+
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+
+    fn error(&self, kind: ErrorKind, offset: usize) -> Error {
+        Error { kind, offset }
+    }
+
+    pub fn take(&mut self, n: usize) -> Result<&'a [u8], Error> {
+        let left = self.bytes.len() - self.pos;
+        if n > left {
+            return Err(self.error(ErrorKind::UnexpectedEnd { needed: n - left }, self.pos));
+        }
+        let bytes = &self.bytes[self.pos..self.pos + n];
+        self.pos += n;
+        Ok(bytes)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], Error> {
+        let mut out = [0; N];
+        out.copy_from_slice(self.take(N)?);
+        if self.config.endian == Endian::Big {
+            out.reverse();
+        }
+        // always little-endian from here on
+        Ok(out)
+    }
+
+    // LEB128: 7 bits at a time, low bits first
+    fn varint(&mut self, ty: &'static str) -> Result<u128, Error> {
+        let start = self.pos;
+        let mut value = 0u128;
+        let mut shift = 0;
+        loop {
+            let byte = self.take(1)?[0];
+            let bits = u128::from(byte & 0x7f);
+            if shift >= 128 || (shift > 0 && bits >> (128 - shift) != 0) {
+                return Err(self.error(ErrorKind::TooLarge(ty), start));
+            }
+            value |= bits << shift;
+            shift += 7;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+    }
+}
+
+pub trait Encode {
+    fn encode(&self, config: Config, out: &mut Vec<u8>);
+}
+
+pub trait Decode: Sized {
+    fn decode(input: &mut Reader) -> Result<Self, Error>;
+}
+
+pub fn to_bytes<T: Encode + ?Sized>(value: &T, config: Config) -> Vec<u8> {
+    let mut out = Vec::new();
+    value.encode(config, &mut out);
+    out
+}
+
+pub fn from_bytes<T: Decode>(bytes: &[u8], config: Config) -> Result<T, Error> {
+    let mut input = Reader::new(bytes, config);
+    let value = T::decode(&mut input)?;
+    let left = bytes.len() - input.pos;
+    if left > 0 {
+        return Err(input.error(ErrorKind::TrailingBytes(left), input.pos));
+    }
+    Ok(value)
+}
+
+fn put(config: Config, mut bytes: Vec<u8>, out: &mut Vec<u8>) {
+    // bytes come in little-endian
+    if config.endian == Endian::Big {
+        bytes.reverse();
+    }
+    out.extend_from_slice(&bytes);
+}
+
+fn put_varint(mut v: u128, out: &mut Vec<u8>) {
+    while v >= 0x80 {
+        out.push(v as u8 | 0x80);
+        v >>= 7;
+    }
+    out.push(v as u8);
+}
+
+// -1 -> 1, 1 -> 2, -2 -> 3 ... so small negative numbers stay short
+fn zigzag(v: i128) -> u128 {
+    ((v << 1) ^ (v >> 127)) as u128
+}
+
+fn unzigzag(v: u128) -> i128 {
+    (v >> 1) as i128 ^ -((v & 1) as i128)
+}
+
+// $wire is the type that goes on the wire, only different for
+// isize and usize
+macro_rules! unsigned {
+    ($($t:ident as $wire:ident),*) => {$(
+        impl Encode for $t {
+            fn encode(&self, config: Config, out: &mut Vec<u8>) {
+                if config.varint {
+                    put_varint(*self as u128, out);
+                } else {
+                    put(config, (*self as $wire).to_le_bytes().to_vec(), out);
+                }
+            }
+        }
+
+        impl Decode for $t {
+            fn decode(input: &mut Reader) -> Result<$t, Error> {
+                let start = input.pos;
+                let wide = if input.config.varint {
+                    input.varint(stringify!($t))?
+                } else {
+                    $wire::from_le_bytes(input.array()?) as u128
+                };
+                $t::try_from(wide)
+                    .map_err(|_| input.error(ErrorKind::TooLarge(stringify!($t)), start))
+            }
+        }
+    )*};
+}
+
+macro_rules! signed {
+    ($($t:ident as $wire:ident),*) => {$(
+        impl Encode for $t {
+            fn encode(&self, config: Config, out: &mut Vec<u8>) {
+                if config.varint {
+                    put_varint(zigzag(*self as i128), out);
+                } else {
+                    put(config, (*self as $wire).to_le_bytes().to_vec(), out);
+                }
+            }
+        }
+
+        impl Decode for $t {
+            fn decode(input: &mut Reader) -> Result<$t, Error> {
+                let start = input.pos;
+                let wide = if input.config.varint {
+                    unzigzag(input.varint(stringify!($t))?)
+                } else {
+                    $wire::from_le_bytes(input.array()?) as i128
+                };
+                $t::try_from(wide)
+                    .map_err(|_| input.error(ErrorKind::TooLarge(stringify!($t)), start))
+            }
+        }
+    )*};
+}
+
+unsigned!(u8 as u8, u16 as u16, u32 as u32, u64 as u64, u128 as u128, usize as u64);
+signed!(i8 as i8, i16 as i16, i32 as i32, i64 as i64, i128 as i128, isize as i64);
+
+macro_rules! float {
+    ($($t:ident as $bits:ident),*) => {$(
+        impl Encode for $t {
+            fn encode(&self, config: Config, out: &mut Vec<u8>) {
+                put(config, self.to_bits().to_le_bytes().to_vec(), out);
+            }
+        }
+
+        impl Decode for $t {
+            fn decode(input: &mut Reader) -> Result<$t, Error> {
+                Ok($t::from_bits($bits::from_le_bytes(input.array()?)))
+            }
+        }
+    )*};
+}
+
+float!(f32 as u32, f64 as u64);
+
+impl Encode for bool {
+    fn encode(&self, _: Config, out: &mut Vec<u8>) {
+        out.push(*self as u8);
+    }
+}
+
+impl Decode for bool {
+    fn decode(input: &mut Reader) -> Result<bool, Error> {
+        let start = input.pos;
+        match input.take(1)?[0] {
+            0 => Ok(false),
+            1 => Ok(true),
+            b => Err(input.error(ErrorKind::InvalidBool(b), start)),
+        }
+    }
+}
+
+impl Encode for char {
+    fn encode(&self, config: Config, out: &mut Vec<u8>) {
+        u32::from(*self).encode(config, out);
+    }
+}
+
+impl Decode for char {
+    fn decode(input: &mut Reader) -> Result<char, Error> {
+        let start = input.pos;
+        let code = u32::decode(input)?;
+        char::from_u32(code).ok_or_else(|| input.error(ErrorKind::InvalidChar(code), start))
+    }
+}
+
+// an array is its elements one after the other, the length is part
+// of the type so it isn't written
+impl<T: Encode, const N: usize> Encode for [T; N] {
+    fn encode(&self, config: Config, out: &mut Vec<u8>) {
+        for item in self {
+            item.encode(config, out);
+        }
+    }
+}
+
+impl<T: Decode, const N: usize> Decode for [T; N] {
+    fn decode(input: &mut Reader) -> Result<[T; N], Error> {
+        let mut items = Vec::with_capacity(N);
+        for _ in 0..N {
+            items.push(T::decode(input)?);
+        }
+        match <[T; N]>::try_from(items) {
+            Ok(array) => Ok(array),
+            Err(_) => unreachable!("exactly N items were pushed"),
+        }
+    }
+}
+
+// and a tuple is its fields in order
+macro_rules! tuple {
+    ($($name:ident)*) => {
+        impl<$($name: Encode),*> Encode for ($($name,)*) {
+            #[allow(non_snake_case, unused_variables)]
+            fn encode(&self, config: Config, out: &mut Vec<u8>) {
+                let ($($name,)*) = self;
+                $($name.encode(config, out);)*
+            }
+        }
+
+        impl<$($name: Decode),*> Decode for ($($name,)*) {
+            #[allow(unused_variables)]
+            fn decode(input: &mut Reader) -> Result<($($name,)*), Error> {
+                Ok(($($name::decode(input)?,)*))
+            }
+        }
+    };
+}
+
+tuple!();
+tuple!(A);
+tuple!(A B);
+tuple!(A B C);
+tuple!(A B C D);
+tuple!(A B C D E);
+tuple!(A B C D E F);
+tuple!(A B C D E F G);
+tuple!(A B C D E F G H);
+tuple!(A B C D E F G H I);
+tuple!(A B C D E F G H I J);
+tuple!(A B C D E F G H I J K);
+tuple!(A B C D E F G H I J K L);
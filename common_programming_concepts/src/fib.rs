@@ -0,0 +1,128 @@
+// SEE: ch03-05-control-flow.html#summary
+//
+// "generate the nth Fibonacci number", three ways. F(0) = 0,
+// F(1) = 1, F(n) = F(n-1) + F(n-2). the numbers grow fast, F(94)
+// is already too big for a u64 and F(1_000_000) has 208_988 digits,
+// so they're BigUints.
+//
+//   iterative      keep the last two numbers and add. n additions
+//   memoized       the same, but every number is kept so asking
+//                  again (or for a smaller n) is a lookup. the table
+//                  gets big: it holds all n numbers
+//   fast doubling  F(2k)   = F(k) * (2 F(k+1) - F(k))
+//                  F(2k+1) = F(k)^2 + F(k+1)^2
+//                  walks the bits of n, so about log2(n) steps of a
+//                  few multiplications. the only one that's fast
+//                  for n in the millions
+//
+// past the others' limits there's nothing to compare fast doubling
+// with, so check() tests an F(n) against its neighbours instead,
+// worked out by separate calls.
+
+use num_bigint::BigUint;
+
+pub fn iterative(n: u64) -> BigUint {
+    let (mut a, mut b) = (BigUint::from(0u32), BigUint::from(1u32));
+    for _ in 0..n {
+        let next = &a + &b;
+        a = std::mem::replace(&mut b, next);
+    }
+    a
+}
+
+pub struct Memo {
+    table: Vec<BigUint>,
+}
+
+impl Memo {
+    pub fn new() -> Memo {
+        Memo { table: vec![BigUint::from(0u32), BigUint::from(1u32)] }
+    }
+
+    pub fn get(&mut self, n: u64) -> &BigUint {
+        let n = n as usize;
+        while self.table.len() <= n {
+            let len = self.table.len();
+            let next = &self.table[len - 1] + &self.table[len - 2];
+            self.table.push(next);
+        }
+        &self.table[n]
+    }
+}
+
+impl Default for Memo {
+    fn default() -> Memo {
+        Memo::new()
+    }
+}
+
+pub fn fast_doubling(n: u64) -> BigUint {
+    // (F(k), F(k+1)), starting at k = 0 and reading n's bits from the
+    // top: each bit doubles k, and a 1 bit adds one more
+    let mut a = BigUint::from(0u32);
+    let mut b = BigUint::from(1u32);
+    for bit in (0..64 - n.leading_zeros()).rev() {
+        let c = &a * (&b + &b - &a);
+        let d = &a * &a + &b * &b;
+        if n >> bit & 1 == 1 {
+            b = &c + &d;
+            a = d;
+        } else {
+            a = c;
+            b = d;
+        }
+    }
+    a
+}
+
+// Cassini's identity, F(n-1) F(n+1) - F(n)^2 = (-1)^n, and
+// F(n+1) = F(n) + F(n-1). a wrong F(n) can't satisfy both
+pub fn check(n: u64, value: &BigUint) -> bool {
+    // F(-1) = 1 keeps both true at n = 0
+    let prev = if n == 0 { BigUint::from(1u32) } else { fast_doubling(n - 1) };
+    let next = fast_doubling(n + 1);
+    let square = value * value;
+    let product = &prev * &next;
+    let cassini = if n.is_multiple_of(2) { product == square + 1u32 } else { product + 1u32 == square };
+    cassini && next == value + &prev
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Algorithm {
+    Iterative,
+    Memoized,
+    FastDoubling,
+}
+
+impl Algorithm {
+    pub const ALL: [Algorithm; 3] = [Algorithm::Iterative, Algorithm::Memoized, Algorithm::FastDoubling];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Algorithm::Iterative => "iterative",
+            Algorithm::Memoized => "memoized",
+            Algorithm::FastDoubling => "fast doubling",
+        }
+    }
+
+    // the biggest n worth trying. iterative and memoized do n additions
+    // of numbers up to 0.7n bits long, so their time grows like n^2,
+    // and the memo table's size too
+    pub fn limit(self) -> Option<u64> {
+        match self {
+            Algorithm::Iterative => Some(200_000),
+            // about 110 MB of table at 50_000
+            Algorithm::Memoized => Some(50_000),
+            Algorithm::FastDoubling => None,
+        }
+    }
+
+    // a fresh Memo each time, so the timing includes filling it
+    pub fn run(self, n: u64) -> BigUint {
+        match self {
+            Algorithm::Iterative => iterative(n),
+            Algorithm::Memoized => Memo::new().get(n).clone(),
+            Algorithm::FastDoubling => fast_doubling(n),
+        }
+    }
+}
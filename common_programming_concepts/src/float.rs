@@ -0,0 +1,314 @@
+// data_types() prints 1.2 for both a_f32 and b_f32, but they are
+// not the same number and neither of them is 1.2. a float is
+//
+//   sign | exponent | mantissa
+//
+// f32 has 1 | 8 | 23 bits and f64 has 1 | 11 | 52. for normal
+// numbers the value is (-1)^sign * 1.mantissa * 2^(exponent - bias).
+// println!("{}") shows the shortest decimal that parses back to the
+// same bits, which is why both print as 1.2. exact() shows every
+// digit of what's actually stored.
+
+use std::fmt;
+
+use crate::literal::{self, Literal, Type};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Class {
+    Zero,
+    // exponent bits all 0: no implicit leading 1, fixed exponent
+    Subnormal,
+    Normal,
+    Infinite,
+    // exponent bits all 1 and a non-zero mantissa. the top mantissa
+    // bit says quiet (1) or signaling (0), the rest is the payload
+    Nan { quiet: bool, payload: u64 },
+}
+
+impl fmt::Display for Class {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Class::Zero => write!(f, "zero"),
+            Class::Subnormal => write!(f, "subnormal"),
+            Class::Normal => write!(f, "normal"),
+            Class::Infinite => write!(f, "infinite"),
+            Class::Nan { quiet, payload } => write!(
+                f,
+                "{} NaN, payload {:#x}",
+                if *quiet { "quiet" } else { "signaling" },
+                payload
+            ),
+        }
+    }
+}
+
+// the raw bits of an f32 or f64. f32 bits sit in the low 32
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Float {
+    pub ty: Type,
+    pub bits: u64,
+}
+
+impl Float {
+    pub fn from_f32(v: f32) -> Float {
+        Float { ty: Type::F32, bits: v.to_bits().into() }
+    }
+
+    pub fn from_f64(v: f64) -> Float {
+        Float { ty: Type::F64, bits: v.to_bits() }
+    }
+
+    // None if ty isn't a float type or the bits don't fit
+    pub fn from_bits(ty: Type, bits: u64) -> Option<Float> {
+        match ty {
+            Type::F32 if bits <= u32::MAX.into() => Some(Float { ty, bits }),
+            Type::F64 => Some(Float { ty, bits }),
+            _ => None,
+        }
+    }
+
+    // (exponent bits, mantissa bits)
+    fn layout(self) -> (u32, u32) {
+        if self.ty == Type::F32 { (8, 23) } else { (11, 52) }
+    }
+
+    pub fn exponent_bits(self) -> u32 {
+        self.layout().0
+    }
+
+    pub fn mantissa_bits(self) -> u32 {
+        self.layout().1
+    }
+
+    pub fn bias(self) -> i32 {
+        (1 << (self.exponent_bits() - 1)) - 1
+    }
+
+    pub fn sign(self) -> bool {
+        let (e, m) = self.layout();
+        self.bits >> (e + m) & 1 == 1
+    }
+
+    pub fn biased_exponent(self) -> u64 {
+        let (e, m) = self.layout();
+        self.bits >> m & ((1 << e) - 1)
+    }
+
+    pub fn mantissa(self) -> u64 {
+        self.bits & ((1 << self.mantissa_bits()) - 1)
+    }
+
+    pub fn class(self) -> Class {
+        let (e, m) = self.layout();
+        let max_exponent = (1 << e) - 1;
+        match (self.biased_exponent(), self.mantissa()) {
+            (0, 0) => Class::Zero,
+            (0, _) => Class::Subnormal,
+            (x, 0) if x == max_exponent => Class::Infinite,
+            (x, man) if x == max_exponent => Class::Nan {
+                quiet: man >> (m - 1) == 1,
+                payload: man & ((1 << (m - 1)) - 1),
+            },
+            _ => Class::Normal,
+        }
+    }
+
+    // the power of two the mantissa is scaled by. subnormals are
+    // stuck at the smallest normal exponent
+    pub fn exponent(self) -> Option<i32> {
+        match self.class() {
+            Class::Normal => Some(self.biased_exponent() as i32 - self.bias()),
+            Class::Subnormal => Some(1 - self.bias()),
+            _ => None,
+        }
+    }
+
+    // every f32 is exactly representable as an f64
+    pub fn to_f64(self) -> f64 {
+        if self.ty == Type::F32 {
+            f32::from_bits(self.bits as u32).into()
+        } else {
+            f64::from_bits(self.bits)
+        }
+    }
+
+    pub fn next_up(self) -> Float {
+        match self.ty {
+            Type::F32 => Float::from_f32(f32::from_bits(self.bits as u32).next_up()),
+            _ => Float::from_f64(f64::from_bits(self.bits).next_up()),
+        }
+    }
+
+    pub fn next_down(self) -> Float {
+        match self.ty {
+            Type::F32 => Float::from_f32(f32::from_bits(self.bits as u32).next_down()),
+            _ => Float::from_f64(f64::from_bits(self.bits).next_down()),
+        }
+    }
+
+    // every decimal digit of the stored value. None for inf and NaN
+    pub fn exact(self) -> Option<String> {
+        exact_decimal(self.to_f64())
+    }
+
+    // exact distance to another float of the same type. neighbours
+    // are always a power of two apart, which f64 holds exactly
+    pub fn gap(self, other: Float) -> Option<String> {
+        let (a, b) = (self.to_f64(), other.to_f64());
+        if !a.is_finite() || !b.is_finite() {
+            return None;
+        }
+        exact_decimal((a - b).abs())
+    }
+
+    // "0 01111111 00110011001100110011010"
+    pub fn bit_string(self) -> String {
+        let (e, m) = self.layout();
+        let all = format!("{:0width$b}", self.bits, width = (1 + e + m) as usize);
+        format!("{} {} {}", &all[..1], &all[1..1 + e as usize], &all[1 + e as usize..])
+    }
+}
+
+impl fmt::Display for Float {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.ty == Type::F32 {
+            write!(f, "{:?}", f32::from_bits(self.bits as u32))
+        } else {
+            write!(f, "{:?}", f64::from_bits(self.bits))
+        }
+    }
+}
+
+// the same decimal text rounded to f32 and to f64
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rounding {
+    pub f32: Float,
+    pub f64: Float,
+    // a_f32 as f64 == b_f32 ?
+    pub same: bool,
+    // how far apart the two stored values are, in f32 ULPs. the
+    // f32 error can be up to 0.5 of these, the f64 one is ~2^-29 smaller
+    pub apart_in_f32_ulps: f64,
+}
+
+// both roundings of a literal like "1.2". each type rounds the
+// decimal text on its own, going through f64 first would round twice
+pub fn rounding(text: &str) -> Result<Rounding, literal::Error> {
+    let single = match literal::parse(&format!("{}f32", text))? {
+        Literal::F32(v) => v,
+        // only a hex literal can swallow the suffix as digits
+        _ => return Err(hex_float()),
+    };
+    let double = match literal::parse(&format!("{}f64", text))? {
+        Literal::F64(v) => v,
+        _ => return Err(hex_float()),
+    };
+
+    let (f32, f64) = (Float::from_f32(single), Float::from_f64(double));
+    // the gap to the next f32 up, or down from f32::MAX where the
+    // next one up is infinity
+    let up = f32.next_up().to_f64();
+    let ulp = if up.is_finite() { up - f32.to_f64() } else { f32.to_f64() - f32.next_down().to_f64() };
+    Ok(Rounding {
+        f32,
+        f64,
+        same: f32.to_f64() == double,
+        apart_in_f32_ulps: (f32.to_f64() - double) / ulp,
+    })
+}
+
+fn hex_float() -> literal::Error {
+    literal::Error {
+        kind: literal::ErrorKind::FloatWithBase(16),
+        pos: 0,
+    }
+}
+
+// a finite float is m * 2^e for integers m and e. for e < 0 that
+// is m * 5^-e / 10^-e, so the digits are m * 5^-e with the point
+// moved -e places. f64's smallest subnormal has 751 digits
+pub fn exact_decimal(v: f64) -> Option<String> {
+    if !v.is_finite() {
+        return None;
+    }
+    let bits = v.to_bits();
+    let biased = (bits >> 52 & 0x7ff) as i32;
+    let fraction = bits & ((1 << 52) - 1);
+    let (m, e) = if biased == 0 {
+        (fraction, -1074)
+    } else {
+        (fraction | 1 << 52, biased - 1075)
+    };
+
+    let mut digits = BigDecimal::from(m);
+    if e >= 0 {
+        for _ in 0..e {
+            digits.mul_small(2);
+        }
+    } else {
+        for _ in 0..-e {
+            digits.mul_small(5);
+        }
+    }
+
+    let mut s = digits.to_string();
+    if e < 0 {
+        let point = (-e) as usize;
+        if s.len() <= point {
+            s = "0".repeat(point - s.len() + 1) + &s;
+        }
+        s.insert(s.len() - point, '.');
+        let trimmed = s.trim_end_matches('0').trim_end_matches('.').len();
+        s.truncate(trimmed);
+    }
+    if v.is_sign_negative() {
+        s.insert(0, '-');
+    }
+    Some(s)
+}
+
+// just enough of a big unsigned integer to multiply by small
+// numbers and print. little endian limbs of 9 decimal digits
+struct BigDecimal {
+    limbs: Vec<u32>,
+}
+
+const LIMB: u64 = 1_000_000_000;
+
+impl BigDecimal {
+    fn from(mut v: u64) -> BigDecimal {
+        let mut limbs = vec![];
+        while v > 0 {
+            limbs.push((v % LIMB) as u32);
+            v /= LIMB;
+        }
+        BigDecimal { limbs }
+    }
+
+    fn mul_small(&mut self, k: u32) {
+        let mut carry = 0u64;
+        for limb in self.limbs.iter_mut() {
+            let x = u64::from(*limb) * u64::from(k) + carry;
+            *limb = (x % LIMB) as u32;
+            carry = x / LIMB;
+        }
+        if carry > 0 {
+            self.limbs.push(carry as u32);
+        }
+    }
+}
+
+impl fmt::Display for BigDecimal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.limbs.split_last() {
+            None => write!(f, "0"),
+            Some((top, rest)) => {
+                write!(f, "{}", top)?;
+                for limb in rest.iter().rev() {
+                    write!(f, "{:09}", limb)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
@@ -0,0 +1,138 @@
+// a tiny interpreter for the subset of Rust the lessons use: i32 and
+// bool, let/mut/shadowing, blocks as expressions, if/else, loop (with
+// break values), while and for over ranges. programs are type checked
+// before they run, and errors point at the code like rustc does
+
+pub mod ast;
+pub mod check;
+pub mod eval;
+mod lexer;
+mod parser;
+
+use std::fmt;
+
+use ast::Type;
+use check::Checker;
+use eval::{Env, Value};
+
+pub use parser::parse_program;
+
+// byte offsets into the source, end exclusive
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    pub fn new(start: usize, end: usize) -> Span {
+        Span { start, end }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Error {
+    // the rustc error code for the same mistake, if there is one
+    pub code: Option<&'static str>,
+    pub message: String,
+    pub span: Span,
+    pub note: Option<String>,
+}
+
+impl Error {
+    pub fn new(code: Option<&'static str>, message: &str, span: Span) -> Error {
+        Error {
+            code,
+            message: message.to_string(),
+            span,
+            note: None,
+        }
+    }
+
+    pub fn note(mut self, note: &str) -> Error {
+        self.note = Some(note.to_string());
+        self
+    }
+
+    // the error with the line it happened on and carets under the
+    // span, close to what rustc prints
+    pub fn render(&self, src: &str) -> String {
+        let start = self.span.start.min(src.len());
+        let line_start = src[..start].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = src[start..].find('\n').map_or(src.len(), |i| start + i);
+        let line_no = src[..start].matches('\n').count() + 1;
+        let col = src[line_start..start].chars().count();
+        let end = self.span.end.clamp(start, line_end);
+        let carets = src[start..end].chars().count().max(1);
+
+        let gutter = line_no.to_string().len();
+        let mut out = format!("{}\n", self);
+        out += &format!("{:w$}--> line {}:{}\n", "", line_no, col + 1, w = gutter);
+        out += &format!("{:w$} |\n", "", w = gutter);
+        out += &format!("{} | {}\n", line_no, &src[line_start..line_end]);
+        out += &format!("{:w$} | {}{}", "", " ".repeat(col), "^".repeat(carets), w = gutter);
+        if let Some(note) = &self.note {
+            out += &format!("\n{:w$} = note: {}", "", note, w = gutter);
+        }
+        out
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.code {
+            Some(code) => write!(f, "error[{}]: {}", code, self.message),
+            None => write!(f, "error: {}", self.message),
+        }
+    }
+}
+
+// the variables a REPL has built up so far. each run() checks the
+// whole input before running any of it, and an input that fails
+// leaves the session as it was
+#[derive(Debug, Clone, Default)]
+pub struct Session {
+    checker: Checker,
+    env: Env,
+}
+
+impl Session {
+    pub fn new() -> Session {
+        Session {
+            checker: Checker::new(),
+            env: Env::new(),
+        }
+    }
+
+    // the value of the last expression and its type, or None when the
+    // input ends with a statement
+    pub fn run(&mut self, src: &str) -> Result<Option<(Value, Type)>, Error> {
+        let program = parse_program(src)?;
+        let backup = self.clone();
+        let result = self
+            .checker
+            .check_program(&program)
+            .and_then(|ty| self.env.run_program(&program).map(|v| (v, ty)));
+        match result {
+            Ok((value, ty)) => Ok(program.tail.as_ref().map(|_| (value, ty))),
+            Err(e) => {
+                *self = backup;
+                Err(e)
+            }
+        }
+    }
+
+    // name, type, mutability and value of every variable in scope
+    pub fn bindings(&self) -> Vec<(String, check::Binding, Value)> {
+        let mut names = self.checker.names();
+        names.sort();
+        names
+            .into_iter()
+            .map(|name| {
+                let binding = self.checker.lookup(&name).unwrap();
+                let value = self.env.get(&name).unwrap();
+                (name, binding, value)
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,149 @@
+// the tree the parser builds. every node keeps its span so the
+// checker and the evaluator can point at the code they complain about
+
+use std::fmt;
+
+use super::Span;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Type {
+    I32,
+    Bool,
+    Unit,
+    // the type of `break`, `continue` and a loop that never breaks.
+    // it never produces a value, so it agrees with every other type
+    Never,
+}
+
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Type::I32 => "i32",
+            Type::Bool => "bool",
+            Type::Unit => "()",
+            Type::Never => "!",
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnOp {
+    Neg,
+    Not,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    And,
+    Or,
+}
+
+impl BinOp {
+    pub fn is_arithmetic(self) -> bool {
+        matches!(self, BinOp::Add | BinOp::Sub | BinOp::Mul | BinOp::Div | BinOp::Rem)
+    }
+
+    pub fn is_comparison(self) -> bool {
+        matches!(self, BinOp::Eq | BinOp::Ne | BinOp::Lt | BinOp::Le | BinOp::Gt | BinOp::Ge)
+    }
+
+    pub fn symbol(self) -> &'static str {
+        match self {
+            BinOp::Add => "+",
+            BinOp::Sub => "-",
+            BinOp::Mul => "*",
+            BinOp::Div => "/",
+            BinOp::Rem => "%",
+            BinOp::Eq => "==",
+            BinOp::Ne => "!=",
+            BinOp::Lt => "<",
+            BinOp::Le => "<=",
+            BinOp::Gt => ">",
+            BinOp::Ge => ">=",
+            BinOp::And => "&&",
+            BinOp::Or => "||",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Expr {
+    pub kind: ExprKind,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExprKind {
+    // kept unsigned so that -2147483648 can be checked as a whole
+    Int(u64),
+    Bool(bool),
+    Unit,
+    Var(String),
+    Unary(UnOp, Box<Expr>),
+    Binary(BinOp, Box<Expr>, Box<Expr>),
+    // `x = e`, or `x += e` when the op is set
+    Assign(String, Option<BinOp>, Box<Expr>),
+    Block(Block),
+    If(Box<Expr>, Block, Option<Box<Expr>>),
+    Loop(Block),
+    While(Box<Expr>, Block),
+    For(For),
+    Break(Option<Box<Expr>>),
+    Continue,
+}
+
+impl ExprKind {
+    // if, loop, while, for and plain blocks end in `}` and may be used
+    // as statements without a semicolon, as long as they are ()
+    pub fn is_block_like(&self) -> bool {
+        matches!(
+            self,
+            ExprKind::Block(_) | ExprKind::If(..) | ExprKind::Loop(_) | ExprKind::While(..) | ExprKind::For(_)
+        )
+    }
+}
+
+// for var in start..end (or ..=end, or (start..end).rev())
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct For {
+    pub var: String,
+    pub start: Box<Expr>,
+    pub end: Box<Expr>,
+    pub inclusive: bool,
+    pub rev: bool,
+    pub body: Block,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Block {
+    pub stmts: Vec<Stmt>,
+    // the last expression without a semicolon is the block's value
+    pub tail: Option<Box<Expr>>,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Stmt {
+    Let {
+        name: String,
+        mutable: bool,
+        ty: Option<Type>,
+        init: Expr,
+        span: Span,
+    },
+    // an expression followed by `;`, its value is thrown away
+    Semi(Expr),
+    // a block-like expression with no `;` that isn't the tail
+    BlockLike(Expr),
+}
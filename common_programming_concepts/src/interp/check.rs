@@ -0,0 +1,319 @@
+// the type checker runs before anything is evaluated, like rustc
+// does, so a program with a type error never runs at all. the
+// error codes are the ones rustc uses for the same mistake, try
+// `rustc --explain E0308`
+
+use std::collections::HashMap;
+
+use super::ast::{BinOp, Block, Expr, ExprKind, Stmt, Type, UnOp};
+use super::{Error, Span};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Binding {
+    pub ty: Type,
+    pub mutable: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LoopKind {
+    Loop,
+    While,
+    For,
+}
+
+#[derive(Debug, Clone)]
+struct LoopCtx {
+    kind: LoopKind,
+    // the type of the `break` values seen so far, `loop` only
+    break_ty: Option<Type>,
+}
+
+// the outermost scope outlives a single check() so the REPL can
+// keep its variables between lines
+#[derive(Debug, Clone, Default)]
+pub struct Checker {
+    scopes: Vec<HashMap<String, Binding>>,
+    loops: Vec<LoopCtx>,
+}
+
+impl Checker {
+    pub fn new() -> Checker {
+        Checker {
+            scopes: vec![HashMap::new()],
+            loops: vec![],
+        }
+    }
+
+    pub fn lookup(&self, name: &str) -> Option<Binding> {
+        self.scopes.iter().rev().find_map(|s| s.get(name).cloned())
+    }
+
+    // every variable name in the outermost scope
+    pub fn names(&self) -> Vec<String> {
+        self.scopes[0].keys().cloned().collect()
+    }
+
+    // checks a whole program in the outermost scope
+    pub fn check_program(&mut self, program: &Block) -> Result<Type, Error> {
+        self.block_in_scope(program)
+    }
+
+    fn block(&mut self, block: &Block) -> Result<Type, Error> {
+        self.scopes.push(HashMap::new());
+        let ty = self.block_in_scope(block);
+        self.scopes.pop();
+        ty
+    }
+
+    fn block_in_scope(&mut self, block: &Block) -> Result<Type, Error> {
+        let mut diverges = false;
+        for stmt in &block.stmts {
+            match stmt {
+                Stmt::Let { name, mutable, ty, init, .. } => {
+                    let found = self.expr(init)?;
+                    if let Some(ty) = ty {
+                        expect(*ty, found, init.span)?;
+                    }
+                    let ty = ty.unwrap_or(found);
+                    diverges |= found == Type::Never;
+                    // shadowing: a second `let x` simply replaces the
+                    // binding, with whatever type and mutability it has
+                    let binding = Binding { ty, mutable: *mutable };
+                    self.scopes.last_mut().unwrap().insert(name.clone(), binding);
+                }
+                Stmt::Semi(e) => {
+                    diverges |= self.expr(e)? == Type::Never;
+                }
+                Stmt::BlockLike(e) => {
+                    let ty = self.expr(e)?;
+                    if ty != Type::Unit && ty != Type::Never {
+                        return Err(mismatch(Type::Unit, ty, e.span)
+                            .note("a block-like expression used as a statement must be ()"));
+                    }
+                    diverges |= ty == Type::Never;
+                }
+            }
+        }
+
+        match &block.tail {
+            Some(tail) => self.expr(tail),
+            // { 6; } is () because the semicolon turned the last
+            // expression into a statement. unless we never get there
+            None if diverges => Ok(Type::Never),
+            None => Ok(Type::Unit),
+        }
+    }
+
+    fn expr(&mut self, e: &Expr) -> Result<Type, Error> {
+        match &e.kind {
+            ExprKind::Int(v) => {
+                if *v > i32::MAX as u64 {
+                    return Err(Error::new(None, "literal out of range for `i32`", e.span));
+                }
+                Ok(Type::I32)
+            }
+            ExprKind::Bool(_) => Ok(Type::Bool),
+            ExprKind::Unit => Ok(Type::Unit),
+            ExprKind::Var(name) => match self.lookup(name) {
+                Some(b) => Ok(b.ty),
+                None => {
+                    let msg = format!("cannot find value `{}` in this scope", name);
+                    Err(Error::new(Some("E0425"), &msg, e.span))
+                }
+            },
+            ExprKind::Unary(UnOp::Neg, operand) => {
+                // -2147483648 is fine even though 2147483648 isn't
+                if let ExprKind::Int(v) = operand.kind {
+                    if v == 1 << 31 {
+                        return Ok(Type::I32);
+                    }
+                }
+                let ty = self.expr(operand)?;
+                expect(Type::I32, ty, operand.span)?;
+                Ok(Type::I32)
+            }
+            ExprKind::Unary(UnOp::Not, operand) => {
+                let ty = self.expr(operand)?;
+                match ty {
+                    Type::I32 | Type::Bool | Type::Never => Ok(ty),
+                    _ => {
+                        let msg = format!("cannot apply unary operator `!` to type `{}`", ty);
+                        Err(Error::new(Some("E0600"), &msg, e.span))
+                    }
+                }
+            }
+            ExprKind::Binary(op, lhs, rhs) => self.binary(*op, lhs, rhs, e.span),
+            ExprKind::Assign(name, op, value) => {
+                let binding = match self.lookup(name) {
+                    Some(b) => b,
+                    None => {
+                        let msg = format!("cannot find value `{}` in this scope", name);
+                        return Err(Error::new(Some("E0425"), &msg, e.span));
+                    }
+                };
+                if !binding.mutable {
+                    let msg = format!("cannot assign twice to immutable variable `{}`", name);
+                    return Err(Error::new(Some("E0384"), &msg, e.span)
+                        .note(&format!("declare it with `let mut {}` to allow this", name)));
+                }
+                let ty = self.expr(value)?;
+                if op.is_some() {
+                    expect(Type::I32, binding.ty, e.span)?;
+                }
+                expect(binding.ty, ty, value.span)?;
+                Ok(Type::Unit)
+            }
+            ExprKind::Block(block) => self.block(block),
+            ExprKind::If(cond, then, otherwise) => {
+                let ty = self.expr(cond)?;
+                expect(Type::Bool, ty, cond.span)?;
+                let then_ty = self.block(then)?;
+                match otherwise {
+                    None => {
+                        if then_ty != Type::Unit && then_ty != Type::Never {
+                            let msg = "`if` may be missing an `else` clause";
+                            return Err(Error::new(Some("E0317"), msg, e.span).note(&format!(
+                                "the `if` block is `{}`, without an `else` it has to be `()`",
+                                then_ty
+                            )));
+                        }
+                        Ok(Type::Unit)
+                    }
+                    Some(otherwise) => {
+                        let else_ty = self.expr(otherwise)?;
+                        unify(then_ty, else_ty).ok_or_else(|| {
+                            let msg = "`if` and `else` have incompatible types";
+                            Error::new(Some("E0308"), msg, otherwise.span).note(&format!(
+                                "expected `{}` because of the `if` block, found `{}`",
+                                then_ty, else_ty
+                            ))
+                        })
+                    }
+                }
+            }
+            ExprKind::Loop(body) => {
+                let ctx = self.in_loop(LoopKind::Loop, body.span, |c| c.block(body))?;
+                // a loop without a break never finishes
+                Ok(ctx.break_ty.unwrap_or(Type::Never))
+            }
+            ExprKind::While(cond, body) => {
+                let ty = self.expr(cond)?;
+                expect(Type::Bool, ty, cond.span)?;
+                self.in_loop(LoopKind::While, body.span, |c| c.block(body))?;
+                Ok(Type::Unit)
+            }
+            ExprKind::For(f) => {
+                for bound in [&f.start, &f.end] {
+                    let ty = self.expr(bound)?;
+                    expect(Type::I32, ty, bound.span)?;
+                }
+                self.in_loop(LoopKind::For, f.body.span, |c| {
+                    let mut scope = HashMap::new();
+                    let binding = Binding { ty: Type::I32, mutable: false };
+                    scope.insert(f.var.clone(), binding);
+                    c.scopes.push(scope);
+                    let ty = c.block(&f.body);
+                    c.scopes.pop();
+                    ty
+                })?;
+                Ok(Type::Unit)
+            }
+            ExprKind::Break(value) => {
+                let value_ty = match value {
+                    Some(v) => self.expr(v)?,
+                    None => Type::Unit,
+                };
+                let ctx = match self.loops.last_mut() {
+                    Some(ctx) => ctx,
+                    None => {
+                        return Err(Error::new(Some("E0268"), "`break` outside of a loop", e.span))
+                    }
+                };
+                if ctx.kind != LoopKind::Loop && value.is_some() {
+                    let kind = if ctx.kind == LoopKind::While { "while" } else { "for" };
+                    let msg = format!("`break` with value from a `{}` loop", kind);
+                    return Err(Error::new(Some("E0571"), &msg, e.span)
+                        .note("only `loop` can break with a value"));
+                }
+                let ty = match ctx.break_ty {
+                    None => value_ty,
+                    Some(prev) => match unify(prev, value_ty) {
+                        Some(ty) => ty,
+                        None => {
+                            let span = value.as_ref().map_or(e.span, |v| v.span);
+                            return Err(mismatch(prev, value_ty, span)
+                                .note("every `break` in a loop must give the same type"));
+                        }
+                    },
+                };
+                ctx.break_ty = Some(ty);
+                Ok(Type::Never)
+            }
+            ExprKind::Continue => {
+                if self.loops.is_empty() {
+                    return Err(Error::new(Some("E0268"), "`continue` outside of a loop", e.span));
+                }
+                Ok(Type::Never)
+            }
+        }
+    }
+
+    fn binary(&mut self, op: BinOp, lhs: &Expr, rhs: &Expr, span: Span) -> Result<Type, Error> {
+        let l = self.expr(lhs)?;
+        let r = self.expr(rhs)?;
+        if op.is_arithmetic() {
+            expect(Type::I32, l, lhs.span)?;
+            expect(Type::I32, r, rhs.span)?;
+            return Ok(Type::I32);
+        }
+        if op == BinOp::And || op == BinOp::Or {
+            expect(Type::Bool, l, lhs.span)?;
+            expect(Type::Bool, r, rhs.span)?;
+            return Ok(Type::Bool);
+        }
+        // comparisons: both sides the same type
+        if unify(l, r).is_none() {
+            let msg = format!("cannot compare `{}` with `{}`", l, r);
+            return Err(Error::new(Some("E0308"), &msg, span));
+        }
+        Ok(Type::Bool)
+    }
+
+    fn in_loop<F>(&mut self, kind: LoopKind, span: Span, body: F) -> Result<LoopCtx, Error>
+    where
+        F: FnOnce(&mut Checker) -> Result<Type, Error>,
+    {
+        self.loops.push(LoopCtx { kind, break_ty: None });
+        let result = body(self);
+        let ctx = self.loops.pop().unwrap();
+        let ty = result?;
+        // the body's value is thrown away each time around, so it
+        // must be () just like a statement
+        if ty != Type::Unit && ty != Type::Never {
+            return Err(mismatch(Type::Unit, ty, span)
+                .note("a loop body's value is thrown away, end it with `;`"));
+        }
+        Ok(ctx)
+    }
+}
+
+// the common type of two branches, if there is one
+fn unify(a: Type, b: Type) -> Option<Type> {
+    match (a, b) {
+        (Type::Never, t) | (t, Type::Never) => Some(t),
+        (a, b) if a == b => Some(a),
+        _ => None,
+    }
+}
+
+fn expect(expected: Type, found: Type, span: Span) -> Result<(), Error> {
+    match unify(expected, found) {
+        Some(_) => Ok(()),
+        None => Err(mismatch(expected, found, span)),
+    }
+}
+
+fn mismatch(expected: Type, found: Type, span: Span) -> Error {
+    let msg = format!("mismatched types: expected `{}`, found `{}`", expected, found);
+    Error::new(Some("E0308"), &msg, span)
+}
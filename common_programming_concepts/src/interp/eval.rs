@@ -0,0 +1,276 @@
+// runs a program that already passed the checker, so the only
+// errors left are the ones Rust also finds at runtime: overflow
+// (we behave like a debug build and panic), division by zero, and
+// our own step limit so `loop {}` doesn't hang the REPL
+
+use std::collections::HashMap;
+use std::fmt;
+
+use super::ast::{BinOp, Block, Expr, ExprKind, Stmt, UnOp};
+use super::{Error, Span};
+
+// plenty for the lessons, and small enough to notice `loop {}`
+pub const MAX_STEPS: u64 = 10_000_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Value {
+    Int(i32),
+    Bool(bool),
+    Unit,
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Value::Int(v) => write!(f, "{}", v),
+            Value::Bool(v) => write!(f, "{}", v),
+            Value::Unit => write!(f, "()"),
+        }
+    }
+}
+
+// why evaluation stopped early. break and continue travel up to
+// the nearest loop the same way an error travels up to the caller
+enum Flow {
+    Break(Value),
+    Continue,
+    Error(Error),
+}
+
+type Eval = Result<Value, Flow>;
+
+#[derive(Debug, Clone, Default)]
+pub struct Env {
+    scopes: Vec<HashMap<String, Value>>,
+    steps: u64,
+}
+
+impl Env {
+    pub fn new() -> Env {
+        Env {
+            scopes: vec![HashMap::new()],
+            steps: 0,
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<Value> {
+        self.scopes.iter().rev().find_map(|s| s.get(name).cloned())
+    }
+
+    pub fn run_program(&mut self, program: &Block) -> Result<Value, Error> {
+        self.steps = 0;
+        match self.block_in_scope(program) {
+            Ok(v) => Ok(v),
+            Err(Flow::Error(e)) => Err(e),
+            // the checker doesn't allow these outside a loop
+            Err(Flow::Break(_)) | Err(Flow::Continue) => unreachable!("checked"),
+        }
+    }
+
+    fn block(&mut self, block: &Block) -> Eval {
+        self.scopes.push(HashMap::new());
+        let v = self.block_in_scope(block);
+        self.scopes.pop();
+        v
+    }
+
+    fn block_in_scope(&mut self, block: &Block) -> Eval {
+        for stmt in &block.stmts {
+            match stmt {
+                Stmt::Let { name, init, .. } => {
+                    let v = self.expr(init)?;
+                    self.scopes.last_mut().unwrap().insert(name.clone(), v);
+                }
+                Stmt::Semi(e) | Stmt::BlockLike(e) => {
+                    self.expr(e)?;
+                }
+            }
+        }
+        match &block.tail {
+            Some(tail) => self.expr(tail),
+            None => Ok(Value::Unit),
+        }
+    }
+
+    fn step(&mut self, span: Span) -> Result<(), Flow> {
+        self.steps += 1;
+        if self.steps > MAX_STEPS {
+            let msg = format!("gave up after {} steps, is there a loop without a `break`?", MAX_STEPS);
+            return Err(Flow::Error(Error::new(None, &msg, span)));
+        }
+        Ok(())
+    }
+
+    fn expr(&mut self, e: &Expr) -> Eval {
+        self.step(e.span)?;
+        match &e.kind {
+            ExprKind::Int(v) => Ok(Value::Int(*v as i32)),
+            ExprKind::Bool(v) => Ok(Value::Bool(*v)),
+            ExprKind::Unit => Ok(Value::Unit),
+            ExprKind::Var(name) => Ok(self.get(name).expect("checked")),
+            ExprKind::Unary(UnOp::Neg, operand) => {
+                if let ExprKind::Int(v) = operand.kind {
+                    if v == 1 << 31 {
+                        return Ok(Value::Int(i32::MIN));
+                    }
+                }
+                let v = int(self.expr(operand)?);
+                match v.checked_neg() {
+                    Some(v) => Ok(Value::Int(v)),
+                    None => panic_at("attempt to negate with overflow", e.span),
+                }
+            }
+            ExprKind::Unary(UnOp::Not, operand) => match self.expr(operand)? {
+                Value::Int(v) => Ok(Value::Int(!v)),
+                Value::Bool(v) => Ok(Value::Bool(!v)),
+                Value::Unit => unreachable!("checked"),
+            },
+            ExprKind::Binary(op, lhs, rhs) => {
+                // && and || only look at the right side when they need to
+                if *op == BinOp::And || *op == BinOp::Or {
+                    let l = boolean(self.expr(lhs)?);
+                    if (*op == BinOp::And) != l {
+                        return Ok(Value::Bool(l));
+                    }
+                    return self.expr(rhs);
+                }
+                let l = self.expr(lhs)?;
+                let r = self.expr(rhs)?;
+                binary(*op, l, r, e.span)
+            }
+            ExprKind::Assign(name, op, value) => {
+                let mut v = self.expr(value)?;
+                if let Some(op) = op {
+                    let old = self.get(name).expect("checked");
+                    v = binary(*op, old, v, e.span)?;
+                }
+                // the innermost binding with that name
+                for scope in self.scopes.iter_mut().rev() {
+                    if let Some(slot) = scope.get_mut(name) {
+                        *slot = v;
+                        break;
+                    }
+                }
+                Ok(Value::Unit)
+            }
+            ExprKind::Block(block) => self.block(block),
+            ExprKind::If(cond, then, otherwise) => {
+                if boolean(self.expr(cond)?) {
+                    self.block(then)
+                } else {
+                    match otherwise {
+                        Some(otherwise) => self.expr(otherwise),
+                        None => Ok(Value::Unit),
+                    }
+                }
+            }
+            ExprKind::Loop(body) => loop {
+                self.step(body.span)?;
+                match self.block(body) {
+                    Ok(_) | Err(Flow::Continue) => {}
+                    Err(Flow::Break(v)) => return Ok(v),
+                    Err(e) => return Err(e),
+                }
+            },
+            ExprKind::While(cond, body) => {
+                while boolean(self.expr(cond)?) {
+                    self.step(body.span)?;
+                    match self.block(body) {
+                        Ok(_) | Err(Flow::Continue) => {}
+                        Err(Flow::Break(_)) => break,
+                        Err(e) => return Err(e),
+                    }
+                }
+                Ok(Value::Unit)
+            }
+            ExprKind::For(f) => {
+                let start = int(self.expr(&f.start)?);
+                let end = int(self.expr(&f.end)?);
+                let range: Box<dyn Iterator<Item = i32>> = match (f.inclusive, f.rev) {
+                    (false, false) => Box::new(start..end),
+                    (false, true) => Box::new((start..end).rev()),
+                    (true, false) => Box::new(start..=end),
+                    (true, true) => Box::new((start..=end).rev()),
+                };
+                for i in range {
+                    self.step(f.body.span)?;
+                    let mut scope = HashMap::new();
+                    scope.insert(f.var.clone(), Value::Int(i));
+                    self.scopes.push(scope);
+                    let result = self.block(&f.body);
+                    self.scopes.pop();
+                    match result {
+                        Ok(_) | Err(Flow::Continue) => {}
+                        Err(Flow::Break(_)) => break,
+                        Err(e) => return Err(e),
+                    }
+                }
+                Ok(Value::Unit)
+            }
+            ExprKind::Break(value) => {
+                let v = match value {
+                    Some(v) => self.expr(v)?,
+                    None => Value::Unit,
+                };
+                Err(Flow::Break(v))
+            }
+            ExprKind::Continue => Err(Flow::Continue),
+        }
+    }
+}
+
+fn binary(op: BinOp, l: Value, r: Value, span: Span) -> Eval {
+    if op.is_comparison() {
+        let ord = match (l, r) {
+            (Value::Int(a), Value::Int(b)) => a.cmp(&b),
+            (Value::Bool(a), Value::Bool(b)) => a.cmp(&b),
+            _ => std::cmp::Ordering::Equal,
+        };
+        let result = match op {
+            BinOp::Eq => ord.is_eq(),
+            BinOp::Ne => ord.is_ne(),
+            BinOp::Lt => ord.is_lt(),
+            BinOp::Le => ord.is_le(),
+            BinOp::Gt => ord.is_gt(),
+            _ => ord.is_ge(),
+        };
+        return Ok(Value::Bool(result));
+    }
+
+    let (a, b) = (int(l), int(r));
+    let (result, what) = match op {
+        BinOp::Add => (a.checked_add(b), "add"),
+        BinOp::Sub => (a.checked_sub(b), "subtract"),
+        BinOp::Mul => (a.checked_mul(b), "multiply"),
+        BinOp::Div if b == 0 => return panic_at("attempt to divide by zero", span),
+        BinOp::Div => (a.checked_div(b), "divide"),
+        BinOp::Rem if b == 0 => {
+            let msg = "attempt to calculate the remainder with a divisor of zero";
+            return panic_at(msg, span);
+        }
+        BinOp::Rem => (a.checked_rem(b), "calculate the remainder"),
+        _ => unreachable!("checked"),
+    };
+    match result {
+        Some(v) => Ok(Value::Int(v)),
+        None => panic_at(&format!("attempt to {} with overflow", what), span),
+    }
+}
+
+fn panic_at(msg: &str, span: Span) -> Eval {
+    Err(Flow::Error(Error::new(None, &format!("panicked: {}", msg), span)))
+}
+
+fn int(v: Value) -> i32 {
+    match v {
+        Value::Int(v) => v,
+        _ => unreachable!("checked"),
+    }
+}
+
+fn boolean(v: Value) -> bool {
+    match v {
+        Value::Bool(v) => v,
+        _ => unreachable!("checked"),
+    }
+}
@@ -0,0 +1,217 @@
+// turns source text into tokens. whitespace and // comments are
+// skipped, everything else becomes a token with its byte span
+
+use super::{Error, Span};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Tok {
+    Int(u64),
+    Ident(String),
+    True,
+    False,
+    Let,
+    Mut,
+    If,
+    Else,
+    Loop,
+    While,
+    For,
+    In,
+    Break,
+    Continue,
+    LParen,
+    RParen,
+    LBrace,
+    RBrace,
+    Semi,
+    Colon,
+    Dot,
+    DotDot,
+    DotDotEq,
+    Eq,
+    EqEq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Plus,
+    Minus,
+    Star,
+    Slash,
+    Percent,
+    Bang,
+    AndAnd,
+    OrOr,
+    PlusEq,
+    MinusEq,
+    StarEq,
+    SlashEq,
+    PercentEq,
+    Eof,
+}
+
+impl Tok {
+    // how the token is written, for error messages
+    pub fn describe(&self) -> String {
+        let s = match self {
+            Tok::Int(v) => return format!("`{}`", v),
+            Tok::Ident(name) => return format!("`{}`", name),
+            Tok::Eof => return String::from("end of input"),
+            Tok::True => "true",
+            Tok::False => "false",
+            Tok::Let => "let",
+            Tok::Mut => "mut",
+            Tok::If => "if",
+            Tok::Else => "else",
+            Tok::Loop => "loop",
+            Tok::While => "while",
+            Tok::For => "for",
+            Tok::In => "in",
+            Tok::Break => "break",
+            Tok::Continue => "continue",
+            Tok::LParen => "(",
+            Tok::RParen => ")",
+            Tok::LBrace => "{",
+            Tok::RBrace => "}",
+            Tok::Semi => ";",
+            Tok::Colon => ":",
+            Tok::Dot => ".",
+            Tok::DotDot => "..",
+            Tok::DotDotEq => "..=",
+            Tok::Eq => "=",
+            Tok::EqEq => "==",
+            Tok::Ne => "!=",
+            Tok::Lt => "<",
+            Tok::Le => "<=",
+            Tok::Gt => ">",
+            Tok::Ge => ">=",
+            Tok::Plus => "+",
+            Tok::Minus => "-",
+            Tok::Star => "*",
+            Tok::Slash => "/",
+            Tok::Percent => "%",
+            Tok::Bang => "!",
+            Tok::AndAnd => "&&",
+            Tok::OrOr => "||",
+            Tok::PlusEq => "+=",
+            Tok::MinusEq => "-=",
+            Tok::StarEq => "*=",
+            Tok::SlashEq => "/=",
+            Tok::PercentEq => "%=",
+        };
+        format!("`{}`", s)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Token {
+    pub tok: Tok,
+    pub span: Span,
+}
+
+pub fn lex(src: &str) -> Result<Vec<Token>, Error> {
+    let bytes = src.as_bytes();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let c = bytes[i];
+        if c.is_ascii_whitespace() {
+            i += 1;
+            continue;
+        }
+        if src[i..].starts_with("//") {
+            while i < bytes.len() && bytes[i] != b'\n' {
+                i += 1;
+            }
+            continue;
+        }
+
+        let start = i;
+        let tok = if c.is_ascii_digit() {
+            while i < bytes.len() && (bytes[i].is_ascii_digit() || bytes[i] == b'_') {
+                i += 1;
+            }
+            if i < bytes.len() && bytes[i].is_ascii_alphabetic() {
+                let span = Span::new(i, i + 1);
+                return Err(Error::new(None, "only plain decimal i32 literals are supported", span));
+            }
+            let digits: String = src[start..i].chars().filter(|&c| c != '_').collect();
+            match digits.parse() {
+                Ok(v) => Tok::Int(v),
+                Err(_) => {
+                    let span = Span::new(start, i);
+                    return Err(Error::new(None, "integer literal is too large", span));
+                }
+            }
+        } else if c.is_ascii_alphabetic() || c == b'_' {
+            while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'_') {
+                i += 1;
+            }
+            match &src[start..i] {
+                "true" => Tok::True,
+                "false" => Tok::False,
+                "let" => Tok::Let,
+                "mut" => Tok::Mut,
+                "if" => Tok::If,
+                "else" => Tok::Else,
+                "loop" => Tok::Loop,
+                "while" => Tok::While,
+                "for" => Tok::For,
+                "in" => Tok::In,
+                "break" => Tok::Break,
+                "continue" => Tok::Continue,
+                name => Tok::Ident(name.to_string()),
+            }
+        } else {
+            // longest match first
+            let ops: &[(&str, Tok)] = &[
+                ("..=", Tok::DotDotEq),
+                ("..", Tok::DotDot),
+                ("==", Tok::EqEq),
+                ("!=", Tok::Ne),
+                ("<=", Tok::Le),
+                (">=", Tok::Ge),
+                ("&&", Tok::AndAnd),
+                ("||", Tok::OrOr),
+                ("+=", Tok::PlusEq),
+                ("-=", Tok::MinusEq),
+                ("*=", Tok::StarEq),
+                ("/=", Tok::SlashEq),
+                ("%=", Tok::PercentEq),
+                ("(", Tok::LParen),
+                (")", Tok::RParen),
+                ("{", Tok::LBrace),
+                ("}", Tok::RBrace),
+                (";", Tok::Semi),
+                (":", Tok::Colon),
+                (".", Tok::Dot),
+                ("=", Tok::Eq),
+                ("<", Tok::Lt),
+                (">", Tok::Gt),
+                ("+", Tok::Plus),
+                ("-", Tok::Minus),
+                ("*", Tok::Star),
+                ("/", Tok::Slash),
+                ("%", Tok::Percent),
+                ("!", Tok::Bang),
+            ];
+            match ops.iter().find(|(text, _)| src[i..].starts_with(text)) {
+                Some((text, tok)) => {
+                    i += text.len();
+                    tok.clone()
+                }
+                None => {
+                    let ch = src[i..].chars().next().unwrap();
+                    let span = Span::new(i, i + ch.len_utf8());
+                    return Err(Error::new(None, &format!("unknown start of token: {}", ch), span));
+                }
+            }
+        };
+        tokens.push(Token { tok, span: Span::new(start, i) });
+    }
+
+    tokens.push(Token { tok: Tok::Eof, span: Span::new(src.len(), src.len()) });
+    Ok(tokens)
+}
@@ -0,0 +1,455 @@
+// recursive descent, one function per precedence level. from
+// loosest to tightest, the same as Rust:
+//
+//   =  +=  -=  ...     right associative
+//   ||
+//   &&
+//   == != < <= > >=    can't be chained, a < b < c is an error
+//   +  -
+//   *  /  %
+//   -  !               unary
+//
+// a program is the inside of a block without the braces, so the
+// REPL can take `let x = 5; x + 1` and show 6.
+
+use super::ast::{BinOp, Block, Expr, ExprKind, For, Stmt, Type, UnOp};
+use super::lexer::{lex, Tok, Token};
+use super::{Error, Span};
+
+pub fn parse_program(src: &str) -> Result<Block, Error> {
+    let mut p = Parser { tokens: lex(src)?, pos: 0 };
+    let block = p.block_body(Tok::Eof, 0)?;
+    Ok(block)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> &Tok {
+        &self.tokens[self.pos].tok
+    }
+
+    fn span(&self) -> Span {
+        self.tokens[self.pos].span
+    }
+
+    // the end of the token before the current one
+    fn prev_end(&self) -> usize {
+        if self.pos == 0 { 0 } else { self.tokens[self.pos - 1].span.end }
+    }
+
+    fn bump(&mut self) -> Tok {
+        let tok = self.tokens[self.pos].tok.clone();
+        if tok != Tok::Eof {
+            self.pos += 1;
+        }
+        tok
+    }
+
+    fn eat(&mut self, tok: &Tok) -> bool {
+        if self.peek() == tok {
+            self.bump();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, tok: Tok) -> Result<Span, Error> {
+        let span = self.span();
+        if self.eat(&tok) {
+            Ok(span)
+        } else {
+            Err(self.unexpected(&tok.describe()))
+        }
+    }
+
+    fn unexpected(&self, wanted: &str) -> Error {
+        let msg = format!("expected {}, found {}", wanted, self.peek().describe());
+        Error::new(None, &msg, self.span())
+    }
+
+    fn ident(&mut self) -> Result<String, Error> {
+        match self.peek().clone() {
+            Tok::Ident(name) => {
+                self.bump();
+                Ok(name)
+            }
+            _ => Err(self.unexpected("identifier")),
+        }
+    }
+
+    // statements up to `end`. the last expression without a `;`
+    // becomes the tail, which is the value of the whole block
+    fn block_body(&mut self, end: Tok, start: usize) -> Result<Block, Error> {
+        let mut stmts = Vec::new();
+        let mut tail = None;
+
+        while self.peek() != &end {
+            if self.peek() == &Tok::Eof {
+                return Err(self.unexpected(&end.describe()));
+            }
+            if self.eat(&Tok::Semi) {
+                continue;
+            }
+            if self.peek() == &Tok::Let {
+                stmts.push(self.let_stmt()?);
+                continue;
+            }
+
+            let block_like = matches!(
+                self.peek(),
+                Tok::LBrace | Tok::If | Tok::Loop | Tok::While | Tok::For
+            );
+            // `if c { 1 } else { 2 } - 1` at the start of a statement is
+            // two things in Rust: the if, then `-1`. so a block-like
+            // expression is never continued with a binary operator here
+            let expr = if block_like { self.primary()? } else { self.expr()? };
+
+            if self.eat(&Tok::Semi) {
+                stmts.push(Stmt::Semi(expr));
+            } else if self.peek() == &end {
+                tail = Some(Box::new(expr));
+            } else if block_like {
+                stmts.push(Stmt::BlockLike(expr));
+            } else {
+                let mut err = self.unexpected("`;` or `}`");
+                err.span = Span::new(expr.span.end, expr.span.end);
+                return Err(err);
+            }
+        }
+
+        let close = self.bump();
+        debug_assert_eq!(close, end);
+        Ok(Block {
+            stmts,
+            tail,
+            span: Span::new(start, self.prev_end()),
+        })
+    }
+
+    fn block(&mut self) -> Result<Block, Error> {
+        let open = self.expect(Tok::LBrace)?;
+        self.block_body(Tok::RBrace, open.start)
+    }
+
+    // let [mut] name [: type] = expr;
+    fn let_stmt(&mut self) -> Result<Stmt, Error> {
+        let start = self.expect(Tok::Let)?.start;
+        let mutable = self.eat(&Tok::Mut);
+        let name = self.ident()?;
+        let ty = if self.eat(&Tok::Colon) { Some(self.ty()?) } else { None };
+        if self.peek() == &Tok::Semi {
+            return Err(Error::new(
+                None,
+                "`let` without a value is not supported here, write `let x = ...;`",
+                self.span(),
+            ));
+        }
+        self.expect(Tok::Eq)?;
+        let init = self.expr()?;
+        let end = self.expect(Tok::Semi)?.end;
+        Ok(Stmt::Let {
+            name,
+            mutable,
+            ty,
+            init,
+            span: Span::new(start, end),
+        })
+    }
+
+    fn ty(&mut self) -> Result<Type, Error> {
+        let span = self.span();
+        if self.eat(&Tok::LParen) {
+            self.expect(Tok::RParen)?;
+            return Ok(Type::Unit);
+        }
+        match self.ident()?.as_str() {
+            "i32" => Ok(Type::I32),
+            "bool" => Ok(Type::Bool),
+            other => {
+                let msg = format!("unknown type `{}`, only i32, bool and () exist here", other);
+                Err(Error::new(Some("E0412"), &msg, span))
+            }
+        }
+    }
+
+    fn expr(&mut self) -> Result<Expr, Error> {
+        let lhs = self.binary(0)?;
+        let op = match self.peek() {
+            Tok::Eq => None,
+            Tok::PlusEq => Some(BinOp::Add),
+            Tok::MinusEq => Some(BinOp::Sub),
+            Tok::StarEq => Some(BinOp::Mul),
+            Tok::SlashEq => Some(BinOp::Div),
+            Tok::PercentEq => Some(BinOp::Rem),
+            _ => return Ok(lhs),
+        };
+        let op_span = self.span();
+        self.bump();
+
+        let name = match lhs.kind {
+            ExprKind::Var(name) => name,
+            _ => {
+                let msg = "invalid left-hand side of assignment";
+                return Err(Error::new(Some("E0070"), msg, op_span));
+            }
+        };
+        let rhs = self.expr()?;
+        let span = Span::new(lhs.span.start, rhs.span.end);
+        Ok(Expr {
+            kind: ExprKind::Assign(name, op, Box::new(rhs)),
+            span,
+        })
+    }
+
+    fn binary(&mut self, min_prec: u8) -> Result<Expr, Error> {
+        let mut lhs = self.unary()?;
+        loop {
+            let op = match binop(self.peek()) {
+                Some(op) => op,
+                None => return Ok(lhs),
+            };
+            let prec = precedence(op);
+            if prec < min_prec {
+                return Ok(lhs);
+            }
+            self.bump();
+            let rhs = self.binary(prec + 1)?;
+
+            if op.is_comparison() {
+                if let Some(next) = binop(self.peek()) {
+                    if next.is_comparison() {
+                        let msg = "comparison operators cannot be chained";
+                        return Err(Error::new(None, msg, self.span()));
+                    }
+                }
+            }
+            let span = Span::new(lhs.span.start, rhs.span.end);
+            lhs = Expr {
+                kind: ExprKind::Binary(op, Box::new(lhs), Box::new(rhs)),
+                span,
+            };
+        }
+    }
+
+    fn unary(&mut self) -> Result<Expr, Error> {
+        let start = self.span().start;
+        let op = match self.peek() {
+            Tok::Minus => UnOp::Neg,
+            Tok::Bang => UnOp::Not,
+            _ => return self.primary(),
+        };
+        self.bump();
+        let operand = self.unary()?;
+        let span = Span::new(start, operand.span.end);
+        Ok(Expr {
+            kind: ExprKind::Unary(op, Box::new(operand)),
+            span,
+        })
+    }
+
+    fn primary(&mut self) -> Result<Expr, Error> {
+        let span = self.span();
+        let kind = match self.peek().clone() {
+            Tok::Int(v) => {
+                self.bump();
+                ExprKind::Int(v)
+            }
+            Tok::True => {
+                self.bump();
+                ExprKind::Bool(true)
+            }
+            Tok::False => {
+                self.bump();
+                ExprKind::Bool(false)
+            }
+            Tok::Ident(name) => {
+                self.bump();
+                ExprKind::Var(name)
+            }
+            Tok::LParen => {
+                self.bump();
+                if self.eat(&Tok::RParen) {
+                    ExprKind::Unit
+                } else {
+                    let inner = self.expr()?;
+                    self.expect(Tok::RParen)?;
+                    // keep the parens in the span for error messages
+                    return Ok(Expr {
+                        kind: inner.kind,
+                        span: Span::new(span.start, self.prev_end()),
+                    });
+                }
+            }
+            Tok::LBrace => ExprKind::Block(self.block()?),
+            Tok::If => return self.if_expr(),
+            Tok::Loop => {
+                self.bump();
+                ExprKind::Loop(self.block()?)
+            }
+            Tok::While => {
+                self.bump();
+                let cond = self.expr()?;
+                ExprKind::While(Box::new(cond), self.block()?)
+            }
+            Tok::For => return self.for_expr(),
+            Tok::Break => {
+                self.bump();
+                let value = if starts_expr(self.peek()) {
+                    Some(Box::new(self.expr()?))
+                } else {
+                    None
+                };
+                ExprKind::Break(value)
+            }
+            Tok::Continue => {
+                self.bump();
+                ExprKind::Continue
+            }
+            // the lesson's `let x = (let y = 6);`
+            Tok::Let => {
+                let msg = "expected expression, found `let` statement";
+                return Err(Error::new(None, msg, span).note(
+                    "`let` is a statement, it doesn't produce a value to bind",
+                ));
+            }
+            _ => return Err(self.unexpected("expression")),
+        };
+        Ok(Expr {
+            kind,
+            span: Span::new(span.start, self.prev_end()),
+        })
+    }
+
+    // if cond { .. } [else if .. | else { .. }]
+    fn if_expr(&mut self) -> Result<Expr, Error> {
+        let start = self.expect(Tok::If)?.start;
+        let cond = self.expr()?;
+        let then = self.block()?;
+        let otherwise = if self.eat(&Tok::Else) {
+            if self.peek() == &Tok::If {
+                Some(Box::new(self.if_expr()?))
+            } else {
+                let block = self.block()?;
+                let span = block.span;
+                Some(Box::new(Expr {
+                    kind: ExprKind::Block(block),
+                    span,
+                }))
+            }
+        } else {
+            None
+        };
+        Ok(Expr {
+            kind: ExprKind::If(Box::new(cond), then, otherwise),
+            span: Span::new(start, self.prev_end()),
+        })
+    }
+
+    // for x in a..b { }, for x in a..=b { }, for x in (a..b).rev() { }
+    fn for_expr(&mut self) -> Result<Expr, Error> {
+        let start = self.expect(Tok::For)?.start;
+        let var = self.ident()?;
+        self.expect(Tok::In)?;
+
+        // (a..b).rev() needs a peek past the parenthesis: (a)..b
+        // is a plain range whose start happens to be in parens
+        let saved = self.pos;
+        let mut range = None;
+        if self.eat(&Tok::LParen) {
+            let from = self.binary(0)?;
+            if matches!(self.peek(), Tok::DotDot | Tok::DotDotEq) {
+                let (to, inclusive) = self.range_end()?;
+                self.expect(Tok::RParen)?;
+                let rev = if self.eat(&Tok::Dot) {
+                    let span = self.span();
+                    if self.ident()? != "rev" {
+                        let msg = "only `.rev()` can be called on a range";
+                        return Err(Error::new(None, msg, span));
+                    }
+                    self.expect(Tok::LParen)?;
+                    self.expect(Tok::RParen)?;
+                    true
+                } else {
+                    false
+                };
+                range = Some((from, to, inclusive, rev));
+            } else {
+                self.pos = saved;
+            }
+        }
+        let (from, to, inclusive, rev) = match range {
+            Some(range) => range,
+            None => {
+                let from = self.binary(0)?;
+                let (to, inclusive) = self.range_end()?;
+                (from, to, inclusive, false)
+            }
+        };
+
+        let body = self.block()?;
+        Ok(Expr {
+            kind: ExprKind::For(For {
+                var,
+                start: Box::new(from),
+                end: Box::new(to),
+                inclusive,
+                rev,
+                body,
+            }),
+            span: Span::new(start, self.prev_end()),
+        })
+    }
+
+    fn range_end(&mut self) -> Result<(Expr, bool), Error> {
+        let inclusive = match self.peek() {
+            Tok::DotDot => false,
+            Tok::DotDotEq => true,
+            _ => return Err(self.unexpected("`..` or `..=`, `for` only loops over ranges here")),
+        };
+        self.bump();
+        Ok((self.binary(0)?, inclusive))
+    }
+}
+
+fn binop(tok: &Tok) -> Option<BinOp> {
+    let op = match tok {
+        Tok::Plus => BinOp::Add,
+        Tok::Minus => BinOp::Sub,
+        Tok::Star => BinOp::Mul,
+        Tok::Slash => BinOp::Div,
+        Tok::Percent => BinOp::Rem,
+        Tok::EqEq => BinOp::Eq,
+        Tok::Ne => BinOp::Ne,
+        Tok::Lt => BinOp::Lt,
+        Tok::Le => BinOp::Le,
+        Tok::Gt => BinOp::Gt,
+        Tok::Ge => BinOp::Ge,
+        Tok::AndAnd => BinOp::And,
+        Tok::OrOr => BinOp::Or,
+        _ => return None,
+    };
+    Some(op)
+}
+
+fn precedence(op: BinOp) -> u8 {
+    match op {
+        BinOp::Or => 1,
+        BinOp::And => 2,
+        BinOp::Eq | BinOp::Ne | BinOp::Lt | BinOp::Le | BinOp::Gt | BinOp::Ge => 3,
+        BinOp::Add | BinOp::Sub => 4,
+        BinOp::Mul | BinOp::Div | BinOp::Rem => 5,
+    }
+}
+
+// can `break` be followed by a value?
+fn starts_expr(tok: &Tok) -> bool {
+    !matches!(
+        tok,
+        Tok::Semi | Tok::RBrace | Tok::RParen | Tok::Eof | Tok::Else
+    )
+}
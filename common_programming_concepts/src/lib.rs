@@ -0,0 +1,14 @@
+// the lessons live in main.rs. the tools that grew out of them
+// live here so they can be used (and run) on their own
+pub mod bits;
+pub mod codec;
+pub mod fib;
+pub mod float;
+pub mod interp;
+pub mod linalg;
+pub mod lint;
+pub mod literal;
+pub mod overflow;
+pub mod song;
+pub mod unicode;
+pub mod units;
@@ -0,0 +1,542 @@
+// arr1: [f32; 3] from data_types(), grown into a vector type. the
+// length is a const generic, part of the type like it is for arrays,
+// so adding a Vector<f32, 2> to a Vector<f32, 3> or multiplying a
+// 2x3 matrix by a 2x3 matrix doesn't compile. no runtime checks, no
+// panics, and no heap: a Vector<f32, 3> is just a [f32; 3].
+
+use std::convert::TryFrom;
+use std::fmt;
+use std::ops::{Add, AddAssign, Div, Index, IndexMut, Mul, Neg, Sub, SubAssign};
+
+// what the element type has to support. implemented below for the
+// signed integer and float types.
+//
+// the operators overflow like they do for the ints themselves. the
+// checked_ ones give None instead: |i8::MIN| is 128, which isn't an
+// i8. floats just give inf, never None
+pub trait Num:
+    Copy
+    + PartialEq
+    + PartialOrd
+    + fmt::Debug
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Neg<Output = Self>
+{
+    const ZERO: Self;
+    const ONE: Self;
+
+    // a type with room for the products of two values, i128 for the
+    // ints. the determinant works in it
+    type Wide: Num;
+
+    fn widen(self) -> Self::Wide;
+    // None when it doesn't fit back
+    fn narrow(wide: Self::Wide) -> Option<Self>;
+
+    fn checked_abs(self) -> Option<Self>;
+    fn checked_add(self, other: Self) -> Option<Self>;
+    fn checked_sub(self, other: Self) -> Option<Self>;
+    fn checked_mul(self, other: Self) -> Option<Self>;
+    fn checked_div(self, other: Self) -> Option<Self>;
+}
+
+// and what a length needs on top of that
+pub trait Float: Num {
+    fn sqrt(self) -> Self;
+}
+
+macro_rules! int {
+    ($($t:ty),*) => {$(
+        impl Num for $t {
+            const ZERO: $t = 0;
+            const ONE: $t = 1;
+
+            type Wide = i128;
+
+            fn widen(self) -> i128 {
+                self as i128
+            }
+
+            fn narrow(wide: i128) -> Option<$t> {
+                <$t>::try_from(wide).ok()
+            }
+
+            fn checked_abs(self) -> Option<$t> {
+                <$t>::checked_abs(self)
+            }
+
+            fn checked_add(self, other: $t) -> Option<$t> {
+                <$t>::checked_add(self, other)
+            }
+
+            fn checked_sub(self, other: $t) -> Option<$t> {
+                <$t>::checked_sub(self, other)
+            }
+
+            fn checked_mul(self, other: $t) -> Option<$t> {
+                <$t>::checked_mul(self, other)
+            }
+
+            fn checked_div(self, other: $t) -> Option<$t> {
+                <$t>::checked_div(self, other)
+            }
+        }
+    )*};
+}
+
+macro_rules! float {
+    ($($t:ty),*) => {$(
+        impl Num for $t {
+            const ZERO: $t = 0.0;
+            const ONE: $t = 1.0;
+
+            type Wide = $t;
+
+            fn widen(self) -> $t {
+                self
+            }
+
+            fn narrow(wide: $t) -> Option<$t> {
+                Some(wide)
+            }
+
+            fn checked_abs(self) -> Option<$t> {
+                Some(self.abs())
+            }
+
+            fn checked_add(self, other: $t) -> Option<$t> {
+                Some(self + other)
+            }
+
+            fn checked_sub(self, other: $t) -> Option<$t> {
+                Some(self - other)
+            }
+
+            fn checked_mul(self, other: $t) -> Option<$t> {
+                Some(self * other)
+            }
+
+            fn checked_div(self, other: $t) -> Option<$t> {
+                Some(self / other)
+            }
+        }
+    )*};
+}
+
+int!(i8, i16, i32, i64, i128, isize);
+float!(f32, f64);
+
+impl Float for f32 {
+    fn sqrt(self) -> f32 {
+        f32::sqrt(self)
+    }
+}
+
+impl Float for f64 {
+    fn sqrt(self) -> f64 {
+        f64::sqrt(self)
+    }
+}
+
+// the sizes have to match, or it doesn't compile. these are run as
+// doctests, so they keep being rejected:
+//
+/// ```compile_fail,E0308
+/// use common_programming_concepts::linalg::Vector;
+/// let _ = Vector::new([1, 2]) + Vector::new([1, 2, 3]);
+/// ```
+///
+/// ```compile_fail,E0308
+/// use common_programming_concepts::linalg::Vector;
+/// let _ = Vector::new([1, 2]).dot(Vector::new([1, 2, 3]));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Vector<T, const N: usize>([T; N]);
+
+impl<T: Num, const N: usize> Vector<T, N> {
+    pub fn new(items: [T; N]) -> Vector<T, N> {
+        Vector(items)
+    }
+
+    pub fn zero() -> Vector<T, N> {
+        Vector([T::ZERO; N])
+    }
+
+    pub fn as_array(&self) -> &[T; N] {
+        &self.0
+    }
+
+    pub fn dot(self, other: Vector<T, N>) -> T {
+        self.0.iter().zip(other.0.iter()).fold(T::ZERO, |sum, (&a, &b)| sum + a * b)
+    }
+
+    // the length squared, no square root needed so it works for ints
+    pub fn norm_squared(self) -> T {
+        self.dot(self)
+    }
+
+    // |x1| + |x2| + ..., the "taxicab" length. None if it doesn't fit
+    // in T
+    pub fn norm_l1(self) -> Option<T> {
+        self.0.iter().try_fold(T::ZERO, |sum, &x| sum.checked_add(x.checked_abs()?))
+    }
+
+    // the biggest |xi|. None for an int MIN, whose |xi| doesn't fit
+    pub fn norm_max(self) -> Option<T> {
+        self.0.iter().try_fold(T::ZERO, |max, &x| {
+            let x = x.checked_abs()?;
+            Some(if x > max { x } else { max })
+        })
+    }
+
+    pub fn map<U, F: FnMut(T) -> U>(self, f: F) -> Vector<U, N> {
+        Vector(self.0.map(f))
+    }
+}
+
+impl<T: Float, const N: usize> Vector<T, N> {
+    // the usual euclidean length
+    pub fn norm(self) -> T {
+        self.norm_squared().sqrt()
+    }
+
+    // the same direction with length 1, None for the zero vector
+    pub fn normalize(self) -> Option<Vector<T, N>> {
+        let len = self.norm();
+        if len == T::ZERO {
+            None
+        } else {
+            Some(self / len)
+        }
+    }
+}
+
+// only 3d vectors have a cross product, and only they get this impl
+impl<T: Num> Vector<T, 3> {
+    pub fn cross(self, other: Vector<T, 3>) -> Vector<T, 3> {
+        let [a1, a2, a3] = self.0;
+        let [b1, b2, b3] = other.0;
+        Vector([a2 * b3 - a3 * b2, a3 * b1 - a1 * b3, a1 * b2 - a2 * b1])
+    }
+}
+
+impl<T, const N: usize> From<[T; N]> for Vector<T, N> {
+    fn from(items: [T; N]) -> Vector<T, N> {
+        Vector(items)
+    }
+}
+
+impl<T, const N: usize> From<Vector<T, N>> for [T; N] {
+    fn from(v: Vector<T, N>) -> [T; N] {
+        v.0
+    }
+}
+
+impl<T, const N: usize> Index<usize> for Vector<T, N> {
+    type Output = T;
+
+    fn index(&self, i: usize) -> &T {
+        &self.0[i]
+    }
+}
+
+impl<T, const N: usize> IndexMut<usize> for Vector<T, N> {
+    fn index_mut(&mut self, i: usize) -> &mut T {
+        &mut self.0[i]
+    }
+}
+
+impl<T: Num, const N: usize> Add for Vector<T, N> {
+    type Output = Vector<T, N>;
+
+    fn add(mut self, other: Vector<T, N>) -> Vector<T, N> {
+        self += other;
+        self
+    }
+}
+
+impl<T: Num, const N: usize> AddAssign for Vector<T, N> {
+    fn add_assign(&mut self, other: Vector<T, N>) {
+        for (a, b) in self.0.iter_mut().zip(other.0) {
+            *a = *a + b;
+        }
+    }
+}
+
+impl<T: Num, const N: usize> Sub for Vector<T, N> {
+    type Output = Vector<T, N>;
+
+    fn sub(mut self, other: Vector<T, N>) -> Vector<T, N> {
+        self -= other;
+        self
+    }
+}
+
+impl<T: Num, const N: usize> SubAssign for Vector<T, N> {
+    fn sub_assign(&mut self, other: Vector<T, N>) {
+        for (a, b) in self.0.iter_mut().zip(other.0) {
+            *a = *a - b;
+        }
+    }
+}
+
+impl<T: Num, const N: usize> Neg for Vector<T, N> {
+    type Output = Vector<T, N>;
+
+    fn neg(self) -> Vector<T, N> {
+        self.map(|x| -x)
+    }
+}
+
+// v * 2.0 and v / 2.0
+impl<T: Num, const N: usize> Mul<T> for Vector<T, N> {
+    type Output = Vector<T, N>;
+
+    fn mul(self, k: T) -> Vector<T, N> {
+        self.map(|x| x * k)
+    }
+}
+
+impl<T: Num, const N: usize> Div<T> for Vector<T, N> {
+    type Output = Vector<T, N>;
+
+    fn div(self, k: T) -> Vector<T, N> {
+        self.map(|x| x / k)
+    }
+}
+
+// 2.0 * v needs one impl per concrete type, a generic
+// `impl<T> Mul<Vector<T, N>> for T` isn't allowed
+macro_rules! scalar_mul {
+    ($($t:ty),*) => {$(
+        impl<const N: usize> Mul<Vector<$t, N>> for $t {
+            type Output = Vector<$t, N>;
+
+            fn mul(self, v: Vector<$t, N>) -> Vector<$t, N> {
+                v * self
+            }
+        }
+    )*};
+}
+
+scalar_mul!(i8, i16, i32, i64, i128, isize, f32, f64);
+
+impl<T: fmt::Display, const N: usize> fmt::Display for Vector<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "(")?;
+        for (i, x) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", x)?;
+        }
+        write!(f, ")")
+    }
+}
+
+// R rows of C columns, stored row by row
+// a 2x3 times a 2x3 has nothing to match up, and a 2x3 has no
+// determinant:
+//
+/// ```compile_fail,E0308
+/// use common_programming_concepts::linalg::Matrix;
+/// let a = Matrix::new([[1, 2, 3], [4, 5, 6]]);
+/// let _ = a * a;
+/// ```
+///
+/// ```compile_fail,E0599
+/// use common_programming_concepts::linalg::Matrix;
+/// let _ = Matrix::new([[1, 2, 3], [4, 5, 6]]).determinant();
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Matrix<T, const R: usize, const C: usize>([[T; C]; R]);
+
+impl<T: Num, const R: usize, const C: usize> Matrix<T, R, C> {
+    pub fn new(rows: [[T; C]; R]) -> Matrix<T, R, C> {
+        Matrix(rows)
+    }
+
+    pub fn zero() -> Matrix<T, R, C> {
+        Matrix([[T::ZERO; C]; R])
+    }
+
+    pub fn row(&self, r: usize) -> Vector<T, C> {
+        Vector(self.0[r])
+    }
+
+    pub fn col(&self, c: usize) -> Vector<T, R> {
+        Vector(self.0.map(|row| row[c]))
+    }
+
+    // rows become columns: a 2x3 turns into a 3x2
+    pub fn transpose(&self) -> Matrix<T, C, R> {
+        let mut out = Matrix::<T, C, R>::zero();
+        for r in 0..R {
+            for c in 0..C {
+                out.0[c][r] = self.0[r][c];
+            }
+        }
+        out
+    }
+}
+
+impl<T: Num, const N: usize> Matrix<T, N, N> {
+    pub fn identity() -> Matrix<T, N, N> {
+        let mut m = Matrix::zero();
+        for i in 0..N {
+            m.0[i][i] = T::ONE;
+        }
+        m
+    }
+
+    // Bareiss elimination: like the school method of clearing the
+    // columns below each pivot, but every division is exact, so an
+    // integer matrix never needs fractions. only square matrices
+    // have one, so only they get this method.
+    //
+    // the values in between are products of two smaller determinants
+    // and outgrow T long before the answer does, so the work is done
+    // in T::Wide. None when even that overflows, or the answer doesn't
+    // fit in T
+    pub fn determinant(&self) -> Option<T> {
+        let mut m = self.0.map(|row| row.map(T::widen));
+        let one = T::ONE.widen();
+        let zero = T::ZERO.widen();
+        let mut sign = one;
+        let mut prev = one;
+        for k in 0..N {
+            // a zero pivot: swap in a row below that has something there
+            if m[k][k] == zero {
+                match (k + 1..N).find(|&r| m[r][k] != zero) {
+                    Some(r) => {
+                        m.swap(k, r);
+                        sign = -sign;
+                    }
+                    None => return Some(T::ZERO),
+                }
+            }
+            for i in k + 1..N {
+                for j in k + 1..N {
+                    let a = m[i][j].checked_mul(m[k][k])?;
+                    let b = m[i][k].checked_mul(m[k][j])?;
+                    m[i][j] = a.checked_sub(b)?.checked_div(prev)?;
+                }
+            }
+            prev = m[k][k];
+        }
+        match N {
+            0 => Some(T::ONE),
+            _ => T::narrow(sign.checked_mul(m[N - 1][N - 1])?),
+        }
+    }
+}
+
+impl<T, const R: usize, const C: usize> From<[[T; C]; R]> for Matrix<T, R, C> {
+    fn from(rows: [[T; C]; R]) -> Matrix<T, R, C> {
+        Matrix(rows)
+    }
+}
+
+impl<T, const R: usize, const C: usize> From<Matrix<T, R, C>> for [[T; C]; R] {
+    fn from(m: Matrix<T, R, C>) -> [[T; C]; R] {
+        m.0
+    }
+}
+
+// m[(row, col)]
+impl<T, const R: usize, const C: usize> Index<(usize, usize)> for Matrix<T, R, C> {
+    type Output = T;
+
+    fn index(&self, (r, c): (usize, usize)) -> &T {
+        &self.0[r][c]
+    }
+}
+
+impl<T, const R: usize, const C: usize> IndexMut<(usize, usize)> for Matrix<T, R, C> {
+    fn index_mut(&mut self, (r, c): (usize, usize)) -> &mut T {
+        &mut self.0[r][c]
+    }
+}
+
+impl<T: Num, const R: usize, const C: usize> Add for Matrix<T, R, C> {
+    type Output = Matrix<T, R, C>;
+
+    fn add(mut self, other: Matrix<T, R, C>) -> Matrix<T, R, C> {
+        for (a, b) in self.0.iter_mut().flatten().zip(other.0.iter().flatten()) {
+            *a = *a + *b;
+        }
+        self
+    }
+}
+
+impl<T: Num, const R: usize, const C: usize> Sub for Matrix<T, R, C> {
+    type Output = Matrix<T, R, C>;
+
+    fn sub(mut self, other: Matrix<T, R, C>) -> Matrix<T, R, C> {
+        for (a, b) in self.0.iter_mut().flatten().zip(other.0.iter().flatten()) {
+            *a = *a - *b;
+        }
+        self
+    }
+}
+
+// (R x K) * (K x C) = (R x C). the inner sizes have to agree, and
+// because both are the same K this is checked by the compiler
+impl<T: Num, const R: usize, const K: usize, const C: usize> Mul<Matrix<T, K, C>> for Matrix<T, R, K> {
+    type Output = Matrix<T, R, C>;
+
+    fn mul(self, other: Matrix<T, K, C>) -> Matrix<T, R, C> {
+        let mut out = Matrix::zero();
+        for r in 0..R {
+            for c in 0..C {
+                out.0[r][c] = self.row(r).dot(other.col(c));
+            }
+        }
+        out
+    }
+}
+
+impl<T: Num, const R: usize, const C: usize> Mul<Vector<T, C>> for Matrix<T, R, C> {
+    type Output = Vector<T, R>;
+
+    fn mul(self, v: Vector<T, C>) -> Vector<T, R> {
+        let mut out = Vector::zero();
+        for r in 0..R {
+            out.0[r] = self.row(r).dot(v);
+        }
+        out
+    }
+}
+
+impl<T: Num, const R: usize, const C: usize> Mul<T> for Matrix<T, R, C> {
+    type Output = Matrix<T, R, C>;
+
+    fn mul(self, k: T) -> Matrix<T, R, C> {
+        Matrix(self.0.map(|row| row.map(|x| x * k)))
+    }
+}
+
+impl<T: fmt::Display, const R: usize, const C: usize> fmt::Display for Matrix<T, R, C> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // right-align every column to its widest entry
+        let cells: Vec<Vec<String>> = self.0.iter()
+            .map(|row| row.iter().map(|x| x.to_string()).collect())
+            .collect();
+        let widths: Vec<usize> = (0..C)
+            .map(|c| cells.iter().map(|row| row[c].len()).max().unwrap_or(0))
+            .collect();
+        for (r, row) in cells.iter().enumerate() {
+            if r > 0 {
+                writeln!(f)?;
+            }
+            let padded: Vec<String> = row.iter().zip(&widths)
+                .map(|(x, &w)| format!("{:>1$}", x, w))
+                .collect();
+            write!(f, "[{}]", padded.join(" "))?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,650 @@
+// SEE: ch03-01-variables-and-mutability.html
+//
+// variables() shows `let`, `let mut`, shadowing and `const`. this
+// reads a Rust file and points out three things about them:
+//
+//   unused-mut     a `let mut` that is never changed. rustc warns
+//                  about this too, but only for code that compiles
+//   shadow-type    a `let` that shadows an earlier variable with a
+//                  different type, like `let spaces = spaces.len()`.
+//                  legal and sometimes the point, but worth a look
+//   inline-const   a private `const` used only once (or never)
+//
+// it only sees the syntax, not the types, so it guesses: a method
+// call on a variable counts as a change unless it's a method that
+// never needs `&mut self` (len, iter, clone, ...), anything passed
+// to a macro other than println! and friends counts as changed, and
+// a type is only compared when it's written down or easy to tell
+// from the value. when unsure it stays quiet.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use proc_macro2::{Span, TokenStream, TokenTree};
+use quote::ToTokens;
+use syn::punctuated::Punctuated;
+use syn::visit::{self, Visit};
+use syn::{
+    BinOp, Block, Expr, FnArg, Lit, Pat, Signature, Stmt, Token, Type, UnOp, Visibility,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    UnusedMut,
+    ShadowType,
+    InlineConst,
+}
+
+impl fmt::Display for Kind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Kind::UnusedMut => "unused-mut",
+            Kind::ShadowType => "shadow-type",
+            Kind::InlineConst => "inline-const",
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Finding {
+    pub line: usize,
+    // 1-based, in chars
+    pub column: usize,
+    pub kind: Kind,
+    pub message: String,
+}
+
+pub fn lint(source: &str) -> syn::Result<Vec<Finding>> {
+    let file = syn::parse_file(source)?;
+
+    let mut consts = ConstNames::default();
+    consts.visit_file(&file);
+
+    let mut linter = Linter {
+        consts: consts.0.into_iter().map(|name| (name, 0)).collect(),
+        ..Linter::default()
+    };
+    linter.visit_file(&file);
+    linter.report_consts();
+
+    let mut findings = linter.findings;
+    findings.sort_by_key(|f| (f.line, f.column));
+    Ok(findings)
+}
+
+// methods that only need &self or self, so calling one doesn't
+// need the variable to be `mut`
+const READ_ONLY: &[&str] = &[
+    "as_bytes", "as_ref", "as_str", "bytes", "chars", "clone", "cmp", "contains",
+    "contains_key", "count", "ends_with", "eq", "expect", "find", "first", "get",
+    "into_iter", "is_empty", "is_err", "is_none", "is_ok", "is_some", "iter", "keys",
+    "last", "len", "lines", "map", "max", "min", "ne", "parse", "partial_cmp", "pow",
+    "split", "split_whitespace", "starts_with", "to_lowercase", "to_owned", "to_string",
+    "to_uppercase", "to_vec", "trim", "trim_end", "trim_start", "unwrap", "unwrap_or",
+    "values",
+];
+
+// macros whose arguments are plain expressions that are only read
+const FORMAT_MACROS: &[&str] = &[
+    "assert", "assert_eq", "assert_ne", "debug_assert", "debug_assert_eq",
+    "debug_assert_ne", "eprint", "eprintln", "format", "panic", "print", "println",
+];
+
+fn line_col(span: Span) -> (usize, usize) {
+    let start = span.start();
+    (start.line, start.column + 1)
+}
+
+// first pass: the names of every const, so uses that come before the
+// declaration are counted too
+#[derive(Default)]
+struct ConstNames(HashSet<String>);
+
+impl<'ast> Visit<'ast> for ConstNames {
+    fn visit_item_const(&mut self, item: &'ast syn::ItemConst) {
+        self.0.insert(item.ident.to_string());
+        visit::visit_item_const(self, item);
+    }
+}
+
+struct Binding {
+    name: String,
+    span: Span,
+    mutable: bool,
+    mutated: bool,
+    // written down or guessed, None when we can't tell
+    ty: Option<String>,
+}
+
+struct Const {
+    name: String,
+    span: Span,
+    public: bool,
+}
+
+#[derive(Default)]
+struct Linter {
+    bindings: Vec<Binding>,
+    // indices into bindings, innermost scope last
+    scopes: Vec<Vec<usize>>,
+    consts: HashMap<String, usize>,
+    const_defs: Vec<Const>,
+    findings: Vec<Finding>,
+}
+
+impl Linter {
+    fn push(&mut self) {
+        self.scopes.push(Vec::new());
+    }
+
+    fn pop(&mut self) {
+        for i in self.scopes.pop().unwrap_or_default() {
+            let b = &self.bindings[i];
+            if b.mutable && !b.mutated && !b.name.starts_with('_') {
+                let msg = format!("`{}` is declared `mut` but never changed, drop the `mut`", b.name);
+                self.add(b.span, Kind::UnusedMut, msg);
+            }
+        }
+    }
+
+    fn add(&mut self, span: Span, kind: Kind, message: String) {
+        let (line, column) = line_col(span);
+        self.findings.push(Finding { line, column, kind, message });
+    }
+
+    fn lookup(&self, name: &str) -> Option<usize> {
+        // innermost first
+        self.scopes
+            .iter()
+            .rev()
+            .flat_map(|scope| scope.iter().rev())
+            .copied()
+            .find(|&i| self.bindings[i].name == name)
+    }
+
+    fn declare(&mut self, pat: &Pat, ty: Option<String>) {
+        // counts consts used in the pattern, like `MAX => ...`
+        self.visit_pat(pat);
+
+        let mut idents = Vec::new();
+        pat_idents(pat, &mut idents);
+        // a type can only be pinned to a plain `let x`
+        let ty = if matches!(pat, Pat::Ident(_)) { ty } else { None };
+        for ident in idents {
+            let name = ident.ident.to_string();
+            if self.consts.contains_key(&name) {
+                continue;
+            }
+            let span = ident.ident.span();
+            if let (Some(new), Some(old)) = (&ty, self.lookup(&name)) {
+                let old = &self.bindings[old];
+                if let Some(old_ty) = &old.ty {
+                    if !same_type(old_ty, new) {
+                        let msg = format!(
+                            "`{}` shadows the `{}` from line {} and changes its type from `{}` to `{}`",
+                            name, name, line_col(old.span).0, old_ty, new
+                        );
+                        self.add(span, Kind::ShadowType, msg);
+                    }
+                }
+            }
+            self.bindings.push(Binding {
+                name,
+                span,
+                mutable: ident.mutability.is_some(),
+                mutated: false,
+                ty: ty.clone(),
+            });
+            let i = self.bindings.len() - 1;
+            self.scopes.last_mut().unwrap().push(i);
+        }
+    }
+
+    // the variable a place expression like `x.a[3].b` belongs to
+    fn mark_mutated(&mut self, mut place: &Expr) {
+        loop {
+            place = match place {
+                Expr::Field(e) => &e.base,
+                Expr::Index(e) => &e.expr,
+                Expr::Paren(e) => &e.expr,
+                Expr::Unary(e) if matches!(e.op, UnOp::Deref(_)) => &e.expr,
+                Expr::Path(e) => {
+                    if let Some(ident) = e.path.get_ident() {
+                        self.mark_name(&ident.to_string());
+                    }
+                    return;
+                }
+                _ => return,
+            }
+        }
+    }
+
+    fn mark_name(&mut self, name: &str) {
+        if let Some(i) = self.lookup(name) {
+            self.bindings[i].mutated = true;
+        }
+    }
+
+    fn function(&mut self, sig: &Signature, body: &Block) {
+        // an inner fn can't see the outer fn's variables
+        let outer = std::mem::take(&mut self.scopes);
+        self.push();
+        for arg in &sig.inputs {
+            if let FnArg::Typed(arg) = arg {
+                self.visit_type(&arg.ty);
+                self.declare(&arg.pat, Some(type_name(&arg.ty)));
+            }
+        }
+        self.visit_block(body);
+        self.pop();
+        self.scopes = outer;
+    }
+
+    // a guess at the type of an expression, in rustc's notation.
+    // {integer} and {float} are literals whose type isn't fixed yet
+    fn guess(&self, e: &Expr) -> Option<String> {
+        let ty = match e {
+            Expr::Lit(e) => match &e.lit {
+                Lit::Int(i) if i.suffix().is_empty() => "{integer}".to_string(),
+                Lit::Int(i) => i.suffix().to_string(),
+                Lit::Float(f) if f.suffix().is_empty() => "{float}".to_string(),
+                Lit::Float(f) => f.suffix().to_string(),
+                Lit::Str(_) => "&str".to_string(),
+                Lit::Char(_) => "char".to_string(),
+                Lit::Bool(_) => "bool".to_string(),
+                _ => return None,
+            },
+            Expr::Cast(e) => type_name(&e.ty),
+            Expr::Paren(e) => return self.guess(&e.expr),
+            Expr::Path(e) => {
+                let i = self.lookup(&e.path.get_ident()?.to_string())?;
+                return self.bindings[i].ty.clone();
+            }
+            Expr::Reference(e) => {
+                let inner = self.guess(&e.expr)?;
+                let amp = if e.mutability.is_some() { "&mut " } else { "&" };
+                format!("{}{}", amp, inner)
+            }
+            Expr::Unary(e) => match e.op {
+                UnOp::Neg(_) | UnOp::Not(_) => return self.guess(&e.expr),
+                _ => return None,
+            },
+            Expr::Binary(e) => match e.op {
+                BinOp::Eq(_) | BinOp::Ne(_) | BinOp::Lt(_) | BinOp::Le(_) | BinOp::Gt(_)
+                | BinOp::Ge(_) | BinOp::And(_) | BinOp::Or(_) => "bool".to_string(),
+                BinOp::Add(_) | BinOp::Sub(_) | BinOp::Mul(_) | BinOp::Div(_) | BinOp::Rem(_) => {
+                    // a literal on one side takes the type of the other
+                    let (l, r) = (self.guess(&e.left), self.guess(&e.right));
+                    return match (l, r) {
+                        (Some(l), Some(r)) if l.starts_with('{') => Some(r),
+                        (Some(l), _) => Some(l),
+                        (None, r) => r,
+                    };
+                }
+                _ => return None,
+            },
+            Expr::Tuple(e) => {
+                let items: Option<Vec<String>> = e.elems.iter().map(|x| self.guess(x)).collect();
+                format!("({})", items?.join(", "))
+            }
+            // String::new(), Vec::with_capacity(3), ...
+            Expr::Call(e) => match &*e.func {
+                Expr::Path(p) if p.path.segments.len() == 2 => {
+                    let ty = &p.path.segments[0];
+                    let ctor = p.path.segments[1].ident.to_string();
+                    if !["new", "from", "with_capacity", "default"].contains(&ctor.as_str()) {
+                        return None;
+                    }
+                    ty.ident.to_string()
+                }
+                _ => return None,
+            },
+            Expr::MethodCall(e) => {
+                let method = e.method.to_string();
+                match method.as_str() {
+                    "len" | "count" | "capacity" => "usize".to_string(),
+                    "to_string" | "to_uppercase" | "to_lowercase" => "String".to_string(),
+                    "trim" | "trim_start" | "trim_end" | "as_str" => "&str".to_string(),
+                    "is_empty" | "contains" | "starts_with" | "ends_with" => "bool".to_string(),
+                    // "42".parse::<u32>().unwrap()
+                    "unwrap" | "expect" => match &*e.receiver {
+                        Expr::MethodCall(inner) if inner.method == "parse" => {
+                            match inner.turbofish.as_ref()?.args.first()? {
+                                syn::GenericArgument::Type(ty) => type_name(ty),
+                                _ => return None,
+                            }
+                        }
+                        _ => return None,
+                    },
+                    _ => return None,
+                }
+            }
+            Expr::Macro(e) if e.mac.path.is_ident("format") => "String".to_string(),
+            Expr::Macro(e) if e.mac.path.is_ident("vec") => "Vec".to_string(),
+            _ => return None,
+        };
+        Some(ty)
+    }
+
+    // a macro's tokens aren't parsed as code. for println! and friends
+    // they can be, anything else is scanned for names
+    fn visit_macro_tokens(&mut self, mac: &syn::Macro) {
+        let name = mac.path.segments.last().map(|s| s.ident.to_string()).unwrap_or_default();
+        if FORMAT_MACROS.contains(&name.as_str()) {
+            let parser = Punctuated::<Expr, Token![,]>::parse_terminated;
+            if let Ok(args) = mac.parse_body_with(parser) {
+                for arg in &args {
+                    // "{MAX}" in a format string is a use of MAX
+                    if let Expr::Lit(syn::ExprLit { lit: Lit::Str(s), .. }) = arg {
+                        for name in inline_args(&s.value()) {
+                            self.use_const(&name);
+                        }
+                    }
+                    self.visit_expr(arg);
+                }
+                return;
+            }
+        }
+        self.scan_tokens(mac.tokens.clone());
+    }
+
+    fn scan_tokens(&mut self, tokens: TokenStream) {
+        for tt in tokens {
+            match tt {
+                TokenTree::Ident(ident) => {
+                    let name = ident.to_string();
+                    self.use_const(&name);
+                    // could be anything, like write!(s, ...), so assume
+                    // the macro changes it
+                    self.mark_name(&name);
+                }
+                TokenTree::Group(g) => self.scan_tokens(g.stream()),
+                _ => {}
+            }
+        }
+    }
+
+    fn use_const(&mut self, name: &str) {
+        if let Some(n) = self.consts.get_mut(name) {
+            *n += 1;
+        }
+    }
+
+    fn report_consts(&mut self) {
+        let defs = std::mem::take(&mut self.const_defs);
+        for c in defs.iter().filter(|c| !c.public) {
+            let uses = self.consts[&c.name];
+            let msg = match uses {
+                0 => format!("const `{}` is never used", c.name),
+                1 => format!(
+                    "const `{}` is used only once, it could be written inline (or kept if the name explains the value)",
+                    c.name
+                ),
+                _ => continue,
+            };
+            self.add(c.span, Kind::InlineConst, msg);
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for Linter {
+    fn visit_item_fn(&mut self, item: &'ast syn::ItemFn) {
+        self.function(&item.sig, &item.block);
+    }
+
+    fn visit_impl_item_fn(&mut self, item: &'ast syn::ImplItemFn) {
+        self.function(&item.sig, &item.block);
+    }
+
+    fn visit_trait_item_fn(&mut self, item: &'ast syn::TraitItemFn) {
+        if let Some(body) = &item.default {
+            self.function(&item.sig, body);
+        }
+    }
+
+    fn visit_item_const(&mut self, item: &'ast syn::ItemConst) {
+        self.const_defs.push(Const {
+            name: item.ident.to_string(),
+            span: item.ident.span(),
+            public: !matches!(item.vis, Visibility::Inherited),
+        });
+        self.visit_type(&item.ty);
+        self.visit_expr(&item.expr);
+    }
+
+    fn visit_block(&mut self, block: &'ast Block) {
+        self.push();
+        for stmt in &block.stmts {
+            self.visit_stmt(stmt);
+        }
+        self.pop();
+    }
+
+    fn visit_stmt(&mut self, stmt: &'ast Stmt) {
+        match stmt {
+            Stmt::Local(local) => {
+                if let Some(init) = &local.init {
+                    self.visit_expr(&init.expr);
+                    if let Some((_, diverge)) = &init.diverge {
+                        self.visit_expr(diverge);
+                    }
+                    if has_ref_mut(&local.pat) {
+                        self.mark_mutated(&init.expr);
+                    }
+                }
+                let (pat, ty) = match &local.pat {
+                    Pat::Type(p) => {
+                        self.visit_type(&p.ty);
+                        (&*p.pat, Some(type_name(&p.ty)))
+                    }
+                    p => (p, local.init.as_ref().and_then(|i| self.guess(&i.expr))),
+                };
+                self.declare(pat, ty);
+            }
+            _ => visit::visit_stmt(self, stmt),
+        }
+    }
+
+    fn visit_expr(&mut self, e: &'ast Expr) {
+        match e {
+            // `if let` and `while let` bindings live in the body only
+            Expr::If(e) => {
+                self.push();
+                self.visit_expr(&e.cond);
+                self.visit_block(&e.then_branch);
+                self.pop();
+                if let Some((_, else_branch)) = &e.else_branch {
+                    self.visit_expr(else_branch);
+                }
+            }
+            Expr::While(e) => {
+                self.push();
+                self.visit_expr(&e.cond);
+                self.visit_block(&e.body);
+                self.pop();
+            }
+            Expr::Let(e) => {
+                self.visit_expr(&e.expr);
+                if has_ref_mut(&e.pat) {
+                    self.mark_mutated(&e.expr);
+                }
+                self.declare(&e.pat, None);
+            }
+            Expr::ForLoop(e) => {
+                self.visit_expr(&e.expr);
+                self.push();
+                self.declare(&e.pat, None);
+                self.visit_block(&e.body);
+                self.pop();
+            }
+            Expr::Match(e) => {
+                self.visit_expr(&e.expr);
+                for arm in &e.arms {
+                    self.push();
+                    if has_ref_mut(&arm.pat) {
+                        self.mark_mutated(&e.expr);
+                    }
+                    self.declare(&arm.pat, None);
+                    if let Some((_, guard)) = &arm.guard {
+                        self.visit_expr(guard);
+                    }
+                    self.visit_expr(&arm.body);
+                    self.pop();
+                }
+            }
+            Expr::Closure(e) => {
+                self.push();
+                for input in &e.inputs {
+                    match input {
+                        Pat::Type(p) => self.declare(&p.pat, Some(type_name(&p.ty))),
+                        p => self.declare(p, None),
+                    }
+                }
+                self.visit_expr(&e.body);
+                self.pop();
+            }
+            Expr::Assign(a) => {
+                self.mark_mutated(&a.left);
+                visit::visit_expr(self, e);
+            }
+            Expr::Binary(b) if is_assign_op(&b.op) => {
+                self.mark_mutated(&b.left);
+                visit::visit_expr(self, e);
+            }
+            Expr::Reference(r) if r.mutability.is_some() => {
+                self.mark_mutated(&r.expr);
+                visit::visit_expr(self, e);
+            }
+            Expr::MethodCall(m) => {
+                if !READ_ONLY.contains(&m.method.to_string().as_str()) {
+                    self.mark_mutated(&m.receiver);
+                }
+                visit::visit_expr(self, e);
+            }
+            // calling a closure that changes what it captured needs
+            // `let mut f = || ...`
+            Expr::Call(c) => {
+                if let Expr::Path(p) = &*c.func {
+                    if let Some(ident) = p.path.get_ident() {
+                        self.mark_name(&ident.to_string());
+                    }
+                }
+                visit::visit_expr(self, e);
+            }
+            _ => visit::visit_expr(self, e),
+        }
+    }
+
+    fn visit_expr_path(&mut self, e: &'ast syn::ExprPath) {
+        if let Some(ident) = e.path.get_ident() {
+            self.use_const(&ident.to_string());
+        }
+        visit::visit_expr_path(self, e);
+    }
+
+    fn visit_pat_ident(&mut self, p: &'ast syn::PatIdent) {
+        self.use_const(&p.ident.to_string());
+        visit::visit_pat_ident(self, p);
+    }
+
+    fn visit_macro(&mut self, mac: &'ast syn::Macro) {
+        self.visit_macro_tokens(mac);
+    }
+}
+
+fn is_assign_op(op: &BinOp) -> bool {
+    matches!(
+        op,
+        BinOp::AddAssign(_) | BinOp::SubAssign(_) | BinOp::MulAssign(_) | BinOp::DivAssign(_)
+            | BinOp::RemAssign(_) | BinOp::BitXorAssign(_) | BinOp::BitAndAssign(_)
+            | BinOp::BitOrAssign(_) | BinOp::ShlAssign(_) | BinOp::ShrAssign(_)
+    )
+}
+
+// the names a pattern binds
+fn pat_idents<'a>(pat: &'a Pat, out: &mut Vec<&'a syn::PatIdent>) {
+    match pat {
+        Pat::Ident(p) => {
+            out.push(p);
+            if let Some((_, sub)) = &p.subpat {
+                pat_idents(sub, out);
+            }
+        }
+        Pat::Tuple(p) => p.elems.iter().for_each(|p| pat_idents(p, out)),
+        Pat::TupleStruct(p) => p.elems.iter().for_each(|p| pat_idents(p, out)),
+        Pat::Struct(p) => p.fields.iter().for_each(|f| pat_idents(&f.pat, out)),
+        Pat::Slice(p) => p.elems.iter().for_each(|p| pat_idents(p, out)),
+        Pat::Reference(p) => pat_idents(&p.pat, out),
+        Pat::Paren(p) => pat_idents(&p.pat, out),
+        Pat::Type(p) => pat_idents(&p.pat, out),
+        // every alternative binds the same names
+        Pat::Or(p) => {
+            if let Some(first) = p.cases.first() {
+                pat_idents(first, out);
+            }
+        }
+        _ => {}
+    }
+}
+
+// `ref mut x` borrows part of the matched value mutably
+fn has_ref_mut(pat: &Pat) -> bool {
+    let mut idents = Vec::new();
+    pat_idents(pat, &mut idents);
+    idents.iter().any(|p| p.by_ref.is_some() && p.mutability.is_some())
+}
+
+// `Vec < i32 >` -> `Vec<i32>`
+fn type_name(ty: &Type) -> String {
+    let text = ty.to_token_stream().to_string();
+    let mut out = String::new();
+    let chars: Vec<char> = text.chars().collect();
+    let word = |c: Option<&char>| c.is_some_and(|c| c.is_alphanumeric() || *c == '_');
+    for (i, &c) in chars.iter().enumerate() {
+        let before = chars.get(i.wrapping_sub(1));
+        // keep the space in `&mut T`, `dyn Trait` and after a comma
+        let keep = (word(before) && word(chars.get(i + 1))) || before == Some(&',');
+        if c != ' ' || keep {
+            out.push(c);
+        }
+    }
+    out
+}
+
+const INTEGERS: &[&str] = &[
+    "i8", "i16", "i32", "i64", "i128", "isize", "u8", "u16", "u32", "u64", "u128", "usize",
+];
+
+// {integer} agrees with every integer type, and a bare `Vec` with
+// any `Vec<...>`
+fn same_type(a: &str, b: &str) -> bool {
+    let base = |t: &str| t.split('<').next().unwrap().to_string();
+    let fits = |lit: &str, t: &str| match lit {
+        "{integer}" => INTEGERS.contains(&t) || t == lit,
+        "{float}" => t == "f32" || t == "f64" || t == lit,
+        _ => false,
+    };
+    a == b
+        || fits(a, b)
+        || fits(b, a)
+        || ((!a.contains('<') || !b.contains('<')) && base(a) == base(b))
+}
+
+// the names in "{x} and {y:>5}", not "{}" or "{0}"
+fn inline_args(format: &str) -> Vec<String> {
+    let mut names = Vec::new();
+    let mut rest = format;
+    while let Some(open) = rest.find('{') {
+        rest = &rest[open + 1..];
+        if rest.starts_with('{') {
+            rest = &rest[1..];
+            continue;
+        }
+        let end = rest.find(['}', ':']).unwrap_or(rest.len());
+        let name = &rest[..end];
+        if name.starts_with(|c: char| c.is_alphabetic() || c == '_') {
+            names.push(name.to_string());
+        }
+    }
+    names
+}
@@ -0,0 +1,455 @@
+// "4".parse::<u32>() only understands plain digits, but the
+// compiler accepts a lot more: 1_000_000, 0xff, 0o77, 0b1010,
+// 5u8, 1e3, 2.5_f32 ... parse() here follows the same rules as
+// rustc's lexer, and like rustc it rejects literals that don't
+// fit their type instead of silently wrapping them.
+//
+// A leading `-` is accepted too. In Rust it's really the unary
+// minus applied to the literal, but that's what people mean when
+// they write -128i8.
+
+use std::error;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Type {
+    I8, I16, I32, I64, I128, Isize,
+    U8, U16, U32, U64, U128, Usize,
+    F32, F64,
+}
+
+impl Type {
+    // the 12 integer types, in the order data_types() declares them
+    pub const INTEGERS: [Type; 12] = [
+        Type::I8, Type::I16, Type::I32, Type::I64, Type::I128, Type::Isize,
+        Type::U8, Type::U16, Type::U32, Type::U64, Type::U128, Type::Usize,
+    ];
+
+    pub fn from_suffix(suffix: &str) -> Option<Type> {
+        let ty = match suffix {
+            "i8" => Type::I8,
+            "i16" => Type::I16,
+            "i32" => Type::I32,
+            "i64" => Type::I64,
+            "i128" => Type::I128,
+            "isize" => Type::Isize,
+            "u8" => Type::U8,
+            "u16" => Type::U16,
+            "u32" => Type::U32,
+            "u64" => Type::U64,
+            "u128" => Type::U128,
+            "usize" => Type::Usize,
+            "f32" => Type::F32,
+            "f64" => Type::F64,
+            _ => return None,
+        };
+        Some(ty)
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Type::I8 => "i8",
+            Type::I16 => "i16",
+            Type::I32 => "i32",
+            Type::I64 => "i64",
+            Type::I128 => "i128",
+            Type::Isize => "isize",
+            Type::U8 => "u8",
+            Type::U16 => "u16",
+            Type::U32 => "u32",
+            Type::U64 => "u64",
+            Type::U128 => "u128",
+            Type::Usize => "usize",
+            Type::F32 => "f32",
+            Type::F64 => "f64",
+        }
+    }
+
+    pub fn is_float(self) -> bool {
+        self == Type::F32 || self == Type::F64
+    }
+
+    pub fn is_signed(self) -> bool {
+        matches!(
+            self,
+            Type::I8 | Type::I16 | Type::I32 | Type::I64 | Type::I128 | Type::Isize
+        )
+    }
+
+    // isize and usize are as wide as a pointer on this machine
+    pub fn bits(self) -> u32 {
+        match self {
+            Type::I8 | Type::U8 => 8,
+            Type::I16 | Type::U16 => 16,
+            Type::I32 | Type::U32 | Type::F32 => 32,
+            Type::I64 | Type::U64 | Type::F64 => 64,
+            Type::I128 | Type::U128 => 128,
+            Type::Isize | Type::Usize => usize::BITS,
+        }
+    }
+
+    // can an integer type hold -magnitude (negative) or +magnitude?
+    fn holds(self, negative: bool, magnitude: u128) -> bool {
+        let bits = self.bits();
+        if self.is_signed() {
+            let max = (1u128 << (bits - 1)) - 1;
+            if negative { magnitude <= max + 1 } else { magnitude <= max }
+        } else if negative {
+            magnitude == 0
+        } else {
+            bits == 128 || magnitude < 1u128 << bits
+        }
+    }
+}
+
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+// a parsed literal, already converted to its type
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Literal {
+    I8(i8), I16(i16), I32(i32), I64(i64), I128(i128), Isize(isize),
+    U8(u8), U16(u16), U32(u32), U64(u64), U128(u128), Usize(usize),
+    F32(f32), F64(f64),
+}
+
+impl Literal {
+    pub fn ty(&self) -> Type {
+        match self {
+            Literal::I8(_) => Type::I8,
+            Literal::I16(_) => Type::I16,
+            Literal::I32(_) => Type::I32,
+            Literal::I64(_) => Type::I64,
+            Literal::I128(_) => Type::I128,
+            Literal::Isize(_) => Type::Isize,
+            Literal::U8(_) => Type::U8,
+            Literal::U16(_) => Type::U16,
+            Literal::U32(_) => Type::U32,
+            Literal::U64(_) => Type::U64,
+            Literal::U128(_) => Type::U128,
+            Literal::Usize(_) => Type::Usize,
+            Literal::F32(_) => Type::F32,
+            Literal::F64(_) => Type::F64,
+        }
+    }
+
+    // whether the value could be stored, unchanged, in each of the
+    // 12 integer types. a float only fits if it's a whole number
+    pub fn fits(&self) -> Vec<(Type, bool)> {
+        let wide = self.sign_magnitude();
+        Type::INTEGERS
+            .iter()
+            .map(|&ty| (ty, wide.is_some_and(|(neg, mag)| ty.holds(neg, mag))))
+            .collect()
+    }
+
+    fn sign_magnitude(&self) -> Option<(bool, u128)> {
+        fn signed(v: i128) -> Option<(bool, u128)> {
+            Some((v < 0, v.unsigned_abs()))
+        }
+        fn float(v: f64) -> Option<(bool, u128)> {
+            // 2^128 is exact in f64, anything at or above it won't fit
+            if !v.is_finite() || v.fract() != 0.0 || v.abs() >= 2f64.powi(128) {
+                return None;
+            }
+            Some((v.is_sign_negative() && v != 0.0, v.abs() as u128))
+        }
+        match *self {
+            Literal::I8(v) => signed(v.into()),
+            Literal::I16(v) => signed(v.into()),
+            Literal::I32(v) => signed(v.into()),
+            Literal::I64(v) => signed(v.into()),
+            Literal::I128(v) => signed(v),
+            Literal::Isize(v) => signed(v as i128),
+            Literal::U8(v) => Some((false, v.into())),
+            Literal::U16(v) => Some((false, v.into())),
+            Literal::U32(v) => Some((false, v.into())),
+            Literal::U64(v) => Some((false, v.into())),
+            Literal::U128(v) => Some((false, v)),
+            Literal::Usize(v) => Some((false, v as u128)),
+            Literal::F32(v) => float(v.into()),
+            Literal::F64(v) => float(v),
+        }
+    }
+}
+
+impl fmt::Display for Literal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Literal::I8(v) => write!(f, "{}", v),
+            Literal::I16(v) => write!(f, "{}", v),
+            Literal::I32(v) => write!(f, "{}", v),
+            Literal::I64(v) => write!(f, "{}", v),
+            Literal::I128(v) => write!(f, "{}", v),
+            Literal::Isize(v) => write!(f, "{}", v),
+            Literal::U8(v) => write!(f, "{}", v),
+            Literal::U16(v) => write!(f, "{}", v),
+            Literal::U32(v) => write!(f, "{}", v),
+            Literal::U64(v) => write!(f, "{}", v),
+            Literal::U128(v) => write!(f, "{}", v),
+            Literal::Usize(v) => write!(f, "{}", v),
+            Literal::F32(v) => write!(f, "{:?}", v),
+            Literal::F64(v) => write!(f, "{:?}", v),
+        }?;
+        write!(f, "{}", self.ty())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ErrorKind {
+    Empty,
+    // the text doesn't start like a number at all, e.g. "_1" or "x"
+    NotANumber,
+    // "0x" or "0b_" with nothing after the prefix
+    NoDigits,
+    InvalidDigit { digit: char, base: u32 },
+    InvalidSuffix(String),
+    // "1.0u8", an integer suffix on a float
+    IntSuffixOnFloat(Type),
+    // "0b1f32" or "0o1.5", only decimal floats exist
+    FloatWithBase(u32),
+    // "1.e3" or "1._5": rustc reads these as field accesses
+    DigitExpectedAfterDot,
+    EmptyExponent,
+    // bigger than u128, the lexer itself gives up
+    TooLarge,
+    // the value doesn't fit its type. `fits` lists the types it would fit
+    OutOfRange { ty: Type, fits: Vec<Type> },
+    // -1u8
+    NegativeUnsigned(Type),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Error {
+    pub kind: ErrorKind,
+    // byte offset into the input where the problem starts
+    pub pos: usize,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.kind {
+            ErrorKind::Empty => write!(f, "empty literal")?,
+            ErrorKind::NotANumber => write!(f, "expected a digit")?,
+            ErrorKind::NoDigits => write!(f, "no valid digits found for number")?,
+            ErrorKind::InvalidDigit { digit, base } => {
+                write!(f, "invalid digit `{}` for a base {} literal", digit, base)?
+            }
+            ErrorKind::InvalidSuffix(s) => write!(f, "invalid suffix `{}` for number literal", s)?,
+            ErrorKind::IntSuffixOnFloat(ty) => write!(f, "invalid suffix `{}` for float literal", ty)?,
+            ErrorKind::FloatWithBase(base) => {
+                let name = match base {
+                    2 => "binary",
+                    8 => "octal",
+                    _ => "hexadecimal",
+                };
+                write!(f, "{} float literal is not supported", name)?
+            }
+            ErrorKind::DigitExpectedAfterDot => write!(f, "expected a digit after `.`")?,
+            ErrorKind::EmptyExponent => write!(f, "expected at least one digit in exponent")?,
+            ErrorKind::TooLarge => write!(f, "integer literal is too large")?,
+            ErrorKind::OutOfRange { ty, fits } => {
+                write!(f, "literal out of range for `{}`", ty)?;
+                if !fits.is_empty() {
+                    let names: Vec<&str> = fits.iter().map(|t| t.name()).collect();
+                    write!(f, ", it would fit in {}", names.join(", "))?;
+                }
+            }
+            ErrorKind::NegativeUnsigned(ty) => {
+                write!(f, "cannot apply unary operator `-` to type `{}`", ty)?
+            }
+        }
+        write!(f, " (at byte {})", self.pos)
+    }
+}
+
+impl error::Error for Error {}
+
+fn err<T>(kind: ErrorKind, pos: usize) -> Result<T, Error> {
+    Err(Error { kind, pos })
+}
+
+// parses one Rust integer or float literal. unsuffixed integers
+// are i32 and unsuffixed floats are f64, same as rustc's fallback
+pub fn parse(src: &str) -> Result<Literal, Error> {
+    if src.is_empty() {
+        return err(ErrorKind::Empty, 0);
+    }
+    let bytes = src.as_bytes();
+    let negative = bytes[0] == b'-';
+    let mut pos = if negative { 1 } else { 0 };
+
+    if pos == bytes.len() || !bytes[pos].is_ascii_digit() {
+        return err(ErrorKind::NotANumber, pos);
+    }
+
+    // only lowercase prefixes, 0X1 is `0` with the suffix `X1`
+    let base = match src.get(pos..pos + 2) {
+        Some("0x") => 16,
+        Some("0o") => 8,
+        Some("0b") => 2,
+        _ => 10,
+    };
+    if base != 10 {
+        pos += 2;
+    }
+
+    // integer part. binary and octal scan all decimal digits so
+    // that 0b102 is "invalid digit" rather than "invalid suffix 2"
+    let digits_start = pos;
+    let scan_hex = base == 16;
+    while pos < bytes.len() {
+        let b = bytes[pos];
+        let is_digit = if scan_hex { b.is_ascii_hexdigit() } else { b.is_ascii_digit() };
+        if !is_digit && b != b'_' {
+            break;
+        }
+        pos += 1;
+    }
+    let int_digits = &src[digits_start..pos];
+
+    let mut is_float = false;
+    let mut float_end = pos;
+    if base == 10 && pos < bytes.len() && bytes[pos] == b'.' {
+        let next = bytes.get(pos + 1).cloned();
+        match next {
+            Some(b) if b.is_ascii_digit() => {
+                pos += 1;
+                while pos < bytes.len() && (bytes[pos].is_ascii_digit() || bytes[pos] == b'_') {
+                    pos += 1;
+                }
+            }
+            // "1." on its own is a valid f64
+            None => pos += 1,
+            Some(_) => return err(ErrorKind::DigitExpectedAfterDot, pos + 1),
+        }
+        is_float = true;
+        float_end = pos;
+    }
+    if base == 10 && pos < bytes.len() && (bytes[pos] == b'e' || bytes[pos] == b'E') {
+        pos += 1;
+        if pos < bytes.len() && (bytes[pos] == b'+' || bytes[pos] == b'-') {
+            pos += 1;
+        }
+        let exp_start = pos;
+        while pos < bytes.len() && (bytes[pos].is_ascii_digit() || bytes[pos] == b'_') {
+            pos += 1;
+        }
+        if !src[exp_start..pos].bytes().any(|b| b.is_ascii_digit()) {
+            return err(ErrorKind::EmptyExponent, exp_start);
+        }
+        is_float = true;
+        float_end = pos;
+    }
+    if base != 10 && pos < bytes.len() && bytes[pos] == b'.' {
+        return err(ErrorKind::FloatWithBase(base), pos);
+    }
+
+    let suffix_start = pos;
+    let suffix = &src[suffix_start..];
+    let ty = if suffix.is_empty() {
+        None
+    } else {
+        match Type::from_suffix(suffix) {
+            Some(ty) => Some(ty),
+            None => return err(ErrorKind::InvalidSuffix(suffix.to_string()), suffix_start),
+        }
+    };
+
+    if is_float || ty.is_some_and(Type::is_float) {
+        if base != 10 {
+            return err(ErrorKind::FloatWithBase(base), suffix_start);
+        }
+        let ty = ty.unwrap_or(Type::F64);
+        if !ty.is_float() {
+            return err(ErrorKind::IntSuffixOnFloat(ty), suffix_start);
+        }
+        let end = if is_float { float_end } else { suffix_start };
+        let text: String = src[..end].chars().filter(|&c| c != '_').collect();
+        return parse_float(&text, ty, digits_start);
+    }
+
+    // a plain integer from here on
+    let mut magnitude: u128 = 0;
+    let mut any = false;
+    for (i, c) in int_digits.char_indices() {
+        if c == '_' {
+            continue;
+        }
+        let d = c.to_digit(16).unwrap();
+        if d >= base {
+            return err(ErrorKind::InvalidDigit { digit: c, base }, digits_start + i);
+        }
+        any = true;
+        magnitude = match magnitude
+            .checked_mul(base.into())
+            .and_then(|m| m.checked_add(d.into()))
+        {
+            Some(m) => m,
+            None => return err(ErrorKind::TooLarge, digits_start),
+        };
+    }
+    if !any {
+        return err(ErrorKind::NoDigits, digits_start);
+    }
+
+    let ty = ty.unwrap_or(Type::I32);
+    if negative && !ty.is_signed() {
+        return err(ErrorKind::NegativeUnsigned(ty), 0);
+    }
+    if !ty.holds(negative, magnitude) {
+        let fits = Type::INTEGERS
+            .iter()
+            .cloned()
+            .filter(|t| t.holds(negative, magnitude))
+            .collect();
+        return err(ErrorKind::OutOfRange { ty, fits }, 0);
+    }
+    Ok(to_int(ty, negative, magnitude))
+}
+
+fn parse_float(text: &str, ty: Type, pos: usize) -> Result<Literal, Error> {
+    // the grammar was checked above, so std's parser won't fail,
+    // and it rounds to nearest like rustc does
+    if ty == Type::F32 {
+        let v: f32 = text.parse().unwrap();
+        if v.is_infinite() {
+            let fits = if text.parse::<f64>().unwrap().is_finite() { vec![Type::F64] } else { vec![] };
+            return err(ErrorKind::OutOfRange { ty, fits }, pos);
+        }
+        Ok(Literal::F32(v))
+    } else {
+        let v: f64 = text.parse().unwrap();
+        if v.is_infinite() {
+            return err(ErrorKind::OutOfRange { ty, fits: vec![] }, pos);
+        }
+        Ok(Literal::F64(v))
+    }
+}
+
+// only called once the value is known to fit, so the casts are exact
+fn to_int(ty: Type, negative: bool, magnitude: u128) -> Literal {
+    let signed = if negative {
+        (magnitude as i128).wrapping_neg()
+    } else {
+        magnitude as i128
+    };
+    match ty {
+        Type::I8 => Literal::I8(signed as i8),
+        Type::I16 => Literal::I16(signed as i16),
+        Type::I32 => Literal::I32(signed as i32),
+        Type::I64 => Literal::I64(signed as i64),
+        Type::I128 => Literal::I128(signed),
+        Type::Isize => Literal::Isize(signed as isize),
+        Type::U8 => Literal::U8(magnitude as u8),
+        Type::U16 => Literal::U16(magnitude as u16),
+        Type::U32 => Literal::U32(magnitude as u32),
+        Type::U64 => Literal::U64(magnitude as u64),
+        Type::U128 => Literal::U128(magnitude),
+        Type::Usize => Literal::Usize(magnitude as usize),
+        Type::F32 | Type::F64 => unreachable!("not an integer type"),
+    }
+}
@@ -2,7 +2,35 @@
 // which means that it must know the types of all 
 // variables at compile time
 
+// the lessons spell some things out the long way on purpose:
+// `a = a - 1`, `6;` as a statement, literals passed to println!
+#![allow(clippy::assign_op_pattern, clippy::no_effect, clippy::print_literal)]
+
+use std::env;
+use std::fs;
+use std::io::{self, BufRead, Write};
+use std::process;
+use std::time::Instant;
+
+use common_programming_concepts::bits::{self, Bits, CastKind, Mark};
+use common_programming_concepts::fib::{self, Algorithm};
+use common_programming_concepts::float::{self, Class, Float};
+use common_programming_concepts::interp::{ast, Session};
+use common_programming_concepts::lint;
+use common_programming_concepts::literal::{self, Type};
+use common_programming_concepts::overflow::{self, Op};
+use common_programming_concepts::song::{self, Numbers, Song};
+use common_programming_concepts::unicode;
+use common_programming_concepts::units::{self, Dimension, Format, Precision};
+
 fn main() {
+    // with no arguments, run the lessons. otherwise run one of the tools
+    let args: Vec<String> = env::args().skip(1).collect();
+    if !args.is_empty() {
+        run_tool(&args);
+        return;
+    }
+
     println!("\nvariables()");
     variables();
 
@@ -16,6 +44,546 @@ fn main() {
     control_flow();
 }
 
+fn run_tool(args: &[String]) {
+    match (args[0].as_str(), &args[1..]) {
+        ("literal", [text]) => print_literal(text),
+        ("float", [text]) => print_float(text),
+        ("float", [flag, ty, bits]) if flag == "--bits" => print_float_bits(ty, bits),
+        ("bits", [text]) => print_bits(text, None),
+        ("bits", [text, kw, ty]) if kw == "as" => print_bits(text, Some(ty)),
+        ("unicode", [text]) => print_unicode(text),
+        ("overflow", [op, a]) => print_overflow(op, a, None),
+        ("overflow", [op, a, b]) => print_overflow(op, a, Some(b)),
+        ("fib", [n]) => print_fib(n, false),
+        ("fib", [n, flag]) if flag == "--print" => print_fib(n, true),
+        ("song", [name]) => print_song(name, None),
+        ("song", [name, flag, n]) if flag == "--verse" => print_song(name, Some(n)),
+        ("lint", files) if !files.is_empty() => print_lint(files),
+        ("repl", []) => repl(),
+        ("convert", [flag]) if flag == "--list" => print_units(),
+        ("convert", rest) if !rest.is_empty() => print_conversion(rest),
+        _ => usage(),
+    }
+}
+
+fn usage() -> ! {
+    eprintln!("usage: common_programming_concepts [TOOL ARGS...]");
+    eprintln!();
+    eprintln!("tools:");
+    eprintln!("  literal TEXT    parse a Rust number literal like 0xff_u8 or 1e3");
+    eprintln!("  float NUMBER    show how NUMBER is stored as f32 and f64. NUMBER can");
+    eprintln!("                  be a float literal, inf, -inf or nan");
+    eprintln!("  float --bits f32|f64 HEX");
+    eprintln!("                  the same for raw bits, e.g. a NaN with a payload");
+    eprintln!("  bits INT [as TYPE]");
+    eprintln!("                  the bits of an integer literal in binary, octal and hex,");
+    eprintln!("                  its bytes, and what `as` does to it. with `as TYPE`, the");
+    eprintln!("                  cast to TYPE bit by bit");
+    eprintln!("  unicode TEXT    break TEXT into graphemes, chars and UTF-8 bytes");
+    eprintln!("  overflow OP A [B]");
+    eprintln!("                  checked, wrapping, saturating and overflowing OP for every");
+    eprintln!("                  integer type. OP is add sub mul div rem neg shl shr pow");
+    eprintln!("  convert QUERY [--decimals N | --sig N] [--round MODE]");
+    eprintln!("                  convert units, QUERY is like \"72 F to C\" or \"5 km in mi\".");
+    eprintln!("                  MODE is half-away (default), half-even, down, floor, ceil.");
+    eprintln!("                  --list shows the units");
+    eprintln!("  fib N [--print]  the Nth Fibonacci number with each algorithm, how long");
+    eprintln!("                  each took and whether they agree. --print shows every digit");
+    eprintln!("  song SONG [--verse N]");
+    eprintln!("                  the lyrics of a cumulative song: twelve-days, old-lady,");
+    eprintln!("                  jack, or the path of a .song file");
+    eprintln!("  lint FILE...    point out `let mut`s that are never changed, shadowing that");
+    eprintln!("                  changes a variable's type, and consts used only once");
+    eprintln!("  repl            type in i32/bool code from the lessons and see its value");
+    eprintln!("                  and type. :help inside for more");
+    process::exit(2);
+}
+
+fn print_literal(text: &str) {
+    let lit = literal::parse(text).unwrap_or_else(|e| fail(text, e));
+    println!("{} is {}, the type is {}", text, lit, lit.ty());
+    for (ty, fits) in lit.fits() {
+        println!("  {:<5} {}", ty.name(), if fits { "fits" } else { "-" });
+    }
+}
+
+fn print_float(text: &str) {
+    let special = match text {
+        "inf" | "+inf" => Some((f32::INFINITY, f64::INFINITY)),
+        "-inf" => Some((f32::NEG_INFINITY, f64::NEG_INFINITY)),
+        "nan" | "NaN" => Some((f32::NAN, f64::NAN)),
+        _ => None,
+    };
+    if let Some((single, double)) = special {
+        inspect_float(Float::from_f32(single));
+        inspect_float(Float::from_f64(double));
+        return;
+    }
+
+    // a suffix picks the type, otherwise show both
+    let lit = literal::parse(text).unwrap_or_else(|e| fail(text, e));
+    match lit {
+        literal::Literal::F32(v) => return inspect_float(Float::from_f32(v)),
+        literal::Literal::F64(v) if text.ends_with("f64") => {
+            return inspect_float(Float::from_f64(v))
+        }
+        _ => {}
+    }
+    let r = match float::rounding(text) {
+        Ok(r) => r,
+        // too big for f32 but maybe not for f64
+        Err(e) => match lit {
+            literal::Literal::F64(v) => {
+                println!("as f32: {}\n", e);
+                return inspect_float(Float::from_f64(v));
+            }
+            _ => fail(text, e),
+        },
+    };
+    inspect_float(r.f32);
+    inspect_float(r.f64);
+
+    println!("{} as f32 vs f64", text);
+    println!("  f32 keeps {} bits of mantissa (~7 digits), f64 keeps {} (~16)",
+        r.f32.mantissa_bits() + 1, r.f64.mantissa_bits() + 1);
+    if r.same {
+        println!("  both store exactly the same value, nothing was rounded away");
+    } else {
+        println!("  the stored values differ by {:.3} f32 ULPs, so `{}f32 as f64 == {}f64` is false",
+            r.apart_in_f32_ulps, text, text);
+        let (single, double) = (r.f32.to_string(), r.f64.to_string());
+        if single == double {
+            println!("  they both print as {} because {{}} prints the shortest", double);
+            println!("  text that reads back as the same bits, not the stored value");
+        } else {
+            println!("  the f32 prints as {} and the f64 as {}: {{}} prints the", single, double);
+            println!("  shortest text that reads back as the same bits of each type");
+        }
+    }
+}
+
+fn print_float_bits(ty: &str, bits: &str) {
+    let ty = Type::from_suffix(ty).filter(|t| t.is_float()).unwrap_or_else(|| usage());
+    let hex: String = bits.trim_start_matches("0x").chars().filter(|&c| c != '_').collect();
+    let f = u64::from_str_radix(&hex, 16).ok()
+        .and_then(|b| Float::from_bits(ty, b))
+        .unwrap_or_else(|| {
+            eprintln!("error: {} is not a {} bit pattern", bits, ty);
+            process::exit(1);
+        });
+    inspect_float(f);
+}
+
+fn inspect_float(f: Float) {
+    println!("{} {}", f.ty, f);
+    println!("  bits      {}", f.bit_string());
+    println!("  sign      {}", if f.sign() { "1, negative" } else { "0, positive" });
+    let e = f.biased_exponent();
+    match f.class() {
+        Class::Normal => {
+            println!("  exponent  {} - bias {} = {}", e, f.bias(), e as i32 - f.bias());
+            println!("  mantissa  {:#x}, value is 1.mantissa x 2^exponent", f.mantissa());
+        }
+        Class::Zero | Class::Subnormal => {
+            println!("  exponent  {} (all zeros), read as 1 - bias = {}", e, 1 - f.bias());
+            println!("  mantissa  {:#x}, value is 0.mantissa x 2^exponent", f.mantissa());
+        }
+        Class::Infinite | Class::Nan { .. } => {
+            println!("  exponent  {} (all ones)", e);
+            println!("  mantissa  {:#x}", f.mantissa());
+        }
+    }
+    println!("  class     {}", f.class());
+    if let Some(exact) = f.exact() {
+        println!("  stored    {}", exact);
+    }
+    for (name, n) in [("next down", f.next_down()), ("next up  ", f.next_up())] {
+        match (n.exact(), f.gap(n)) {
+            (Some(exact), Some(gap)) => println!("  {} {}\n            1 ULP = {}", name, exact, gap),
+            _ => println!("  {} {}", name, n),
+        }
+    }
+    println!();
+}
+
+fn fail(text: &str, e: literal::Error) -> ! {
+    eprintln!("{}", text);
+    eprintln!("{:>1$}", "^", e.pos + 1);
+    eprintln!("error: {}", e);
+    process::exit(1);
+}
+
+fn print_bits(text: &str, to: Option<&str>) {
+    let lit = literal::parse(text).unwrap_or_else(|e| fail(text, e));
+    let b = Bits::new(&lit).unwrap_or_else(|| {
+        eprintln!("error: {} is a float, `as` converts floats by value, not by bits", lit);
+        process::exit(1);
+    });
+    if let Some(to) = to {
+        let to = Type::from_suffix(to).filter(|t| !t.is_float()).unwrap_or_else(|| usage());
+        print_cast(b, to);
+        return;
+    }
+
+    println!("{} ({} bits)", b, b.width());
+    println!("  binary  0b{}", b.binary());
+    println!("  octal   0o{}", b.octal());
+    println!("  hex     0x{}", b.hex());
+    let hex = |bytes: Vec<u8>| bytes.iter().map(|x| format!("{:02x}", x)).collect::<Vec<_>>().join(" ");
+    let native = if cfg!(target_endian = "little") { "little" } else { "big" };
+    println!("  bytes   big-endian     {}   to_be_bytes()", hex(b.be_bytes()));
+    println!("          little-endian  {}   to_le_bytes(), {}-endian is how this machine stores it",
+        hex(b.le_bytes()), native);
+
+    if let Some([magnitude, inverted, plus_one]) = b.twos_complement() {
+        println!();
+        println!("two's complement, from the magnitude:");
+        println!("  {:<10} {}", magnitude.raw(), magnitude.binary());
+        println!("  {:<10} {}", "invert", inverted.binary());
+        println!("  {:<10} {}", "add 1", plus_one.binary());
+        let top = 1u128 << (b.width() - 1);
+        println!("  the top bit is worth -{}, so -{} + {} = {}",
+            top, top, b.raw() - top, lit);
+    }
+
+    println!();
+    println!("{} as ...", b);
+    let casts: Vec<_> = Type::INTEGERS.iter().filter(|&&t| t != b.ty).map(|&t| b.cast(t)).collect();
+    let w = casts.iter().map(|c| c.to.to_string().len()).max().unwrap_or(0);
+    for cast in &casts {
+        let note = if cast.same_value() { "" } else { ", value changed" };
+        println!("  {:<5} {:<w$}  {}{}", cast.to.ty.name(), cast.to.to_string(), cast.kind, note, w = w);
+    }
+}
+
+// the two rows of bits lined up on the right, with a row under
+// them marking the bits the cast dropped or added
+fn print_cast(b: Bits, to: Type) {
+    let cast = b.cast(to);
+    let marks: String = cast.marks().iter()
+        .map(|m| match m {
+            Mark::Kept => ' ',
+            Mark::Dropped => 'x',
+            Mark::SignCopy => 's',
+            Mark::ZeroFill => '0',
+        })
+        .collect();
+    let marks = bits::group(&marks, 4).replace('_', " ");
+    let w = marks.len();
+
+    println!("{} as {}: {}", b, to, cast.kind);
+    println!("  {:<5} {:>w$}  {}", b.ty.name(), cast.from.binary(), cast.from, w = w);
+    println!("  {:<5} {:>w$}  {}", to.name(), cast.to.binary(), cast.to, w = w);
+    if !marks.trim().is_empty() {
+        println!("        {}", marks.trim_end());
+    }
+    match cast.kind {
+        CastKind::Reinterpret => println!("  same bits, read as {}", to),
+        CastKind::Truncate => println!("  x: dropped, only the low {} bits are kept", to.bits()),
+        CastKind::SignExtend => {
+            println!("  s: copies of the sign bit ({}), so a signed value keeps its sign",
+                if b.sign_bit() { 1 } else { 0 })
+        }
+        CastKind::ZeroExtend => println!("  0: filled with zeros, {} is unsigned", b.ty),
+    }
+    if cast.same_value() {
+        println!("  the value is the same");
+    } else {
+        println!("  the value changed, {}::try_from() would have returned an error", to);
+    }
+}
+
+fn print_unicode(text: &str) {
+    let report = unicode::inspect(text);
+    println!("{:?}\n", text);
+    println!("grapheme  width  char     utf-8        category");
+    for g in &report.graphemes {
+        // control characters would mess up the table
+        let shown = if g.scalars.iter().any(|s| s.width.is_none()) {
+            g.text.escape_debug().to_string()
+        } else {
+            g.text.to_string()
+        };
+        // pad by display width, not by bytes or chars
+        let pad = 9usize.saturating_sub(unicode_width::UnicodeWidthStr::width(shown.as_str()));
+        for (i, s) in g.scalars.iter().enumerate() {
+            let bytes: Vec<String> = s.utf8().iter().map(|b| format!("{:02x}", b)).collect();
+            let category = format!("{} {}", s.category.abbreviation(), unicode::category_name(s.category));
+            if i == 0 {
+                print!("{}{} {:>5}  ", shown, " ".repeat(pad), g.width);
+            } else {
+                print!("{:<9} {:>5}  ", "", "");
+            }
+            println!("{:<8} {:<12} {}", s.code_point(), bytes.join(" "), category);
+        }
+    }
+
+    println!();
+    println!("graphemes      {}", report.graphemes.len());
+    println!("chars          {}  (text.chars().count())", report.scalars());
+    println!("UTF-8 bytes    {}  (text.len(), 1 to 4 per char)", report.utf8_bytes());
+    println!("as Vec<char>   {}  ({} x size_of::<char>() = {})",
+        report.char_bytes(), report.scalars(), std::mem::size_of::<char>());
+    println!("display width  {}", report.width());
+}
+
+fn print_overflow(op: &str, a: &str, b: Option<&str>) {
+    let op: Op = op.parse().unwrap_or_else(|e| {
+        eprintln!("error: {}", e);
+        process::exit(1);
+    });
+    if op.is_unary() != b.is_none() {
+        usage();
+    }
+
+    match b {
+        Some(b) => println!("{} with a = {}, b = {}\n", op.expr(), a, b),
+        None => println!("{} with a = {}\n", op.expr(), a),
+    }
+    let rows = overflow::explore(op, a, b);
+    let header = ["type", "checked", "wrapping", "saturating", "overflowing", op.expr()];
+    let mut widths: Vec<usize> = header.iter().map(|h| h.len()).collect();
+    for r in rows.iter().flatten() {
+        let cells = [r.ty.name(), &r.checked, &r.wrapping, &r.saturating, &r.overflowing];
+        for (w, c) in widths.iter_mut().zip(cells.iter()) {
+            *w = (*w).max(c.len());
+        }
+    }
+
+    let line = |cells: [&str; 6]| {
+        let padded: Vec<String> = cells.iter().zip(&widths)
+            .map(|(c, &w)| format!("{:<1$}", c, w))
+            .collect();
+        println!("{}", padded.join("  ").trim_end());
+    };
+    line(header);
+    for row in &rows {
+        match row {
+            Ok(r) => line([r.ty.name(), &r.checked, &r.wrapping, &r.saturating,
+                &r.overflowing, &r.panic.to_string()]),
+            Err(e) => println!("{:<1$}  {2}", "", widths[0], e),
+        }
+    }
+}
+
+fn print_conversion(args: &[String]) {
+    let mut format = Format::default();
+    let mut words = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().unwrap_or_else(|| usage());
+        match arg.as_str() {
+            "--decimals" => format.precision = Precision::Decimals(digits(arg, value(), 0)),
+            "--sig" => format.precision = Precision::Significant(digits(arg, value(), 1)),
+            "--round" => format.rounding = value().parse().unwrap_or_else(|e| {
+                eprintln!("error: {}", e);
+                process::exit(2);
+            }),
+            _ => words.push(arg.as_str()),
+        }
+    }
+
+    let result = units::parse_query(&words.join(" "))
+        .and_then(|q| q.run().map(|v| (q, v)));
+    match result {
+        Ok((q, v)) => println!("{} {} = {} {}", q.value, q.from, format.format(v), q.to),
+        Err(e) => {
+            eprintln!("error: {}", e);
+            process::exit(1);
+        }
+    }
+}
+
+fn number(text: &str) -> u32 {
+    text.parse().unwrap_or_else(|_| usage())
+}
+
+// a precision for convert, more than an f64 has is an error
+fn digits(flag: &str, text: &str, min: u32) -> u32 {
+    let n = number(text);
+    if n < min || n > units::MAX_DIGITS {
+        eprintln!("error: {} takes {} to {} digits", flag, min, units::MAX_DIGITS);
+        process::exit(2);
+    }
+    n
+}
+
+fn print_units() {
+    for dim in Dimension::ALL.iter() {
+        println!("{}", dim);
+        for u in units::UNITS.iter().filter(|u| u.dimension == *dim) {
+            println!("  {:<18} {}", u.name, u.symbols.join(" "));
+        }
+    }
+}
+
+fn print_fib(n: &str, print: bool) {
+    let n: u64 = n.replace('_', "").parse().unwrap_or_else(|_| usage());
+    let mut results = Vec::new();
+    for algo in Algorithm::ALL.iter() {
+        if let Some(limit) = algo.limit().filter(|&l| n > l) {
+            println!("{:<14} skipped, only run up to n = {}", algo.name(), limit);
+            continue;
+        }
+        let start = Instant::now();
+        let value = algo.run(n);
+        println!("{:<14} {:>12.3?}", algo.name(), start.elapsed());
+        results.push(value);
+    }
+
+    let value = &results[results.len() - 1];
+    if results.iter().any(|r| r != value) {
+        println!("\nthe algorithms DISAGREE");
+        process::exit(1);
+    }
+    if results.len() > 1 {
+        println!("\nall {} agree", results.len());
+    }
+    let start = Instant::now();
+    if !fib::check(n, value) {
+        println!("F({}) FAILS Cassini's identity", n);
+        process::exit(1);
+    }
+    println!("checked against F(n-1) and F(n+1) by Cassini's identity in {:.3?}", start.elapsed());
+
+    let digits = value.to_string();
+    if print || digits.len() <= 60 {
+        println!("F({}) = {}", n, digits);
+    } else {
+        println!("F({}) = {}...{}", n, &digits[..20], &digits[digits.len() - 20..]);
+    }
+    println!("{} digits, {} bits", digits.len(), value.bits());
+}
+
+fn print_song(name: &str, verse: Option<&str>) {
+    let fail = |e: &dyn std::fmt::Display| -> ! {
+        eprintln!("error: {}", e);
+        process::exit(1);
+    };
+    let (text, numbers) = match name {
+        "twelve-days" => (song::TWELVE_DAYS.to_string(), song::NUMBERS.to_string()),
+        "old-lady" => (song::OLD_LADY.to_string(), song::NUMBERS.to_string()),
+        "jack" => (song::JACK.to_string(), song::NUMBERS.to_string()),
+        // a numbers.txt next to the song file wins over the bundled one
+        path => {
+            let text = fs::read_to_string(path).unwrap_or_else(|e| fail(&e));
+            let dir = std::path::Path::new(path).with_file_name("numbers.txt");
+            let numbers = fs::read_to_string(dir).unwrap_or_else(|_| song::NUMBERS.to_string());
+            (text, numbers)
+        }
+    };
+    let numbers = Numbers::parse(&numbers).unwrap_or_else(|e| fail(&format!("numbers.txt {}", e)));
+    let song = Song::parse(&text).unwrap_or_else(|e| fail(&e));
+
+    let lyrics = match verse {
+        Some(n) => {
+            let n: usize = n.parse().unwrap_or_else(|_| usage());
+            if n == 0 || n > song.verses() {
+                fail(&format!("{} has verses 1 to {}", song.title, song.verses()));
+            }
+            song.verse(n, &numbers).map(|lines| lines.join("\n"))
+        }
+        None => song.lyrics(&numbers).map(|lyrics| format!("{}\n\n{}", song.title, lyrics)),
+    };
+    println!("{}", lyrics.unwrap_or_else(|e| fail(&e)));
+}
+
+fn print_lint(files: &[String]) {
+    let mut found = 0;
+    for path in files {
+        let source = match fs::read_to_string(path) {
+            Ok(source) => source,
+            Err(e) => {
+                eprintln!("error: {}: {}", path, e);
+                process::exit(1);
+            }
+        };
+        let findings = lint::lint(&source).unwrap_or_else(|e| {
+            let start = e.span().start();
+            eprintln!("error: {}:{}:{}: {}", path, start.line, start.column + 1, e);
+            process::exit(1);
+        });
+        for f in &findings {
+            println!("{}:{}:{}: {}: {}", path, f.line, f.column, f.kind, f.message);
+        }
+        found += findings.len();
+    }
+    // like grep, exit 1 when something was found
+    if found > 0 {
+        process::exit(1);
+    }
+}
+
+fn repl() {
+    let mut session = Session::new();
+    let stdin = io::stdin();
+    let mut input = String::new();
+    loop {
+        print!("{}", if input.is_empty() { ">> " } else { ".. " });
+        io::stdout().flush().unwrap();
+        let mut line = String::new();
+        if stdin.lock().read_line(&mut line).unwrap() == 0 {
+            println!();
+            return;
+        }
+
+        if input.is_empty() {
+            match line.trim() {
+                ":quit" | ":q" => return,
+                ":help" => {
+                    print!("{}", REPL_HELP);
+                    continue;
+                }
+                ":env" => {
+                    for (name, b, value) in session.bindings() {
+                        let kw = if b.mutable { "let mut" } else { "let" };
+                        println!("{} {}: {} = {}", kw, name, b.ty, value);
+                    }
+                    continue;
+                }
+                "" => continue,
+                _ => {}
+            }
+        }
+
+        // keep reading while a block or a paren is still open
+        input.push_str(&line);
+        let depth: i32 = input.chars()
+            .map(|c| match c {
+                '{' | '(' => 1,
+                '}' | ')' => -1,
+                _ => 0,
+            })
+            .sum();
+        if depth > 0 {
+            continue;
+        }
+
+        match session.run(&input) {
+            // like a statement, a trailing `for` or `if` without
+            // else isn't worth printing
+            Ok(Some((_, ast::Type::Unit))) | Ok(None) => {}
+            Ok(Some((value, ty))) => println!("{}: {}", value, ty),
+            Err(e) => println!("{}", e.render(&input)),
+        }
+        input.clear();
+    }
+}
+
+const REPL_HELP: &str = "\
+i32 and bool code like in the lessons, e.g.
+  let mut x = 5;
+  let y = { let x = 3; x + 1 };
+  let n = loop { x += 1; if x == 10 { break x * 2; } };
+  for i in (1..4).rev() { x -= i; }
+an expression without a `;` at the end prints its value and type.
+everything is type checked before it runs, so an error means
+nothing in that input happened.
+
+:env   show the variables so far
+:quit  leave (so does ctrl-d)
+";
+
 fn control_flow() {
     let is_good_looking = true;
     if is_good_looking { // parenthesis is not required and must be a bool
@@ -0,0 +1,319 @@
+// SEE: ch03-02-data-types.html#integer-overflow
+//
+// `a + b` on integers panics on overflow in a debug build and
+// silently wraps in a release build. when you care, std has four
+// families of methods that say exactly what you want instead:
+//
+//   checked_*      None on overflow
+//   wrapping_*     wrap around, like release builds do
+//   saturating_*   stick at MIN or MAX
+//   overflowing_*  the wrapped value plus a "did it overflow" flag
+//
+// explore() runs one operation through all four for every
+// integer type so you can see them side by side.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::literal::{self, Literal, Type};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+    Neg,
+    Shl,
+    Shr,
+    Pow,
+}
+
+impl Op {
+    pub fn is_unary(self) -> bool {
+        self == Op::Neg
+    }
+
+    // how the operation is written with plain operators
+    pub fn expr(self) -> &'static str {
+        match self {
+            Op::Add => "a + b",
+            Op::Sub => "a - b",
+            Op::Mul => "a * b",
+            Op::Div => "a / b",
+            Op::Rem => "a % b",
+            Op::Neg => "-a",
+            Op::Shl => "a << b",
+            Op::Shr => "a >> b",
+            Op::Pow => "a.pow(b)",
+        }
+    }
+}
+
+impl FromStr for Op {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Op, String> {
+        let op = match s {
+            "add" | "+" => Op::Add,
+            "sub" | "-" => Op::Sub,
+            "mul" | "*" => Op::Mul,
+            "div" | "/" => Op::Div,
+            "rem" | "%" => Op::Rem,
+            "neg" => Op::Neg,
+            "shl" | "<<" => Op::Shl,
+            "shr" | ">>" => Op::Shr,
+            "pow" => Op::Pow,
+            _ => return Err(format!("unknown operation `{}`", s)),
+        };
+        Ok(op)
+    }
+}
+
+// what the plain operator does with these operands
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Panic {
+    Never,
+    // overflow checks are on in debug builds only
+    InDebug,
+    // division by zero and MIN / -1 panic in release builds too
+    Always,
+    // e.g. `-a` for an unsigned a is a compile error
+    DoesNotCompile,
+}
+
+impl fmt::Display for Panic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Panic::Never => "ok",
+            Panic::InDebug => "panics in debug",
+            Panic::Always => "always panics",
+            Panic::DoesNotCompile => "does not compile",
+        })
+    }
+}
+
+// one line of the table. every column is already formatted, with
+// "panics" where the method itself panics and "n/a" where std
+// doesn't have that method for the type
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Row {
+    pub ty: Type,
+    pub checked: String,
+    pub wrapping: String,
+    pub saturating: String,
+    pub overflowing: String,
+    pub panic: Panic,
+}
+
+const PANICS: &str = "panics";
+const NA: &str = "n/a";
+
+// std has no trait for checked_add and friends, so a macro writes
+// the same code out once per type
+macro_rules! row {
+    ($t:ident, $variant:ident, $sign:ident, $op:expr, $a:expr, $b:expr) => {{
+        let ty = Type::$variant;
+        let a: $t = match operand($a, ty)? {
+            Literal::$variant(v) => v,
+            _ => unreachable!("parsed with a {} suffix", ty),
+        };
+        let value = |text: Option<&str>| -> Result<$t, literal::Error> {
+            match operand(text.unwrap_or(""), ty)? {
+                Literal::$variant(v) => Ok(v),
+                _ => unreachable!("parsed with a {} suffix", ty),
+            }
+        };
+
+        match $op {
+            Op::Add => {
+                let b = value($b)?;
+                Row {
+                    ty,
+                    checked: some(a.checked_add(b)),
+                    wrapping: a.wrapping_add(b).to_string(),
+                    saturating: a.saturating_add(b).to_string(),
+                    overflowing: pair(a.overflowing_add(b)),
+                    panic: overflow_panic(a.overflowing_add(b).1),
+                }
+            }
+            Op::Sub => {
+                let b = value($b)?;
+                Row {
+                    ty,
+                    checked: some(a.checked_sub(b)),
+                    wrapping: a.wrapping_sub(b).to_string(),
+                    saturating: a.saturating_sub(b).to_string(),
+                    overflowing: pair(a.overflowing_sub(b)),
+                    panic: overflow_panic(a.overflowing_sub(b).1),
+                }
+            }
+            Op::Mul => {
+                let b = value($b)?;
+                Row {
+                    ty,
+                    checked: some(a.checked_mul(b)),
+                    wrapping: a.wrapping_mul(b).to_string(),
+                    saturating: a.saturating_mul(b).to_string(),
+                    overflowing: pair(a.overflowing_mul(b)),
+                    panic: overflow_panic(a.overflowing_mul(b).1),
+                }
+            }
+            Op::Div if value($b)? == 0 => Row {
+                ty,
+                checked: some(a.checked_div(0)),
+                wrapping: PANICS.to_string(),
+                saturating: PANICS.to_string(),
+                overflowing: PANICS.to_string(),
+                panic: Panic::Always,
+            },
+            Op::Div => {
+                let b = value($b)?;
+                Row {
+                    ty,
+                    checked: some(a.checked_div(b)),
+                    wrapping: a.wrapping_div(b).to_string(),
+                    saturating: a.saturating_div(b).to_string(),
+                    overflowing: pair(a.overflowing_div(b)),
+                    panic: if a.overflowing_div(b).1 { Panic::Always } else { Panic::Never },
+                }
+            }
+            Op::Rem if value($b)? == 0 => Row {
+                ty,
+                checked: some(a.checked_rem(0)),
+                wrapping: PANICS.to_string(),
+                saturating: NA.to_string(),
+                overflowing: PANICS.to_string(),
+                panic: Panic::Always,
+            },
+            Op::Rem => {
+                let b = value($b)?;
+                Row {
+                    ty,
+                    checked: some(a.checked_rem(b)),
+                    wrapping: a.wrapping_rem(b).to_string(),
+                    saturating: NA.to_string(),
+                    overflowing: pair(a.overflowing_rem(b)),
+                    panic: if a.overflowing_rem(b).1 { Panic::Always } else { Panic::Never },
+                }
+            }
+            Op::Neg => Row {
+                ty,
+                checked: some(a.checked_neg()),
+                wrapping: a.wrapping_neg().to_string(),
+                saturating: saturating_neg!($sign, a),
+                overflowing: pair(a.overflowing_neg()),
+                panic: neg_panic!($sign, a),
+            },
+            Op::Shl => {
+                let b = amount($b)?;
+                Row {
+                    ty,
+                    checked: some(a.checked_shl(b)),
+                    wrapping: a.wrapping_shl(b).to_string(),
+                    saturating: NA.to_string(),
+                    overflowing: pair(a.overflowing_shl(b)),
+                    panic: overflow_panic(a.overflowing_shl(b).1),
+                }
+            }
+            Op::Shr => {
+                let b = amount($b)?;
+                Row {
+                    ty,
+                    checked: some(a.checked_shr(b)),
+                    wrapping: a.wrapping_shr(b).to_string(),
+                    saturating: NA.to_string(),
+                    overflowing: pair(a.overflowing_shr(b)),
+                    panic: overflow_panic(a.overflowing_shr(b).1),
+                }
+            }
+            Op::Pow => {
+                let b = amount($b)?;
+                Row {
+                    ty,
+                    checked: some(a.checked_pow(b)),
+                    wrapping: a.wrapping_pow(b).to_string(),
+                    saturating: a.saturating_pow(b).to_string(),
+                    overflowing: pair(a.overflowing_pow(b)),
+                    panic: overflow_panic(a.overflowing_pow(b).1),
+                }
+            }
+        }
+    }};
+}
+
+// saturating_neg only exists for signed types
+macro_rules! saturating_neg {
+    (signed, $a:expr) => {
+        $a.saturating_neg().to_string()
+    };
+    (unsigned, $a:expr) => {
+        NA.to_string()
+    };
+}
+
+// and `-a` doesn't even compile for unsigned ones
+macro_rules! neg_panic {
+    (signed, $a:expr) => {
+        overflow_panic($a.overflowing_neg().1)
+    };
+    (unsigned, $a:expr) => {
+        Panic::DoesNotCompile
+    };
+}
+
+// one row per integer type. a row is an error when an operand
+// doesn't fit that type, e.g. 200 as an i8
+pub fn explore(op: Op, a: &str, b: Option<&str>) -> Vec<Result<Row, literal::Error>> {
+    Type::INTEGERS
+        .iter()
+        .map(|&ty| -> Result<Row, literal::Error> {
+            let row = match ty {
+                Type::I8 => row!(i8, I8, signed, op, a, b),
+                Type::I16 => row!(i16, I16, signed, op, a, b),
+                Type::I32 => row!(i32, I32, signed, op, a, b),
+                Type::I64 => row!(i64, I64, signed, op, a, b),
+                Type::I128 => row!(i128, I128, signed, op, a, b),
+                Type::Isize => row!(isize, Isize, signed, op, a, b),
+                Type::U8 => row!(u8, U8, unsigned, op, a, b),
+                Type::U16 => row!(u16, U16, unsigned, op, a, b),
+                Type::U32 => row!(u32, U32, unsigned, op, a, b),
+                Type::U64 => row!(u64, U64, unsigned, op, a, b),
+                Type::U128 => row!(u128, U128, unsigned, op, a, b),
+                Type::Usize => row!(usize, Usize, unsigned, op, a, b),
+                Type::F32 | Type::F64 => unreachable!("not an integer type"),
+            };
+            Ok(row)
+        })
+        .collect()
+}
+
+// the operand text is parsed as a literal of the row's type, so
+// 0xff, 1_000 and -128 all work and out of range values are caught
+fn operand(text: &str, ty: Type) -> Result<Literal, literal::Error> {
+    literal::parse(&format!("{}{}", text, ty))
+}
+
+// shift amounts and exponents are always u32
+fn amount(text: Option<&str>) -> Result<u32, literal::Error> {
+    match operand(text.unwrap_or(""), Type::U32)? {
+        Literal::U32(v) => Ok(v),
+        _ => unreachable!("parsed with a u32 suffix"),
+    }
+}
+
+fn some<T: fmt::Display>(v: Option<T>) -> String {
+    match v {
+        Some(v) => format!("Some({})", v),
+        None => String::from("None"),
+    }
+}
+
+fn pair<T: fmt::Display>((v, overflowed): (T, bool)) -> String {
+    format!("({}, {})", v, overflowed)
+}
+
+fn overflow_panic(overflowed: bool) -> Panic {
+    if overflowed { Panic::InDebug } else { Panic::Never }
+}
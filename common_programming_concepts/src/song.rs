@@ -0,0 +1,325 @@
+// SEE: ch03-05-control-flow.html#summary
+//
+// "print the lyrics to the Christmas carol The Twelve Days of
+// Christmas, taking advantage of the repetition in the song". it's a
+// cumulative song: every verse adds a line and then repeats all the
+// earlier ones, newest first. so are "There Was an Old Lady" and
+// "The House That Jack Built", so the song itself is data (see
+// songs/*.song) and the loop that builds the verses is the same.
+//
+// a .song file has these lines, everything after a # is a comment:
+//
+//   title: ...
+//   start: ...        printed at the start of every verse
+//   chain: ...        printed for item n, n-1, ... down to chain-from
+//   end: ...          printed at the end of every verse
+//   chain-from: N     the oldest item the chain goes back to, 1 if unset
+//   items: a | b      the column names, then one `- x | y` row per item
+//
+// start, chain and end can be given more than once for several lines,
+// and have variants used instead in some places: @first in the first
+// verse, @last in the last one, and (chain only) @final for the last
+// line of the chain. lines can use {column} of the item, {prev.column}
+// of the one before it, and {n}, {count} and {ordinal} for its number,
+// spelled out from numbers.txt. {Count} and the like are capitalized.
+// a line that comes out empty is left out.
+
+use std::error;
+use std::fmt;
+
+pub const TWELVE_DAYS: &str = include_str!("../songs/twelve_days.song");
+pub const OLD_LADY: &str = include_str!("../songs/old_lady.song");
+pub const JACK: &str = include_str!("../songs/jack.song");
+pub const NUMBERS: &str = include_str!("../songs/numbers.txt");
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Error {
+    // 1-based line in the file, 0 when it's not about one line
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.line {
+            0 => write!(f, "{}", self.message),
+            n => write!(f, "line {}: {}", n, self.message),
+        }
+    }
+}
+
+impl error::Error for Error {}
+
+fn error<T>(line: usize, message: &str) -> Result<T, Error> {
+    Err(Error { line, message: message.to_string() })
+}
+
+// the lines of a file without comments or blank lines, numbered
+fn lines(text: &str) -> impl Iterator<Item = (usize, &str)> {
+    text.lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.split('#').next().unwrap().trim()))
+        .filter(|(_, line)| !line.is_empty())
+}
+
+pub struct Numbers {
+    cardinal: Vec<String>,
+    ordinal: Vec<String>,
+}
+
+impl Numbers {
+    // one `N cardinal ordinal` per line, N counting up from 1
+    pub fn parse(text: &str) -> Result<Numbers, Error> {
+        let mut numbers = Numbers { cardinal: vec![], ordinal: vec![] };
+        for (line_no, line) in lines(text) {
+            let words: Vec<&str> = line.split_whitespace().collect();
+            match words[..] {
+                [n, cardinal, ordinal] if n.parse() == Ok(numbers.cardinal.len() + 1) => {
+                    numbers.cardinal.push(cardinal.to_string());
+                    numbers.ordinal.push(ordinal.to_string());
+                }
+                [_, _, _] => {
+                    let msg = format!("expected number {} next", numbers.cardinal.len() + 1);
+                    return error(line_no, &msg);
+                }
+                _ => return error(line_no, "expected `number cardinal ordinal`"),
+            }
+        }
+        Ok(numbers)
+    }
+
+    pub fn len(&self) -> usize {
+        self.cardinal.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cardinal.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Piece {
+    Text(String),
+    Field { prev: bool, name: String, capital: bool },
+}
+
+type Template = Vec<Piece>;
+
+const NUMBER_FIELDS: [&str; 3] = ["n", "count", "ordinal"];
+
+fn parse_template(text: &str, columns: &[String], line_no: usize) -> Result<Template, Error> {
+    let mut pieces = Vec::new();
+    let mut rest = text;
+    while let Some(open) = rest.find('{') {
+        if open > 0 {
+            pieces.push(Piece::Text(rest[..open].to_string()));
+        }
+        let close = match rest[open..].find('}') {
+            Some(i) => open + i,
+            None => return error(line_no, "`{` without a `}`"),
+        };
+        let field = &rest[open + 1..close];
+        let (prev, name) = match field.strip_prefix("prev.") {
+            Some(name) => (true, name),
+            None => (false, field),
+        };
+        let capital = name.starts_with(|c: char| c.is_uppercase());
+        let name = name.to_lowercase();
+        if !NUMBER_FIELDS.contains(&name.as_str()) && !columns.contains(&name) {
+            return error(line_no, &format!("unknown field {{{}}}", field));
+        }
+        pieces.push(Piece::Field { prev, name, capital });
+        rest = &rest[close + 1..];
+    }
+    if !rest.is_empty() {
+        pieces.push(Piece::Text(rest.to_string()));
+    }
+    Ok(pieces)
+}
+
+// the lines of one part of a verse, and the variants that replace them
+#[derive(Debug, Default)]
+struct Part {
+    plain: Vec<Template>,
+    first: Option<Vec<Template>>,
+    last: Option<Vec<Template>>,
+    // the last line of the chain, chain only
+    final_line: Option<Vec<Template>>,
+}
+
+impl Part {
+    fn pick(&self, first: bool, last: bool, final_line: bool) -> &[Template] {
+        let variant = match () {
+            _ if first && self.first.is_some() => &self.first,
+            _ if last && self.last.is_some() => &self.last,
+            _ if final_line && self.final_line.is_some() => &self.final_line,
+            _ => return &self.plain,
+        };
+        variant.as_deref().unwrap()
+    }
+}
+
+pub struct Song {
+    pub title: String,
+    start: Part,
+    chain: Part,
+    end: Part,
+    chain_from: usize,
+    columns: Vec<String>,
+    items: Vec<Vec<String>>,
+}
+
+impl Song {
+    pub fn parse(text: &str) -> Result<Song, Error> {
+        let mut title = None;
+        let mut chain_from = 1;
+        let mut columns: Option<Vec<String>> = None;
+        let mut items = Vec::new();
+        // templates can only be checked once the columns are known
+        let mut templates = Vec::new();
+
+        for (line_no, line) in lines(text) {
+            if let Some(row) = line.strip_prefix('-') {
+                let columns = match &columns {
+                    Some(columns) => columns,
+                    None => return error(line_no, "an item before the `items:` line"),
+                };
+                let row: Vec<String> = row.split('|').map(|cell| cell.trim().to_string()).collect();
+                if row.len() != columns.len() {
+                    let msg = format!("{} column(s), `items:` has {}", row.len(), columns.len());
+                    return error(line_no, &msg);
+                }
+                items.push(row);
+                continue;
+            }
+
+            let (key, value) = match line.split_once(':') {
+                Some((key, value)) => (key.trim(), value.trim()),
+                None => return error(line_no, "expected `key: value` or an `- item`"),
+            };
+            match key {
+                "title" => title = Some(value.to_string()),
+                "chain-from" => {
+                    chain_from = match value.parse() {
+                        Ok(n) if n >= 1 => n,
+                        _ => return error(line_no, "chain-from must be 1 or more"),
+                    }
+                }
+                "items" => columns = Some(value.split('|').map(|c| c.trim().to_lowercase()).collect()),
+                _ => templates.push((line_no, key, value)),
+            }
+        }
+
+        let columns = match columns {
+            Some(columns) => columns,
+            None => return error(0, "no `items:` line"),
+        };
+        if items.is_empty() {
+            return error(0, "no items");
+        }
+
+        let mut song = Song {
+            title: title.unwrap_or_default(),
+            start: Part::default(),
+            chain: Part::default(),
+            end: Part::default(),
+            chain_from,
+            columns,
+            items,
+        };
+        for (line_no, key, value) in templates {
+            let (name, variant) = key.split_once('@').unwrap_or((key, ""));
+            let template = parse_template(value, &song.columns, line_no)?;
+            let part = match name {
+                "start" => &mut song.start,
+                "chain" => &mut song.chain,
+                "end" => &mut song.end,
+                _ => return error(line_no, &format!("unknown key `{}`", key)),
+            };
+            let lines = match (name, variant) {
+                (_, "") => &mut part.plain,
+                (_, "first") => part.first.get_or_insert_with(Vec::new),
+                (_, "last") => part.last.get_or_insert_with(Vec::new),
+                ("chain", "final") => part.final_line.get_or_insert_with(Vec::new),
+                _ => return error(line_no, &format!("unknown variant `{}`", key)),
+            };
+            lines.push(template);
+        }
+        Ok(song)
+    }
+
+    pub fn verses(&self) -> usize {
+        self.items.len()
+    }
+
+    // verse n, counting from 1
+    pub fn verse(&self, n: usize, numbers: &Numbers) -> Result<Vec<String>, Error> {
+        let first = n == 1;
+        let last = n == self.verses();
+        let mut out = Vec::new();
+        let mut add = |templates: &[Template], item: usize| -> Result<(), Error> {
+            for t in templates {
+                let line = self.render(t, item, numbers)?;
+                if !line.is_empty() {
+                    out.push(line);
+                }
+            }
+            Ok(())
+        };
+
+        add(self.start.pick(first, last, false), n)?;
+        for item in (self.chain_from..=n).rev() {
+            let final_line = item == self.chain_from;
+            add(self.chain.pick(first, last, final_line), item)?;
+        }
+        add(self.end.pick(first, last, false), n)?;
+        Ok(out)
+    }
+
+    pub fn lyrics(&self, numbers: &Numbers) -> Result<String, Error> {
+        let mut verses = Vec::new();
+        for n in 1..=self.verses() {
+            verses.push(self.verse(n, numbers)?.join("\n"));
+        }
+        Ok(verses.join("\n\n"))
+    }
+
+    fn render(&self, template: &[Piece], item: usize, numbers: &Numbers) -> Result<String, Error> {
+        let mut out = String::new();
+        for piece in template {
+            let (prev, name, capital) = match piece {
+                Piece::Text(text) => {
+                    out += text;
+                    continue;
+                }
+                Piece::Field { prev, name, capital } => (*prev, name, *capital),
+            };
+            let item = if prev { item - 1 } else { item };
+            if item == 0 {
+                return error(0, &format!("{{prev.{}}} used for the first item", name));
+            }
+            let value = match name.as_str() {
+                "n" => item.to_string(),
+                "count" | "ordinal" if item > numbers.len() => {
+                    return error(0, &format!("numbers.txt only goes up to {}", numbers.len()));
+                }
+                "count" => numbers.cardinal[item - 1].clone(),
+                "ordinal" => numbers.ordinal[item - 1].clone(),
+                _ => {
+                    let column = self.columns.iter().position(|c| c == name).unwrap();
+                    self.items[item - 1][column].clone()
+                }
+            };
+            if capital {
+                let mut chars = value.chars();
+                if let Some(c) = chars.next() {
+                    out.extend(c.to_uppercase());
+                    out += chars.as_str();
+                }
+            } else {
+                out += &value;
+            }
+        }
+        Ok(out.trim().to_string())
+    }
+}
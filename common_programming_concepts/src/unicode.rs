@@ -0,0 +1,107 @@
+// a char is one Unicode scalar value: any code point from U+0000
+// to U+10FFFF except the surrogates U+D800..U+DFFF. that needs 21
+// bits, so a char is always 4 bytes. a String stores the same
+// scalars as UTF-8, which takes 1 to 4 bytes each:
+//
+//   U+0000..U+007F     1 byte    'z'
+//   U+0080..U+07FF     2 bytes   'é'
+//   U+0800..U+FFFF     3 bytes   '€'
+//   U+10000..U+10FFFF  4 bytes   '😻'
+//
+// what a person calls "a character" is a grapheme cluster, which
+// can be several scalars: 'e' + U+0301 is é, and 👩‍💻 is three
+// scalars glued with a zero width joiner. and how many terminal
+// columns it takes is yet another number.
+
+use unicode_general_category::{get_general_category, GeneralCategory};
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Scalar {
+    pub ch: char,
+    // where it starts in the string's bytes
+    pub offset: usize,
+    pub category: GeneralCategory,
+    // None for control characters
+    pub width: Option<usize>,
+}
+
+impl Scalar {
+    pub fn utf8(&self) -> Vec<u8> {
+        let mut buf = [0; 4];
+        self.ch.encode_utf8(&mut buf).as_bytes().to_vec()
+    }
+
+    // "U+1F63B"
+    pub fn code_point(&self) -> String {
+        format!("U+{:04X}", self.ch as u32)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grapheme<'a> {
+    pub text: &'a str,
+    pub scalars: Vec<Scalar>,
+    pub width: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Report<'a> {
+    pub text: &'a str,
+    pub graphemes: Vec<Grapheme<'a>>,
+}
+
+impl<'a> Report<'a> {
+    // what text.len() says
+    pub fn utf8_bytes(&self) -> usize {
+        self.text.len()
+    }
+
+    // what text.chars().count() says
+    pub fn scalars(&self) -> usize {
+        self.graphemes.iter().map(|g| g.scalars.len()).sum()
+    }
+
+    // the same text as a Vec<char> or [char; N]
+    pub fn char_bytes(&self) -> usize {
+        self.scalars() * std::mem::size_of::<char>()
+    }
+
+    pub fn width(&self) -> usize {
+        self.text.width()
+    }
+}
+
+pub fn inspect(text: &str) -> Report<'_> {
+    let graphemes = text
+        .grapheme_indices(true)
+        .map(|(start, g)| Grapheme {
+            text: g,
+            scalars: g
+                .char_indices()
+                .map(|(i, ch)| Scalar {
+                    ch,
+                    offset: start + i,
+                    category: get_general_category(ch),
+                    width: ch.width(),
+                })
+                .collect(),
+            width: g.width(),
+        })
+        .collect();
+    Report { text, graphemes }
+}
+
+// the long name, LowercaseLetter -> "Lowercase Letter"
+pub fn category_name(category: GeneralCategory) -> String {
+    let debug = format!("{:?}", category);
+    let mut name = String::new();
+    for c in debug.chars() {
+        if c.is_uppercase() && !name.is_empty() {
+            name.push(' ');
+        }
+        name.push(c);
+    }
+    name
+}
@@ -0,0 +1,351 @@
+// SEE: ch03-05-control-flow.html#summary
+//
+// the chapter ends with "convert temperatures between Fahrenheit and
+// Celsius". this does that and a bit more: temperature, length,
+// mass, volume and time, with input like "72 F to C".
+//
+// every unit is stored as a straight line to the base unit of its
+// dimension, base = value * scale + offset. only temperatures need
+// the offset: 0 °C isn't 0 K, but 0 m is 0 ft. converting goes
+// through the base unit, and two units can only be converted if
+// they measure the same dimension.
+
+use std::error;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dimension {
+    Temperature,
+    Length,
+    Mass,
+    Volume,
+    Time,
+}
+
+impl Dimension {
+    pub const ALL: [Dimension; 5] = [
+        Dimension::Temperature,
+        Dimension::Length,
+        Dimension::Mass,
+        Dimension::Volume,
+        Dimension::Time,
+    ];
+}
+
+impl fmt::Display for Dimension {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Dimension::Temperature => "temperature",
+            Dimension::Length => "length",
+            Dimension::Mass => "mass",
+            Dimension::Volume => "volume",
+            Dimension::Time => "time",
+        })
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Unit {
+    pub name: &'static str,
+    // the first one is used for output
+    pub symbols: &'static [&'static str],
+    pub dimension: Dimension,
+    scale: f64,
+    offset: f64,
+}
+
+impl Unit {
+    pub fn symbol(&self) -> &'static str {
+        self.symbols[0]
+    }
+
+    fn base_value(&self, value: f64) -> f64 {
+        value * self.scale + self.offset
+    }
+
+    fn unit_value(&self, base: f64) -> f64 {
+        (base - self.offset) / self.scale
+    }
+}
+
+impl fmt::Display for Unit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.symbol())
+    }
+}
+
+macro_rules! unit {
+    ($name:expr, $dim:ident, [$($sym:expr),+], $scale:expr) => {
+        unit!($name, $dim, [$($sym),+], $scale, 0.0)
+    };
+    ($name:expr, $dim:ident, [$($sym:expr),+], $scale:expr, $offset:expr) => {
+        Unit {
+            name: $name,
+            symbols: &[$($sym),+],
+            dimension: Dimension::$dim,
+            scale: $scale,
+            offset: $offset,
+        }
+    };
+}
+
+// base units: kelvin, metre, kilogram, litre, second. the US and
+// imperial ones use their exact legal definitions
+pub const UNITS: &[Unit] = &[
+    unit!("kelvin", Temperature, ["K", "kelvin"], 1.0),
+    unit!("degree Celsius", Temperature, ["°C", "C", "celsius"], 1.0, 273.15),
+    unit!("degree Fahrenheit", Temperature, ["°F", "F", "fahrenheit"], 5.0 / 9.0, 459.67 * 5.0 / 9.0),
+    unit!("degree Rankine", Temperature, ["°R", "R", "rankine"], 5.0 / 9.0),
+
+    unit!("millimetre", Length, ["mm", "millimetre", "millimeter"], 0.001),
+    unit!("centimetre", Length, ["cm", "centimetre", "centimeter"], 0.01),
+    unit!("metre", Length, ["m", "metre", "meter"], 1.0),
+    unit!("kilometre", Length, ["km", "kilometre", "kilometer"], 1000.0),
+    unit!("inch", Length, ["in", "inch", "inches", "\""], 0.0254),
+    unit!("foot", Length, ["ft", "foot", "feet", "'"], 0.3048),
+    unit!("yard", Length, ["yd", "yard"], 0.9144),
+    unit!("mile", Length, ["mi", "mile"], 1609.344),
+    unit!("nautical mile", Length, ["nmi"], 1852.0),
+
+    unit!("milligram", Mass, ["mg", "milligram"], 1e-6),
+    unit!("gram", Mass, ["g", "gram"], 0.001),
+    unit!("kilogram", Mass, ["kg", "kilogram", "kilo"], 1.0),
+    unit!("tonne", Mass, ["t", "tonne"], 1000.0),
+    unit!("ounce", Mass, ["oz", "ounce"], 0.028_349_523_125),
+    unit!("pound", Mass, ["lb", "lbs", "pound"], 0.453_592_37),
+    unit!("stone", Mass, ["st", "stone"], 6.350_293_18),
+
+    unit!("millilitre", Volume, ["mL", "ml", "millilitre", "milliliter"], 0.001),
+    unit!("litre", Volume, ["L", "l", "litre", "liter"], 1.0),
+    unit!("cubic metre", Volume, ["m3", "m³"], 1000.0),
+    unit!("US teaspoon", Volume, ["tsp", "teaspoon"], 0.004_928_921_593_75),
+    unit!("US tablespoon", Volume, ["tbsp", "tablespoon"], 0.014_786_764_781_25),
+    unit!("US fluid ounce", Volume, ["floz", "fl_oz"], 0.029_573_529_562_5),
+    unit!("US cup", Volume, ["cup"], 0.236_588_236_5),
+    unit!("US pint", Volume, ["pt", "pint"], 0.473_176_473),
+    unit!("US quart", Volume, ["qt", "quart"], 0.946_352_946),
+    unit!("US gallon", Volume, ["gal", "gallon"], 3.785_411_784),
+    unit!("imperial gallon", Volume, ["impgal"], 4.546_09),
+
+    unit!("nanosecond", Time, ["ns", "nanosecond"], 1e-9),
+    unit!("microsecond", Time, ["us", "µs", "microsecond"], 1e-6),
+    unit!("millisecond", Time, ["ms", "millisecond"], 0.001),
+    unit!("second", Time, ["s", "sec", "second"], 1.0),
+    unit!("minute", Time, ["min", "minute"], 60.0),
+    unit!("hour", Time, ["h", "hr", "hour"], 3600.0),
+    unit!("day", Time, ["d", "day"], 86_400.0),
+    unit!("week", Time, ["wk", "week"], 604_800.0),
+    // the Julian year, 365.25 days, the one astronomers use
+    unit!("year", Time, ["yr", "year"], 31_557_600.0),
+];
+
+// symbols are case sensitive (mm isn't Mm), full names aren't, and
+// a trailing s is dropped so "metres", "hours", "mins" and "hrs"
+// work. not from one letter symbols, "ds" isn't days
+pub fn lookup(text: &str) -> Option<&'static Unit> {
+    let by_symbol = |t: &str| UNITS.iter().find(|u| u.symbols.contains(&t));
+    let by_name = |t: &str| {
+        let lower = t.to_lowercase();
+        UNITS.iter().find(|u| u.symbols.iter().skip(1).any(|s| s.to_lowercase() == lower))
+    };
+    by_symbol(text)
+        .or_else(|| by_name(text))
+        .or_else(|| {
+            let singular = text.strip_suffix('s').filter(|t| t.chars().count() > 1)?;
+            by_symbol(singular).or_else(|| by_name(singular))
+        })
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Error {
+    Syntax(String),
+    NotANumber(String),
+    UnknownUnit(String),
+    Mismatch { from: &'static Unit, to: &'static Unit },
+    BelowAbsoluteZero,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Syntax(text) => write!(f, "expected something like \"72 F to C\", got {:?}", text),
+            Error::NotANumber(text) => write!(f, "{:?} is not a number", text),
+            Error::UnknownUnit(text) => write!(f, "unknown unit {:?}", text),
+            Error::Mismatch { from, to } => write!(
+                f,
+                "can't convert {} ({}) to {} ({})",
+                from.name, from.dimension, to.name, to.dimension
+            ),
+            Error::BelowAbsoluteZero => write!(f, "that's below absolute zero"),
+        }
+    }
+}
+
+impl error::Error for Error {}
+
+pub fn convert(value: f64, from: &'static Unit, to: &'static Unit) -> Result<f64, Error> {
+    if from.dimension != to.dimension {
+        return Err(Error::Mismatch { from, to });
+    }
+    let base = from.base_value(value);
+    // -459.67 °F can come out a hair below 0 K, that's still fine
+    if from.dimension == Dimension::Temperature && base < -1e-9 {
+        return Err(Error::BelowAbsoluteZero);
+    }
+    Ok(to.unit_value(base))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Query {
+    pub value: f64,
+    pub from: &'static Unit,
+    pub to: &'static Unit,
+}
+
+impl Query {
+    pub fn run(&self) -> Result<f64, Error> {
+        convert(self.value, self.from, self.to)
+    }
+}
+
+// "72 F to C", "72F in C", "1.5 km -> mi"
+pub fn parse_query(text: &str) -> Result<Query, Error> {
+    let syntax = || Error::Syntax(text.to_string());
+    let mut words: Vec<&str> = text.split_whitespace().collect();
+    if words.is_empty() {
+        return Err(syntax());
+    }
+
+    // the number may be stuck to its unit: 72F, 1.5km, -40°C
+    let first = words.remove(0);
+    let split = first
+        .char_indices()
+        .find(|&(i, c)| !(c.is_ascii_digit() || c == '.' || c == '_' || (i == 0 && (c == '-' || c == '+'))))
+        .map_or(first.len(), |(i, _)| i);
+    // ... but not 1e3, which is a number
+    let split = if first[split..].starts_with(['e', 'E']) && first.parse::<f64>().is_ok() {
+        first.len()
+    } else {
+        split
+    };
+    let (number, unit) = first.split_at(split);
+    if !unit.is_empty() {
+        words.insert(0, unit);
+    }
+    let value = number
+        .replace('_', "")
+        .parse::<f64>()
+        .map_err(|_| Error::NotANumber(if number.is_empty() { first } else { number }.to_string()))?;
+
+    // what's left is `unit connector unit`. `in` is also the symbol for
+    // inches, so the connector is found by where it is, not by what it
+    // is: "5 in in cm", "5 cm to in"
+    let connector = |w: &str| matches!(w, "to" | "in" | "->" | "as");
+    if words.len() != 3 || !connector(words[1]) {
+        return Err(syntax());
+    }
+    let unit = |text: &str| lookup(text).ok_or_else(|| Error::UnknownUnit(text.to_string()));
+    Ok(Query { value, from: unit(words[0])?, to: unit(words[2])? })
+}
+
+// what to do with the digits that don't fit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rounding {
+    // 2.5 -> 3, -2.5 -> -3, what f64::round() does
+    HalfAwayFromZero,
+    // 2.5 -> 2, 3.5 -> 4, no bias when adding many rounded values
+    HalfEven,
+    // cut the digits off, 2.9 -> 2, -2.9 -> -2
+    TowardZero,
+    Floor,
+    Ceil,
+}
+
+impl std::str::FromStr for Rounding {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Rounding, String> {
+        let mode = match s {
+            "half-away" | "half-up" => Rounding::HalfAwayFromZero,
+            "half-even" | "bankers" => Rounding::HalfEven,
+            "down" | "truncate" => Rounding::TowardZero,
+            "floor" => Rounding::Floor,
+            "ceil" => Rounding::Ceil,
+            _ => return Err(format!("unknown rounding `{}`", s)),
+        };
+        Ok(mode)
+    }
+}
+
+// an f64 holds 15-17 significant digits, asking for more only adds
+// noise. Format uses at most this many
+pub const MAX_DIGITS: u32 = 17;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Precision {
+    // digits after the decimal point
+    Decimals(u32),
+    // digits counted from the first non-zero one
+    Significant(u32),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Format {
+    pub precision: Precision,
+    pub rounding: Rounding,
+}
+
+impl Default for Format {
+    fn default() -> Format {
+        Format { precision: Precision::Decimals(2), rounding: Rounding::HalfAwayFromZero }
+    }
+}
+
+impl Format {
+    // rounds in binary floating point, so a value like 2.675 (which is
+    // really 2.67499999...) rounds down, as it does in most languages
+    pub fn round(&self, value: f64) -> f64 {
+        if value == 0.0 || !value.is_finite() {
+            return value;
+        }
+        let decimals = self.decimals(value);
+        let factor = 10f64.powi(decimals);
+        // only a value near the smallest f64 asks for so many decimals
+        // that 10^decimals is inf. it has no digits to lose there
+        if !factor.is_finite() {
+            return value;
+        }
+        let scaled = value * factor;
+        let rounded = match self.rounding {
+            Rounding::HalfAwayFromZero => scaled.round(),
+            Rounding::HalfEven => scaled.round_ties_even(),
+            Rounding::TowardZero => scaled.trunc(),
+            Rounding::Floor => scaled.floor(),
+            Rounding::Ceil => scaled.ceil(),
+        };
+        rounded / factor
+    }
+
+    pub fn format(&self, value: f64) -> String {
+        let rounded = self.round(value);
+        let decimals = self.decimals(rounded).max(0) as usize;
+        // -0.00 looks odd after rounding a tiny negative
+        let rounded = if rounded == 0.0 { 0.0 } else { rounded };
+        format!("{:.*}", decimals, rounded)
+    }
+
+    // how many digits after the point, negative to round to tens,
+    // hundreds, ... the precision is clamped to MAX_DIGITS
+    fn decimals(&self, value: f64) -> i32 {
+        match self.precision {
+            Precision::Decimals(n) => n.min(MAX_DIGITS) as i32,
+            Precision::Significant(n) => {
+                let n = n.clamp(1, MAX_DIGITS);
+                if value == 0.0 {
+                    return n as i32 - 1;
+                }
+                n as i32 - 1 - value.abs().log10().floor() as i32
+            }
+        }
+    }
+}
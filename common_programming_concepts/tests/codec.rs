@@ -0,0 +1,111 @@
+use common_programming_concepts::codec::{from_bytes, to_bytes, Config, Decode, Encode, Endian, ErrorKind};
+use proptest::prelude::*;
+
+fn configs() -> impl Strategy<Value = Config> {
+    (any::<bool>(), any::<bool>()).prop_map(|(big, varint)| Config {
+        endian: if big { Endian::Big } else { Endian::Little },
+        varint,
+    })
+}
+
+// floats are compared by their bits so NaN == NaN
+fn round_trip<T, K>(value: &T, config: Config, key: impl Fn(&T) -> K) -> Result<(), TestCaseError>
+where
+    T: Encode + Decode,
+    K: PartialEq + std::fmt::Debug,
+{
+    let bytes = to_bytes(value, config);
+    let back: T = from_bytes(&bytes, config).map_err(|e| TestCaseError::fail(e.to_string()))?;
+    prop_assert_eq!(key(&back), key(value));
+
+    // every shorter input is missing something
+    for n in 0..bytes.len() {
+        match from_bytes::<T>(&bytes[..n], config) {
+            Err(e) => prop_assert!(
+                matches!(e.kind, ErrorKind::UnexpectedEnd { .. }),
+                "{} bytes of {:?}: {}", n, bytes, e
+            ),
+            Ok(_) => prop_assert!(false, "{} bytes of {:?} decoded", n, bytes),
+        }
+    }
+    Ok(())
+}
+
+proptest! {
+    // the tuple and the array from data_types()
+    #[test]
+    fn lesson_tuple(v in any::<(i32, f64, bool)>(), config in configs()) {
+        round_trip(&v, config, |&(a, b, c)| (a, b.to_bits(), c))?;
+    }
+
+    #[test]
+    fn lesson_array(v in any::<[f32; 3]>(), config in configs()) {
+        round_trip(&v, config, |a| a.map(f32::to_bits))?;
+    }
+
+    #[test]
+    fn signed(v in any::<(i8, i16, i32, i64, i128, isize)>(), config in configs()) {
+        round_trip(&v, config, |v| *v)?;
+    }
+
+    #[test]
+    fn unsigned(v in any::<(u8, u16, u32, u64, u128, usize)>(), config in configs()) {
+        round_trip(&v, config, |v| *v)?;
+    }
+
+    #[test]
+    fn nested(v in any::<((char, bool), [(u8, i64); 4], ())>(), config in configs()) {
+        round_trip(&v, config, |v| *v)?;
+    }
+
+    // garbage in is an error, never a panic. and with fixed width
+    // numbers there's only one way to write each value, so anything
+    // that decodes encodes back to the same bytes
+    #[test]
+    fn any_bytes(bytes in proptest::collection::vec(any::<u8>(), 0..40), config in configs()) {
+        if let Ok(v) = from_bytes::<(u16, char, bool, [i32; 2], f64)>(&bytes, config) {
+            if !config.varint {
+                prop_assert_eq!(to_bytes(&v, config), bytes);
+            }
+        }
+    }
+}
+
+#[test]
+fn byte_order() {
+    let big = Config { endian: Endian::Big, varint: false };
+    assert_eq!(to_bytes(&0x0102_0304i32, Config::default()), [4, 3, 2, 1]);
+    assert_eq!(to_bytes(&0x0102_0304i32, big), [1, 2, 3, 4]);
+    // isize and usize are 64 bits whatever the machine
+    assert_eq!(to_bytes(&1usize, big), [0, 0, 0, 0, 0, 0, 0, 1]);
+}
+
+#[test]
+fn varints() {
+    let varint = Config { varint: true, ..Config::default() };
+    assert_eq!(to_bytes(&300u32, varint), [0xac, 0x02]);
+    assert_eq!(to_bytes(&-1i64, varint), [0x01]);
+    assert_eq!(to_bytes(&1i64, varint), [0x02]);
+    assert_eq!(to_bytes(&u128::MAX, varint).len(), 19);
+
+    let e = from_bytes::<u8>(&[0xac, 0x02], varint).unwrap_err();
+    assert_eq!(e.kind, ErrorKind::TooLarge("u8"));
+    let e = from_bytes::<u128>(&[0xff; 20], varint).unwrap_err();
+    assert_eq!(e.kind, ErrorKind::TooLarge("u128"));
+}
+
+#[test]
+fn corrupt_input() {
+    let config = Config::default();
+    let e = from_bytes::<(u8, bool)>(&[7, 2], config).unwrap_err();
+    assert_eq!((e.kind, e.offset), (ErrorKind::InvalidBool(2), 1));
+
+    let e = from_bytes::<char>(&0xd800u32.to_le_bytes(), config).unwrap_err();
+    assert_eq!(e.kind, ErrorKind::InvalidChar(0xd800));
+
+    let e = from_bytes::<u16>(&[1, 2, 3], config).unwrap_err();
+    assert_eq!((e.kind, e.offset), (ErrorKind::TrailingBytes(1), 2));
+
+    let e = from_bytes::<[u32; 2]>(&[0; 6], config).unwrap_err();
+    assert_eq!((e.kind, e.offset), (ErrorKind::UnexpectedEnd { needed: 2 }, 4));
+}
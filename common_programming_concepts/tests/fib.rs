@@ -0,0 +1,52 @@
+use common_programming_concepts::fib::{check, fast_doubling, iterative, Algorithm, Memo};
+use num_bigint::BigUint;
+
+#[test]
+fn known_values() {
+    let known: [(u64, &str); 6] = [
+        (0, "0"),
+        (1, "1"),
+        (2, "1"),
+        (10, "55"),
+        // the last one that fits in a u64, and the first that doesn't
+        (93, "12200160415121876738"),
+        (94, "19740274219868223167"),
+    ];
+    for &(n, value) in known.iter() {
+        for algo in Algorithm::ALL.iter() {
+            assert_eq!(algo.run(n).to_string(), value, "{} F({})", algo.name(), n);
+        }
+    }
+    assert!(iterative(93) <= BigUint::from(u64::MAX));
+    assert!(iterative(94) > BigUint::from(u64::MAX));
+    assert_eq!(fast_doubling(300).to_string(), "222232244629420445529739893461909967206666939096499764990979600");
+}
+
+#[test]
+fn algorithms_agree() {
+    for n in 0..=500 {
+        let value = iterative(n);
+        assert_eq!(fast_doubling(n), value, "F({})", n);
+        assert_eq!(Algorithm::Memoized.run(n), value, "F({})", n);
+    }
+    // one memo, asked out of order
+    let mut memo = Memo::new();
+    for &n in [400, 3, 0, 401, 250].iter() {
+        assert_eq!(*memo.get(n), iterative(n), "F({})", n);
+    }
+}
+
+#[test]
+fn cassini() {
+    for n in 0..200 {
+        assert!(check(n, &iterative(n)), "F({})", n);
+    }
+    for &n in [10_000, 123_457].iter() {
+        assert!(check(n, &fast_doubling(n)), "F({})", n);
+    }
+    // off by one, or a neighbour's value, fails
+    assert!(!check(50, &(iterative(50) + 1u32)));
+    assert!(!check(50, &iterative(51)));
+    assert!(!check(0, &BigUint::from(1u32)));
+    assert!(!check(10_001, &fast_doubling(10_000)));
+}
@@ -0,0 +1,105 @@
+use common_programming_concepts::float::{exact_decimal, rounding, Class, Float};
+use common_programming_concepts::literal::Type;
+
+#[test]
+fn bit_fields() {
+    let one = Float::from_f32(1.0);
+    assert_eq!((one.exponent_bits(), one.mantissa_bits(), one.bias()), (8, 23, 127));
+    assert_eq!((one.sign(), one.biased_exponent(), one.mantissa()), (false, 127, 0));
+    assert_eq!(one.exponent(), Some(0));
+    assert_eq!(one.class(), Class::Normal);
+    assert_eq!(one.bit_string(), "0 01111111 00000000000000000000000");
+
+    // 1.25 * 2^1, the .25 is the second mantissa bit from the top
+    let x = Float::from_f64(-2.5);
+    assert_eq!((x.exponent_bits(), x.mantissa_bits(), x.bias()), (11, 52, 1023));
+    assert_eq!((x.sign(), x.biased_exponent(), x.mantissa()), (true, 1024, 1 << 50));
+    assert_eq!(x.exponent(), Some(1));
+    assert_eq!(x.to_f64(), -2.5);
+    assert_eq!(x.to_string(), "-2.5");
+
+    assert_eq!(Float::from_f32(-0.0).class(), Class::Zero);
+    assert!(Float::from_f32(-0.0).sign());
+    assert_eq!(Float::from_bits(Type::F32, 0x3f80_0000), Some(one));
+    assert_eq!(Float::from_bits(Type::F32, 1 << 32), None);
+    assert_eq!(Float::from_bits(Type::I32, 0), None);
+}
+
+#[test]
+fn subnormals() {
+    let tiny = Float::from_f32(f32::from_bits(1));
+    assert_eq!(tiny.class(), Class::Subnormal);
+    assert_eq!((tiny.biased_exponent(), tiny.mantissa()), (0, 1));
+    // stuck at the smallest normal exponent, not 0 - bias
+    assert_eq!(tiny.exponent(), Some(-126));
+    assert_eq!(Float::from_f32(0.0).next_up(), tiny);
+    assert_eq!(Float::from_f32(f32::MIN_POSITIVE).class(), Class::Normal);
+    assert_eq!(Float::from_f32(f32::MIN_POSITIVE).next_down().class(), Class::Subnormal);
+
+    // 2^-149 has 149 decimal places and ends in a 5
+    let exact = tiny.exact().unwrap();
+    assert!(exact.starts_with("0.0000000000000000000000000000000000000000000014012984"), "{}", exact);
+    assert_eq!(exact.len() - 2, 149);
+    assert!(exact.ends_with('5'));
+
+    // f64's smallest, 2^-1074: 1074 places, 751 of them significant
+    let exact = exact_decimal(f64::from_bits(1)).unwrap();
+    assert_eq!(exact.len() - 2, 1074);
+    assert_eq!(exact.trim_start_matches(['0', '.']).len(), 751);
+}
+
+#[test]
+fn nan_and_infinity() {
+    let inf = Float::from_f64(f64::NEG_INFINITY);
+    assert_eq!(inf.class(), Class::Infinite);
+    assert!(inf.sign());
+    assert_eq!((inf.exponent(), inf.exact()), (None, None));
+    assert_eq!(Float::from_f32(f32::MAX).next_up().class(), Class::Infinite);
+    assert_eq!(Float::from_f32(f32::MAX).gap(Float::from_f32(f32::INFINITY)), None);
+
+    assert_eq!(Float::from_f32(f32::NAN).class(), Class::Nan { quiet: true, payload: 0 });
+    let signaling = Float::from_bits(Type::F32, 0x7f80_0001).unwrap();
+    assert_eq!(signaling.class(), Class::Nan { quiet: false, payload: 1 });
+    assert_eq!(signaling.class().to_string(), "signaling NaN, payload 0x1");
+    let quiet = Float::from_bits(Type::F64, 0x7ff8_0000_0000_002a).unwrap();
+    assert_eq!(quiet.class(), Class::Nan { quiet: true, payload: 42 });
+    assert_eq!((quiet.exponent(), quiet.exact()), (None, None));
+}
+
+#[test]
+fn exact_digits() {
+    assert_eq!(Float::from_f32(0.1).exact().unwrap(), "0.100000001490116119384765625");
+    assert_eq!(Float::from_f64(0.5).exact().unwrap(), "0.5");
+    assert_eq!(Float::from_f64(-3.0).exact().unwrap(), "-3");
+    assert_eq!(Float::from_f64(0.0).exact().unwrap(), "0");
+    assert_eq!(exact_decimal(2f64.powi(70)).unwrap(), "1180591620717411303424");
+    let one = Float::from_f32(1.0);
+    assert_eq!(one.next_up().gap(one).unwrap(), "0.00000011920928955078125");
+}
+
+#[test]
+fn rounding_report() {
+    let r = rounding("0.1").unwrap();
+    assert!(!r.same);
+    assert_eq!(r.f32, Float::from_f32(0.1));
+    assert_eq!(r.f64, Float::from_f64(0.1));
+    assert!((r.apart_in_f32_ulps - 0.2).abs() < 1e-6, "{}", r.apart_in_f32_ulps);
+
+    let r = rounding("1.5").unwrap();
+    assert!(r.same);
+    assert_eq!(r.apart_in_f32_ulps, 0.0);
+
+    // the next f32 up from MAX is infinity, so the ULP is the gap down
+    let r = rounding("3.4028235e38").unwrap();
+    assert_eq!(r.f32, Float::from_f32(f32::MAX));
+    assert!(!r.same);
+    assert!(r.apart_in_f32_ulps.is_finite(), "{}", r.apart_in_f32_ulps);
+    assert!(r.apart_in_f32_ulps < -0.1 && r.apart_in_f32_ulps > -0.5, "{}", r.apart_in_f32_ulps);
+
+    let r = rounding("1.17549435e-38").unwrap();
+    assert_eq!(r.f32, Float::from_f32(f32::MIN_POSITIVE));
+    assert!(r.apart_in_f32_ulps.abs() <= 0.5, "{}", r.apart_in_f32_ulps);
+
+    // too big for an f32
+    assert!(rounding("1e39").is_err());
+}
@@ -0,0 +1,134 @@
+use common_programming_concepts::interp::ast::Type;
+use common_programming_concepts::interp::eval::Value;
+use common_programming_concepts::interp::{Error, Session, Span};
+
+fn run(src: &str) -> Value {
+    match Session::new().run(src) {
+        Ok(Some((value, _))) => value,
+        other => panic!("{:?}: {:?}", src, other),
+    }
+}
+
+fn fail(src: &str) -> Error {
+    Session::new().run(src).unwrap_err()
+}
+
+// (code, message, the text under the span)
+fn error(src: &str) -> (Option<&'static str>, String, &str) {
+    let e = fail(src);
+    (e.code, e.message, &src[e.span.start..e.span.end])
+}
+
+#[test]
+fn precedence_and_associativity() {
+    assert_eq!(run("2 + 3 * 4"), Value::Int(14));
+    assert_eq!(run("(2 + 3) * 4"), Value::Int(20));
+    // left associative
+    assert_eq!(run("10 - 4 - 3"), Value::Int(3));
+    assert_eq!(run("100 / 10 / 5"), Value::Int(2));
+    assert_eq!(run("2 * 3 % 4"), Value::Int(2));
+    // unary binds tighter than anything
+    assert_eq!(run("-2 * 3"), Value::Int(-6));
+    assert_eq!(run("!true || true"), Value::Bool(true));
+    assert_eq!(run("true || false && false"), Value::Bool(true));
+    assert_eq!(run("1 + 1 == 2 && 3 > 2"), Value::Bool(true));
+    // rounds towards zero, like Rust
+    assert_eq!(run("-7 / 2"), Value::Int(-3));
+    assert_eq!(run("-7 % 2"), Value::Int(-1));
+    // comparisons don't chain
+    assert_eq!(error("1 < 2 < 3"), (None, "comparison operators cannot be chained".to_string(), "<"));
+}
+
+#[test]
+fn let_and_shadowing() {
+    assert_eq!(run("let x = 5; let x = x * 2; x"), Value::Int(10));
+    // a new let can change the type
+    let mut session = Session::new();
+    assert_eq!(session.run("let x = 5; let x = x > 3; x"), Ok(Some((Value::Bool(true), Type::Bool))));
+    // a shadow inside a block goes away with the block
+    assert_eq!(run("let x = 1; { let x = 2; } x"), Value::Int(1));
+    assert_eq!(run("let x: i32 = 7; x"), Value::Int(7));
+    assert_eq!(session.run("let y = 1;"), Ok(None));
+    assert_eq!(
+        error("let x: bool = 5;"),
+        (Some("E0308"), "mismatched types: expected `bool`, found `i32`".to_string(), "5")
+    );
+    assert_eq!(error("x + 1"), (Some("E0425"), "cannot find value `x` in this scope".to_string(), "x"));
+}
+
+#[test]
+fn assignment_needs_mut() {
+    assert_eq!(run("let mut x = 5; x += 2; x = x * 10; x"), Value::Int(70));
+    let e = fail("let x = 5; x = 6;");
+    assert_eq!(e.code, Some("E0384"));
+    assert_eq!(e.message, "cannot assign twice to immutable variable `x`");
+    assert_eq!(e.note.as_deref(), Some("declare it with `let mut x` to allow this"));
+    assert_eq!(error("let x = 5; x -= 1;").0, Some("E0384"));
+    // the shadow is immutable even if the first x wasn't
+    assert_eq!(error("let mut x = 5; let x = x; x = 1;").0, Some("E0384"));
+    assert_eq!(error("1 = 2"), (Some("E0070"), "invalid left-hand side of assignment".to_string(), "="));
+
+    // a failed input leaves the session as it was
+    let mut session = Session::new();
+    session.run("let mut n = 1;").unwrap();
+    assert!(session.run("n = 2; let m = 3; m = 4;").is_err());
+    assert_eq!(session.run("n"), Ok(Some((Value::Int(1), Type::I32))));
+    assert!(session.run("m").is_err());
+}
+
+#[test]
+fn blocks_and_ifs_are_expressions() {
+    assert_eq!(run("{ let y = 3; y + 1 }"), Value::Int(4));
+    assert_eq!(run("let a = { 1; 2 }; a"), Value::Int(2));
+    // a block ending in a statement is ()
+    assert_eq!(run("{ 1; }"), Value::Unit);
+    assert_eq!(error("{ let y = 3; } y").0, Some("E0425"));
+
+    assert_eq!(run("let n = 3; if n < 5 { 10 } else { 20 }"), Value::Int(10));
+    assert_eq!(run("let n = 7; let b = if n < 5 { 1 } else if n < 10 { 2 } else { 3 }; b"), Value::Int(2));
+    assert_eq!(run("loop { break 7 }"), Value::Int(7));
+    assert_eq!(run("let mut n = 0; while n < 5 { n += 1 } n"), Value::Int(5));
+
+    let (code, message, under) = error("if true { 1 } else { false }");
+    assert_eq!((code, message.as_str(), under), (Some("E0308"), "`if` and `else` have incompatible types", "{ false }"));
+    assert_eq!(error("if true { 1 }").0, Some("E0317"));
+    // no truthiness
+    assert_eq!(
+        error("if 1 { 2 } else { 3 }"),
+        (Some("E0308"), "mismatched types: expected `bool`, found `i32`".to_string(), "1")
+    );
+}
+
+#[test]
+fn runtime_panics() {
+    assert_eq!(error("10 / 0"), (None, "panicked: attempt to divide by zero".to_string(), "10 / 0"));
+    assert_eq!(
+        error("10 % 0").1,
+        "panicked: attempt to calculate the remainder with a divisor of zero"
+    );
+    // the checker can't see it coming when the zero is in a variable
+    assert_eq!(error("let z = 0; 7 / z").2, "7 / z");
+    assert_eq!(error("2147483647 + 1").1, "panicked: attempt to add with overflow");
+    assert_eq!(error("-2147483648 / -1").1, "panicked: attempt to divide with overflow");
+}
+
+#[test]
+fn parse_error_positions() {
+    assert_eq!(fail("let x = ;").span, Span::new(8, 9));
+    assert_eq!(fail("let x = ;").message, "expected expression, found `;`");
+    // at the very end, an empty span
+    assert_eq!(fail("1 +").span, Span::new(3, 3));
+    assert_eq!(fail("5 $ 3").message, "unknown start of token: $");
+    assert_eq!(fail("5 $ 3").span, Span::new(2, 3));
+
+    // a missing ; is reported at the next token, on the next line
+    let src = "let x = 5\nlet y = 6;";
+    let e = fail(src);
+    assert_eq!((e.message.as_str(), e.span), ("expected `;`, found `let`", Span::new(10, 13)));
+    let rendered = e.render(src);
+    assert!(rendered.starts_with("error: expected `;`, found `let`\n --> line 2:1\n"), "{}", rendered);
+    assert!(rendered.ends_with("2 | let y = 6;\n  | ^^^"), "{}", rendered);
+
+    let e = fail("let x = (let y = 6);");
+    assert_eq!((e.message.as_str(), e.span), ("expected expression, found `let` statement", Span::new(9, 12)));
+}
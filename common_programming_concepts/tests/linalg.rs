@@ -0,0 +1,75 @@
+use common_programming_concepts::linalg::{Matrix, Vector};
+
+#[test]
+fn vectors() {
+    let a = Vector::new([1, 2, 3]);
+    let b = Vector::new([4, 5, 6]);
+    assert_eq!(a + b, Vector::new([5, 7, 9]));
+    assert_eq!(b - a, Vector::new([3, 3, 3]));
+    assert_eq!(-a, Vector::new([-1, -2, -3]));
+    assert_eq!(a * 2, 2 * a);
+    assert_eq!(a.dot(b), 32);
+    assert_eq!(a.cross(b), Vector::new([-3, 6, -3]));
+    assert_eq!(a.cross(b).dot(a), 0);
+
+    let v = Vector::new([3.0, -4.0]);
+    assert_eq!(v.norm(), 5.0);
+    assert_eq!(v.normalize(), Some(Vector::new([0.6, -0.8])));
+    assert_eq!(Vector::<f64, 2>::zero().normalize(), None);
+}
+
+#[test]
+fn norms_at_the_edges() {
+    let v = Vector::new([3i8, -4, 2]);
+    assert_eq!(v.norm_l1(), Some(9));
+    assert_eq!(v.norm_max(), Some(4));
+
+    // |MIN| doesn't fit, and neither does a sum past MAX
+    assert_eq!(Vector::new([i8::MIN, 1]).norm_max(), None);
+    assert_eq!(Vector::new([i8::MIN, 1]).norm_l1(), None);
+    assert_eq!(Vector::new([i8::MAX, 1]).norm_l1(), None);
+    assert_eq!(Vector::new([i8::MIN + 1, 0]).norm_max(), Some(i8::MAX));
+    assert_eq!(Vector::new([i64::MIN]).norm_max(), None);
+    assert_eq!(Vector::new([-2.5f32, 1.0]).norm_max(), Some(2.5));
+}
+
+#[test]
+fn matrices() {
+    let a = Matrix::new([[1, 2, 3], [4, 5, 6]]);
+    let b = a.transpose();
+    assert_eq!(b, Matrix::new([[1, 4], [2, 5], [3, 6]]));
+    assert_eq!(a * b, Matrix::new([[14, 32], [32, 77]]));
+    assert_eq!(a * Vector::new([1, 0, -1]), Vector::new([-2, -2]));
+    assert_eq!(Matrix::<i32, 3, 3>::identity() * b, b);
+    assert_eq!(a.row(1), Vector::new([4, 5, 6]));
+    assert_eq!(a.col(2), Vector::new([3, 6]));
+    assert_eq!(a[(1, 0)], 4);
+    assert_eq!(a.to_string(), "[1 2 3]\n[4 5 6]");
+}
+
+#[test]
+fn determinants() {
+    assert_eq!(Matrix::new([[1, 2], [3, 4]]).determinant(), Some(-2));
+    assert_eq!(Matrix::new([[2, 0, 1], [1, 3, 2], [1, 1, 2]]).determinant(), Some(6));
+    assert_eq!(Matrix::<i32, 4, 4>::identity().determinant(), Some(1));
+    assert_eq!(Matrix::<i32, 0, 0>::new([]).determinant(), Some(1));
+    // a zero pivot needs a row swap, which flips the sign
+    assert_eq!(Matrix::new([[0, 1], [1, 0]]).determinant(), Some(-1));
+    assert_eq!(Matrix::new([[1, 2], [2, 4]]).determinant(), Some(0));
+    assert_eq!(Matrix::new([[1.0, 2.0], [3.0, 4.0]]).determinant(), Some(-2.0));
+}
+
+#[test]
+fn determinants_of_small_ints() {
+    // 16 * 14 and 15 * 15 don't fit in an i8, the answer does
+    assert_eq!(Matrix::new([[16i8, 15], [15, 14]]).determinant(), Some(-1));
+    assert_eq!(
+        Matrix::new([[100i8, 99, 98], [99, 100, 99], [98, 100, 99]]).determinant(),
+        Some(-1)
+    );
+    assert_eq!(Matrix::new([[300i16, 299], [299, 298]]).determinant(), Some(-1));
+    // and when the answer itself doesn't fit
+    assert_eq!(Matrix::new([[100i8, 0], [0, 100]]).determinant(), None);
+    assert_eq!(Matrix::new([[i8::MIN, 0], [0, -1]]).determinant(), None);
+    assert_eq!(Matrix::new([[i128::MAX, 1], [1, i128::MAX]]).determinant(), None);
+}
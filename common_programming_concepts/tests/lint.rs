@@ -0,0 +1,127 @@
+use common_programming_concepts::lint::{lint, Finding, Kind};
+
+// (line, column, kind) of each finding
+fn found(source: &str) -> Vec<(usize, usize, Kind)> {
+    lint(source).unwrap().iter().map(|f| (f.line, f.column, f.kind)).collect()
+}
+
+const UNUSED_MUT: &str = "\
+fn main() {
+    let mut x = 5;
+    let mut name = String::from(\"ferris\");
+    println!(\"{} {}\", x, name.len());
+}
+";
+
+// every one of these is changed, some only the ways the lint has to guess
+const USED_MUT: &str = "\
+use std::fmt::Write;
+
+fn main() {
+    let mut x = 5;
+    x += 1;
+    let mut v = Vec::new();
+    v.push(1);
+    let mut s = String::new();
+    change(&mut s);
+    let mut out = String::new();
+    write!(out, \"{}\", x).unwrap();
+    let mut total = 0;
+    for n in &v {
+        total = total + n;
+    }
+    println!(\"{:?} {} {} {}\", v, s, out, total);
+}
+
+fn change(s: &mut String) {
+    s.push('!');
+}
+";
+
+#[test]
+fn unused_mut() {
+    assert_eq!(found(UNUSED_MUT), [(2, 13, Kind::UnusedMut), (3, 13, Kind::UnusedMut)]);
+    let findings = lint(UNUSED_MUT).unwrap();
+    assert_eq!(findings[0].message, "`x` is declared `mut` but never changed, drop the `mut`");
+    assert_eq!(found(USED_MUT), []);
+}
+
+const SHADOW_TYPE: &str = "\
+fn main() {
+    let spaces = \"   \";
+    let spaces = spaces.len();
+    let guess = String::new();
+    let guess: u32 = guess.trim().parse().unwrap();
+    println!(\"{} {}\", spaces, guess);
+}
+";
+
+// same type each time, or a type it can't tell
+const SHADOW_SAME_TYPE: &str = "\
+fn main() {
+    let x = 5;
+    let x = x + 1;
+    let x = x * 2;
+    let s = String::from(\"a\");
+    let s = s.to_uppercase();
+    let n = some_call();
+    let n = n.len();
+    println!(\"{} {} {}\", x, s, n);
+}
+";
+
+#[test]
+fn shadow_type() {
+    let findings = lint(SHADOW_TYPE).unwrap();
+    assert_eq!(
+        findings,
+        [
+            Finding {
+                line: 3,
+                column: 9,
+                kind: Kind::ShadowType,
+                message: "`spaces` shadows the `spaces` from line 2 and changes its type from `&str` to `usize`".to_string(),
+            },
+            Finding {
+                line: 5,
+                column: 9,
+                kind: Kind::ShadowType,
+                message: "`guess` shadows the `guess` from line 4 and changes its type from `String` to `u32`".to_string(),
+            },
+        ]
+    );
+    assert_eq!(found(SHADOW_SAME_TYPE), []);
+}
+
+const INLINE_CONST: &str = "\
+const MAX: u32 = 100;
+const UNUSED: u32 = 1;
+
+fn main() {
+    println!(\"{}\", MAX);
+}
+";
+
+// used twice, or public so other code may use it
+const KEPT_CONST: &str = "\
+const MAX: u32 = 100;
+pub const PUBLIC: u32 = 2;
+
+fn main() {
+    println!(\"{} {}\", MAX, MAX * 2);
+}
+";
+
+#[test]
+fn inline_const() {
+    assert_eq!(found(INLINE_CONST), [(1, 7, Kind::InlineConst), (2, 7, Kind::InlineConst)]);
+    let findings = lint(INLINE_CONST).unwrap();
+    assert!(findings[0].message.starts_with("const `MAX` is used only once"), "{}", findings[0].message);
+    assert_eq!(findings[1].message, "const `UNUSED` is never used");
+    assert_eq!(found(KEPT_CONST), []);
+}
+
+#[test]
+fn not_rust() {
+    assert!(lint("fn main() { let x = ; }").is_err());
+}
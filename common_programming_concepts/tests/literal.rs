@@ -0,0 +1,104 @@
+use common_programming_concepts::literal::{parse, Error, ErrorKind, Literal, Type};
+
+fn error(src: &str) -> (ErrorKind, usize) {
+    let Error { kind, pos } = parse(src).unwrap_err();
+    (kind, pos)
+}
+
+#[test]
+fn integers() {
+    assert_eq!(parse("0xff_u8"), Ok(Literal::U8(255)));
+    assert_eq!(parse("-128i8"), Ok(Literal::I8(-128)));
+    assert_eq!(parse("127i8"), Ok(Literal::I8(127)));
+    // unsuffixed is i32, like rustc's fallback
+    assert_eq!(parse("1_000"), Ok(Literal::I32(1000)));
+    assert_eq!(parse("0o17"), Ok(Literal::I32(15)));
+    assert_eq!(parse("0b1010_1010u8"), Ok(Literal::U8(0xaa)));
+    // f32 is hex digits here, not a suffix
+    assert_eq!(parse("0x1f32"), Ok(Literal::I32(0x1f32)));
+    assert_eq!(parse("340282366920938463463374607431768211455u128"), Ok(Literal::U128(u128::MAX)));
+    assert_eq!(parse("-170141183460469231731687303715884105728i128"), Ok(Literal::I128(i128::MIN)));
+    assert_eq!(parse("0xff_u8").unwrap().to_string(), "255u8");
+}
+
+#[test]
+fn floats() {
+    assert_eq!(parse("1.5"), Ok(Literal::F64(1.5)));
+    assert_eq!(parse("1."), Ok(Literal::F64(1.0)));
+    assert_eq!(parse("1f32"), Ok(Literal::F32(1.0)));
+    assert_eq!(parse("2.5E-1_f32"), Ok(Literal::F32(0.25)));
+    // underscores can go anywhere in the exponent, as long as there's a digit
+    assert_eq!(parse("1e_3"), Ok(Literal::F64(1000.0)));
+    assert_eq!(parse("1_0.0_1e+0_1"), Ok(Literal::F64(100.1)));
+    assert_eq!(parse("1.0").unwrap().to_string(), "1.0f64");
+}
+
+#[test]
+fn out_of_range() {
+    let (kind, pos) = error("128i8");
+    let fits = vec![
+        Type::I16, Type::I32, Type::I64, Type::I128, Type::Isize,
+        Type::U8, Type::U16, Type::U32, Type::U64, Type::U128, Type::Usize,
+    ];
+    assert_eq!((kind, pos), (ErrorKind::OutOfRange { ty: Type::I8, fits }, 0));
+    assert_eq!(
+        parse("128i8").unwrap_err().to_string(),
+        "literal out of range for `i8`, it would fit in i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize (at byte 0)"
+    );
+
+    let fits = vec![Type::I16, Type::I32, Type::I64, Type::I128, Type::Isize];
+    assert_eq!(error("-129i8"), (ErrorKind::OutOfRange { ty: Type::I8, fits }, 0));
+    let fits = vec![
+        Type::I16, Type::I32, Type::I64, Type::I128, Type::Isize,
+        Type::U16, Type::U32, Type::U64, Type::U128, Type::Usize,
+    ];
+    assert_eq!(error("256u8"), (ErrorKind::OutOfRange { ty: Type::U8, fits }, 0));
+
+    // too big for u128, the lexer itself gives up
+    assert_eq!(error("340282366920938463463374607431768211456"), (ErrorKind::TooLarge, 0));
+    assert_eq!(error("0x1_0000_0000_0000_0000_0000_0000_0000_0000u128"), (ErrorKind::TooLarge, 2));
+    // fits u128 but not the default i32
+    assert_eq!(
+        error("340282366920938463463374607431768211455"),
+        (ErrorKind::OutOfRange { ty: Type::I32, fits: vec![Type::U128] }, 0)
+    );
+
+    assert_eq!(error("1e39f32"), (ErrorKind::OutOfRange { ty: Type::F32, fits: vec![Type::F64] }, 0));
+    assert_eq!(error("1e309"), (ErrorKind::OutOfRange { ty: Type::F64, fits: vec![] }, 0));
+    assert_eq!(error("-1u8"), (ErrorKind::NegativeUnsigned(Type::U8), 0));
+}
+
+#[test]
+fn malformed() {
+    assert_eq!(error(""), (ErrorKind::Empty, 0));
+    assert_eq!(error("_1"), (ErrorKind::NotANumber, 0));
+    assert_eq!(error("-"), (ErrorKind::NotANumber, 1));
+    assert_eq!(error("0x"), (ErrorKind::NoDigits, 2));
+    assert_eq!(error("0b_"), (ErrorKind::NoDigits, 2));
+    assert_eq!(error("0b102"), (ErrorKind::InvalidDigit { digit: '2', base: 2 }, 4));
+    assert_eq!(error("0o8"), (ErrorKind::InvalidDigit { digit: '8', base: 8 }, 2));
+    // rustc reads these as a field access on 1
+    assert_eq!(error("1.e3"), (ErrorKind::DigitExpectedAfterDot, 2));
+    assert_eq!(error("1._5"), (ErrorKind::DigitExpectedAfterDot, 2));
+    assert_eq!(error("1e"), (ErrorKind::EmptyExponent, 2));
+    assert_eq!(error("1e+_"), (ErrorKind::EmptyExponent, 3));
+    assert_eq!(error("1.0u8"), (ErrorKind::IntSuffixOnFloat(Type::U8), 3));
+    assert_eq!(error("1e3i32"), (ErrorKind::IntSuffixOnFloat(Type::I32), 3));
+    assert_eq!(error("0b1f32"), (ErrorKind::FloatWithBase(2), 3));
+    assert_eq!(error("0x1.5"), (ErrorKind::FloatWithBase(16), 3));
+    assert_eq!(error("1u7"), (ErrorKind::InvalidSuffix("u7".to_string()), 1));
+    // only lowercase prefixes
+    assert_eq!(error("0X1"), (ErrorKind::InvalidSuffix("X1".to_string()), 1));
+}
+
+#[test]
+fn fits() {
+    let fitting = |lit: Literal| -> Vec<Type> { lit.fits().into_iter().filter(|&(_, f)| f).map(|(t, _)| t).collect() };
+    assert_eq!(fitting(Literal::I32(-1)), [Type::I8, Type::I16, Type::I32, Type::I64, Type::I128, Type::Isize]);
+    assert_eq!(fitting(Literal::U16(300)).len(), 10);
+    assert_eq!(fitting(Literal::F64(3.0)).len(), 12);
+    assert_eq!(fitting(Literal::F64(-0.0)).len(), 12);
+    assert!(fitting(Literal::F64(1.5)).is_empty());
+    assert!(fitting(Literal::F64(f64::NAN)).is_empty());
+    assert_eq!(fitting(Literal::F64(2f64.powi(127))), [Type::U128]);
+}
@@ -0,0 +1,110 @@
+use common_programming_concepts::units::{lookup, parse_query, Error, Format, Precision, Rounding};
+
+fn query(text: &str) -> (f64, &'static str, &'static str) {
+    let q = parse_query(text).unwrap_or_else(|e| panic!("{:?}: {}", text, e));
+    (q.value, q.from.name, q.to.name)
+}
+
+#[test]
+fn parse() {
+    assert_eq!(query("72 F to C"), (72.0, "degree Fahrenheit", "degree Celsius"));
+    assert_eq!(query("72F in C"), (72.0, "degree Fahrenheit", "degree Celsius"));
+    assert_eq!(query("-40°C as F"), (-40.0, "degree Celsius", "degree Fahrenheit"));
+    assert_eq!(query("1.5 km -> mi"), (1.5, "kilometre", "mile"));
+    assert_eq!(query("1e3 m to km"), (1000.0, "metre", "kilometre"));
+    assert_eq!(query("1_000 metres to feet"), (1000.0, "metre", "foot"));
+}
+
+#[test]
+fn parse_inches() {
+    // `in` is a unit and a connector
+    assert_eq!(query("5 cm to in"), (5.0, "centimetre", "inch"));
+    assert_eq!(query("12 cm in in"), (12.0, "centimetre", "inch"));
+    assert_eq!(query("5 in in cm"), (5.0, "inch", "centimetre"));
+    assert_eq!(query("5in to in"), (5.0, "inch", "inch"));
+    let v = parse_query("5 cm to in").unwrap().run().unwrap();
+    assert!((v - 5.0 / 2.54).abs() < 1e-12, "{}", v);
+}
+
+#[test]
+fn plurals() {
+    let name = |text: &str| lookup(text).map(|u| u.name);
+    assert_eq!(name("metres"), Some("metre"));
+    assert_eq!(name("Hours"), Some("hour"));
+    // the plurals of symbols and other short forms
+    assert_eq!(name("mins"), Some("minute"));
+    assert_eq!(name("hrs"), Some("hour"));
+    assert_eq!(name("Hrs"), Some("hour"));
+    assert_eq!(name("secs"), Some("second"));
+    assert_eq!(name("yrs"), Some("year"));
+    assert_eq!(name("wks"), Some("week"));
+    assert_eq!(name("cups"), Some("US cup"));
+    assert_eq!(name("lbs"), Some("pound"));
+    // a one letter symbol isn't made plural
+    assert_eq!(name("ds"), None);
+    assert_eq!(name("hs"), None);
+    assert_eq!(query("90 mins to hrs"), (90.0, "minute", "hour"));
+    assert_eq!(parse_query("90 mins to hrs").unwrap().run(), Ok(1.5));
+}
+
+#[test]
+fn parse_errors() {
+    for text in &["", "5", "5 cm", "5 cm to", "5 to in", "5 cm in in in", "5 cm mm", "5 cm to in mm"] {
+        assert_eq!(parse_query(text), Err(Error::Syntax(text.to_string())), "{:?}", text);
+    }
+    assert_eq!(parse_query("five m to ft"), Err(Error::NotANumber("five".to_string())));
+    assert_eq!(parse_query("5 m to parsec"), Err(Error::UnknownUnit("parsec".to_string())));
+    let mismatch = parse_query("5 m to kg").unwrap().run().unwrap_err();
+    assert!(matches!(mismatch, Error::Mismatch { .. }), "{:?}", mismatch);
+    assert_eq!(parse_query("-500 C to K").unwrap().run(), Err(Error::BelowAbsoluteZero));
+    assert!(lookup("Mm").is_none());
+}
+
+fn format(precision: Precision, rounding: Rounding) -> Format {
+    Format { precision, rounding }
+}
+
+#[test]
+fn rounding_modes() {
+    let decimals = |rounding| format(Precision::Decimals(0), rounding);
+    let cases = [
+        (Rounding::HalfAwayFromZero, [3.0, -3.0, 4.0, 3.0]),
+        (Rounding::HalfEven, [2.0, -2.0, 4.0, 3.0]),
+        (Rounding::TowardZero, [2.0, -2.0, 3.0, 2.0]),
+        (Rounding::Floor, [2.0, -3.0, 3.0, 2.0]),
+        (Rounding::Ceil, [3.0, -2.0, 4.0, 3.0]),
+    ];
+    for (rounding, expected) in cases.iter() {
+        let got: Vec<f64> = [2.5, -2.5, 3.5, 2.9].iter().map(|&v| decimals(*rounding).round(v)).collect();
+        assert_eq!(got, expected, "{:?}", rounding);
+    }
+    assert_eq!("half-even".parse(), Ok(Rounding::HalfEven));
+    assert!("sideways".parse::<Rounding>().is_err());
+}
+
+#[test]
+fn precision() {
+    let half = Rounding::HalfAwayFromZero;
+    assert_eq!(format(Precision::Decimals(2), half).format(1.0 / 3.0), "0.33");
+    assert_eq!(format(Precision::Decimals(2), half).format(-0.001), "0.00");
+    assert_eq!(format(Precision::Significant(3), half).format(123_456.0), "123000");
+    assert_eq!(format(Precision::Significant(3), half).format(0.000_123_456), "0.000123");
+    assert_eq!(format(Precision::Significant(2), half).round(f64::INFINITY), f64::INFINITY);
+}
+
+#[test]
+fn precision_out_of_range() {
+    let half = Rounding::HalfAwayFromZero;
+    // clamped to 17 digits, not inf or wrapped around
+    for &n in &[18, 400, 3_000_000_000, u32::MAX] {
+        let f = format(Precision::Decimals(n), half);
+        assert_eq!(f.round(1.25), 1.25, "{}", n);
+        assert_eq!(f.format(1000.0), format!("1000.{}", "0".repeat(17)), "{}", n);
+        let f = format(Precision::Significant(n), half);
+        assert_eq!(f.round(1.25), 1.25, "{}", n);
+    }
+    assert_eq!(format(Precision::Significant(0), half).round(1.25), 1.0);
+    // near the smallest f64 there's nothing left to round
+    let tiny = f64::MIN_POSITIVE / 1e10;
+    assert_eq!(format(Precision::Significant(17), half).round(tiny), tiny);
+}
@@ -0,0 +1,106 @@
+// the word list for word mode. every word is stored normalized
+// and sorted, so looking one up is a binary search and comparing
+// two words is just comparing two Strings
+
+use std::fs;
+use std::io;
+use std::path::Path;
+use rand::Rng;
+use unicode_normalization::UnicodeNormalization;
+use unicode_normalization::char::is_combining_mark;
+
+// baked into the binary so word mode works without any files
+const BUNDLED: &str = include_str!("../words.txt");
+
+// how far (in edits) a suggestion may be from what was typed
+const MAX_DISTANCE: usize = 2;
+
+#[derive(Debug)]
+pub struct Dictionary {
+    words: Vec<String>,
+}
+
+impl Dictionary {
+    pub fn bundled() -> Dictionary {
+        Dictionary::parse(BUNDLED)
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Dictionary> {
+        let dictionary = Dictionary::parse(&fs::read_to_string(&path)?);
+        if dictionary.words.is_empty() {
+            let msg = format!("{}: no words found", path.as_ref().display());
+            return Err(io::Error::new(io::ErrorKind::InvalidData, msg));
+        }
+        Ok(dictionary)
+    }
+
+    // one word per line, blank lines and # comments are skipped
+    pub fn parse(text: &str) -> Dictionary {
+        let mut words: Vec<String> = text
+            .lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty() && !l.starts_with('#'))
+            .map(normalize)
+            .collect();
+        words.sort();
+        words.dedup();
+        Dictionary { words }
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    // expects an already normalized word
+    pub fn contains(&self, word: &str) -> bool {
+        self.words.binary_search_by(|w| w.as_str().cmp(word)).is_ok()
+    }
+
+    pub fn random(&self) -> &str {
+        let i = rand::thread_rng().gen_range(0, self.words.len());
+        &self.words[i]
+    }
+
+    // the closest words by edit distance, closest first
+    pub fn suggest(&self, word: &str, max: usize) -> Vec<&str> {
+        let mut found: Vec<(usize, &str)> = self
+            .words
+            .iter()
+            .map(|w| (distance(word, w), w.as_str()))
+            .filter(|&(d, _)| d <= MAX_DISTANCE)
+            .collect();
+        found.sort();
+        found.into_iter().take(max).map(|(_, w)| w).collect()
+    }
+}
+
+// "  Crème " -> "creme". decomposing first turns "è" into
+// "e" + a combining grave accent, which we can then drop
+pub fn normalize(word: &str) -> String {
+    word.trim()
+        .nfd()
+        .filter(|&c| !is_combining_mark(c))
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+// levenshtein distance, counted in chars not bytes
+fn distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut curr = vec![0; b.len() + 1];
+
+    for (i, ca) in a.chars().enumerate() {
+        curr[0] = i + 1;
+        for (j, &cb) in b.iter().enumerate() {
+            let cost = if ca == cb { 0 } else { 1 };
+            curr[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(curr[j] + 1);
+        }
+        std::mem::swap(&mut prev, &mut curr);
+    }
+    prev[b.len()]
+}
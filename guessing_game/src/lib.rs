@@ -0,0 +1,4 @@
+// the game itself lives in main.rs. the parts it's built from live
+// here so they can be tested on their own
+pub mod dictionary;
+pub mod profile;
@@ -1,17 +1,159 @@
 use std::io;
+use std::env;
+use std::process;
 use rand::Rng;
 use std::cmp::Ordering;
 
+use guessing_game::dictionary::{self, Dictionary};
+use guessing_game::profile::{self, Game, Profile};
+
 fn main() {
+    let mut name = None;
+    let mut stats = false;
+    let mut words = false;
+    let mut dict = None;
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--profile" => match args.next() {
+                Some(n) => name = Some(n),
+                None => usage(),
+            },
+            "--dict" => match args.next() {
+                Some(path) => dict = Some(path),
+                None => usage(),
+            },
+            "stats" => stats = true,
+            "words" => words = true,
+            _ => usage(),
+        }
+    }
+
+    // each mode has its own flags, a flag for the other one is a mistake
+    // rather than something to quietly ignore
+    if words && (name.is_some() || stats) || !words && dict.is_some() {
+        usage();
+    }
+
+    if words {
+        let dictionary = match dict {
+            Some(path) => Dictionary::load(path).unwrap_or_else(|e| fail(e)),
+            None => Dictionary::bundled(),
+        };
+        play_words(&dictionary);
+        return;
+    }
+
+    let name = name.unwrap_or_else(|| String::from("player"));
+    let mut profile = Profile::load(&name).unwrap_or_else(|e| fail(e));
+    if stats {
+        print_stats(&profile);
+        return;
+    }
+
+    let game = play(&profile);
+    profile.record(game);
+    profile.save().unwrap_or_else(|e| fail(e));
+}
+
+fn play(profile: &Profile) -> Game {
+    let difficulty = profile.next_difficulty();
+
     println!("----------------");
     println!("Guess the number");
     println!("----------------\n");
-    
+    println!("Hi {}! This time it's {}\n", profile.name, difficulty);
+
     // able to infer that secret_number is a type of integer
-    let secret_number = rand::thread_rng().gen_range(1, 6);
+    let secret_number = rand::thread_rng().gen_range(1, difficulty.range + 1);
+
+    let prompt = format!("Please input your guess (between 1-{} inclusive)", difficulty.range);
+    let (guesses, won) = guess_loop(
+        &secret_number,
+        Some(difficulty.limit),
+        &prompt,
+        ("Too big!", "Too small!"),
+        |guess| {
+            // convert to integer. Rust allow you to "shadow" guess
+            // with a new one. this is to allow you to change the type of
+            // guess instead of creating a new one perhaps called guess_str
+            let guess: u32 = guess.trim().parse().map_err(|_| String::from("Not a number!"))?;
+            Ok(guess)
+        },
+    );
+
+    if won {
+        println!("You win! The secret number is {}", secret_number);
+    } else {
+        println!("You lose! The secret number is {}", secret_number);
+    }
+    Game {
+        range: difficulty.range,
+        limit: difficulty.limit,
+        guesses,
+        won,
+    }
+}
+
+fn play_words(dictionary: &Dictionary) {
+    println!("--------------");
+    println!("Guess the word");
+    println!("--------------\n");
 
-    loop {
-        println!("Please input your guess (between 1-5 inclusive)");
+    // String implements Ord too, so the same match on
+    // guess.cmp(&secret) works. it's just dictionary order
+    let secret_word = dictionary.random().to_string();
+    println!("I picked one of {} words", dictionary.len());
+
+    let (guesses, won) = guess_loop(
+        &secret_word,
+        None,
+        "Please input your guess",
+        ("It comes before that!", "It comes after that!"),
+        |guess| {
+            let guess = dictionary::normalize(guess);
+            if dictionary.contains(&guess) {
+                return Ok(guess);
+            }
+            let suggestions = dictionary.suggest(&guess, 3);
+            if suggestions.is_empty() {
+                Err(String::from("Not a word!"))
+            } else {
+                Err(format!("Not a word! Did you mean: {}?", suggestions.join(", ")))
+            }
+        },
+    );
+
+    if won {
+        println!("You win in {} guesses! The secret word is {}", guesses, secret_word);
+    } else {
+        println!("You give up! The secret word is {}", secret_word);
+    }
+}
+
+// the heart of both games. anything that can be ordered can be
+// guessed: parse turns a line into a guess (or says why it can't)
+// and hints are what to say when the guess is too big or too small.
+// returns the number of guesses made and whether the player won
+fn guess_loop<T, F>(
+    secret: &T,
+    limit: Option<u32>,
+    prompt: &str,
+    hints: (&str, &str),
+    parse: F,
+) -> (u32, bool)
+where
+    T: Ord,
+    F: Fn(&str) -> Result<T, String>,
+{
+    let mut guesses = 0;
+
+    while limit.is_none_or(|limit| guesses < limit) {
+        match limit {
+            Some(limit) => println!("{} ({} attempts left)", prompt, limit - guesses),
+            None => println!("{}", prompt),
+        }
 
         // new, like in Java is a static method.
         // in Rust it is called a associated function.
@@ -20,29 +162,65 @@ fn main() {
         let mut guess = String::new();
 
         // if not for line #1, this would have been std::io::stdin()
-        io::stdin() 
+        let read = io::stdin()
             .read_line(&mut guess)
             .expect("Failed to read line");
+        if read == 0 {
+            break; // stdin closed, count it as giving up
+        }
         print!("You guessed: {}", guess);
 
-        // convert to integer. Rust allow you to "shadow" guess
-        // with a new one. this is to allow you to change the type of
-        // guess instead of creating a new one perhaps called guess_str
-        let guess:u32 = match guess.trim().parse() {
-            Ok(num) => num,
-            Err(_) => {
-                println!("Not a number!");
+        let guess = match parse(&guess) {
+            Ok(guess) => guess,
+            Err(msg) => {
+                println!("{}", msg);
                 continue;
-            }, 
+            },
         };
+        guesses += 1;
 
-        match guess.cmp(&secret_number) {
-            Ordering::Greater => println!("Too big!"),
-            Ordering::Less => println!("Too small!"),
-            Ordering::Equal => {
-                println!("You win! The secret number is {}", secret_number);
-                break;
-            }
+        match guess.cmp(secret) {
+            Ordering::Greater => println!("{}", hints.0),
+            Ordering::Less => println!("{}", hints.1),
+            Ordering::Equal => return (guesses, true),
         }
     }
+    (guesses, false)
+}
+
+fn print_stats(profile: &Profile) {
+    println!("profile: {}", profile.name);
+    println!("target:  {} guesses per game\n", profile::TARGET_GUESSES);
+
+    if profile.games.is_empty() {
+        println!("no games yet");
+    } else {
+        println!("{:>4} {:>9} {:>6} {:>8} {:>5} {:>7} {:>6}",
+            "#", "range", "limit", "guesses", "won", "effort", "skill");
+        let skills = profile.skill_history();
+        for (i, (g, skill)) in profile.games.iter().zip(skills).enumerate() {
+            println!("{:>4} {:>9} {:>6} {:>8} {:>5} {:>7.2} {:>6.2}",
+                i + 1, g.range, g.limit, g.guesses,
+                if g.won { "yes" } else { "no" }, g.effort(), skill);
+        }
+    }
+
+    // the adjustment curve: what the next game would be for a
+    // given skill. lower is better, 1.0 plays like binary search
+    println!("\nskill -> next game");
+    for &skill in &[0.5, 0.75, 1.0, 1.25, 1.5, 2.0, 3.0] {
+        println!("{:>5.2} -> {}", skill, profile::difficulty_for(skill));
+    }
+    println!("\ncurrent skill {:.2}, next game is {}",
+        profile.skill(), profile.next_difficulty());
+}
+
+fn usage() -> ! {
+    eprintln!("usage: guessing_game [--profile NAME] [stats]\n       guessing_game words [--dict PATH]");
+    process::exit(2);
+}
+
+fn fail(e: io::Error) -> ! {
+    eprintln!("error: {}", e);
+    process::exit(1);
 }
@@ -0,0 +1,208 @@
+// a profile is just the list of games a player has finished.
+// it is kept as a plain text file, one game per line:
+//
+//   <range> <limit> <guesses> <won>
+//
+// e.g. "16 8 4 1" means the secret was between 1-16, the player
+// was allowed 8 attempts, needed 4 and won.
+
+use std::env;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+// the number of guesses we want a game to take on average
+pub const TARGET_GUESSES: f64 = 5.0;
+
+// how much the latest game counts when updating the skill.
+// the rest comes from the games before it
+const SMOOTHING: f64 = 0.3;
+
+// keep the range sane even for very lucky or very unlucky players.
+// the skill's floor also caps the range: 2^(5 / 0.4 - 1) is 2896
+const MIN_RANGE: u32 = 5;
+const MIN_SKILL: f64 = 0.4;
+const MAX_SKILL: f64 = 4.0;
+
+#[derive(Debug, Clone, Copy)]
+pub struct Game {
+    pub range: u32,
+    pub limit: u32,
+    pub guesses: u32,
+    pub won: bool,
+}
+
+impl Game {
+    // guesses used compared to a perfect binary search over the
+    // same range. 1.0 means "plays like binary search", higher is worse.
+    // a lost game counts as one guess more than the limit. the limit
+    // comes from a file anyone can edit, so it may already be u32::MAX
+    pub fn effort(&self) -> f64 {
+        let guesses = if self.won { self.guesses } else { self.limit.saturating_add(1) };
+        f64::from(guesses) / ideal_guesses(self.range)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Difficulty {
+    pub range: u32,
+    pub limit: u32,
+}
+
+impl fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "1-{} in {} attempts", self.range, self.limit)
+    }
+}
+
+#[derive(Debug)]
+pub struct Profile {
+    pub name: String,
+    pub games: Vec<Game>,
+}
+
+impl Profile {
+    // a missing file is not an error, it's just a new player
+    pub fn load(name: &str) -> io::Result<Profile> {
+        let path = path_of(name)?;
+        let text = match fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(ref e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(e),
+        };
+
+        let mut games = Vec::new();
+        for (i, line) in text.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let game = parse_game(line).ok_or_else(|| {
+                let msg = format!("{}:{}: bad game record", path.display(), i + 1);
+                io::Error::new(io::ErrorKind::InvalidData, msg)
+            })?;
+            games.push(game);
+        }
+
+        Ok(Profile {
+            name: name.to_string(),
+            games,
+        })
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        let path = path_of(&self.name)?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        let mut text = String::new();
+        for g in &self.games {
+            text.push_str(&format!(
+                "{} {} {} {}\n",
+                g.range, g.limit, g.guesses, g.won as u8
+            ));
+        }
+        fs::write(path, text)
+    }
+
+    pub fn record(&mut self, game: Game) {
+        self.games.push(game);
+    }
+
+    // skill after each game, oldest first. a new player starts at 1.0
+    pub fn skill_history(&self) -> Vec<f64> {
+        let mut skill = 1.0;
+        self.games
+            .iter()
+            .map(|g| {
+                skill = (SMOOTHING * g.effort() + (1.0 - SMOOTHING) * skill)
+                    .clamp(MIN_SKILL, MAX_SKILL);
+                skill
+            })
+            .collect()
+    }
+
+    pub fn skill(&self) -> f64 {
+        self.skill_history().last().cloned().unwrap_or(1.0)
+    }
+
+    pub fn next_difficulty(&self) -> Difficulty {
+        if self.games.is_empty() {
+            // same as the original game for a first timer
+            return Difficulty {
+                range: MIN_RANGE,
+                limit: attempt_limit(MIN_RANGE),
+            };
+        }
+        difficulty_for(self.skill())
+    }
+}
+
+// the adjustment curve. a player with skill s is expected to need
+// s * ideal_guesses(range) guesses, so pick the range that makes
+// that equal to TARGET_GUESSES
+pub fn difficulty_for(skill: f64) -> Difficulty {
+    let skill = skill.clamp(MIN_SKILL, MAX_SKILL);
+    let range = 2f64.powf(TARGET_GUESSES / skill - 1.0).round();
+    let range = (range as u32).max(MIN_RANGE);
+    Difficulty {
+        range,
+        limit: attempt_limit(range),
+    }
+}
+
+// log2(n) + 1 is roughly what binary search needs for 1..=n
+pub fn ideal_guesses(range: u32) -> f64 {
+    f64::from(range.max(1)).log2() + 1.0
+}
+
+// generous enough that the target is reachable, but never below
+// what binary search needs in the worst case so every game is winnable
+fn attempt_limit(range: u32) -> u32 {
+    let worst = 32 - range.leading_zeros();
+    let generous = (TARGET_GUESSES * 1.5).ceil() as u32;
+    worst.max(generous)
+}
+
+fn parse_game(line: &str) -> Option<Game> {
+    let mut parts = line.split_whitespace();
+    let range = parts.next()?.parse().ok()?;
+    let limit = parts.next()?.parse().ok()?;
+    let guesses = parts.next()?.parse().ok()?;
+    let won = match parts.next()? {
+        "1" => true,
+        "0" => false,
+        _ => return None,
+    };
+    if parts.next().is_some() {
+        return None;
+    }
+    Some(Game {
+        range,
+        limit,
+        guesses,
+        won,
+    })
+}
+
+// profiles live in $GUESSING_GAME_HOME, or ~/.guessing_game
+fn path_of(name: &str) -> io::Result<PathBuf> {
+    let valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if !valid {
+        let msg = format!("invalid profile name {:?}, use letters, digits, - or _", name);
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, msg));
+    }
+
+    let dir = match env::var_os("GUESSING_GAME_HOME") {
+        Some(dir) => PathBuf::from(dir),
+        None => {
+            let home = env::var_os("HOME").map(PathBuf::from).unwrap_or_default();
+            home.join(".guessing_game")
+        }
+    };
+    Ok(dir.join(format!("{}.txt", name)))
+}
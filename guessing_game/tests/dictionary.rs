@@ -0,0 +1,69 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use guessing_game::dictionary::{normalize, Dictionary};
+
+#[test]
+fn accents_and_case() {
+    assert_eq!(normalize("  Crème "), "creme");
+    assert_eq!(normalize("ÉCOLE"), "ecole");
+    // é written precomposed and as e + a combining accent
+    assert_eq!(normalize("caf\u{e9}"), "cafe");
+    assert_eq!(normalize("cafe\u{301}"), "cafe");
+    assert_eq!(normalize("Ångström"), "angstrom");
+    assert_eq!(normalize("naïve"), "naive");
+    // no accents to strip, only lowercased
+    assert_eq!(normalize("Straße"), "straße");
+    assert_eq!(normalize("日本"), "日本");
+}
+
+#[test]
+fn parse_and_lookup() {
+    let dict = Dictionary::parse("# fruit\nPoire\n\n  pomme \npoire\nCafé\ncafe\n");
+    // the same words once normalized count once
+    assert_eq!(dict.len(), 3);
+    assert!(!dict.is_empty());
+    assert!(dict.contains("poire") && dict.contains("pomme") && dict.contains("cafe"));
+    assert!(!dict.contains("Poire"));
+    assert!(!dict.contains("# fruit"));
+    assert!(["cafe", "poire", "pomme"].contains(&dict.random()));
+
+    assert!(Dictionary::parse("# nothing\n\n").is_empty());
+    assert!(!Dictionary::bundled().is_empty());
+}
+
+#[test]
+fn suggestions() {
+    let dict = Dictionary::parse("cat\nbat\nhat\ncart\ndog\ncrème\n日本\n");
+    assert_eq!(dict.suggest("cat", 1), ["cat"]);
+    // ties are in alphabetical order
+    assert_eq!(dict.suggest("cat", 3), ["cat", "bat", "cart"]);
+    assert_eq!(dict.suggest("cat", 10), ["cat", "bat", "cart", "hat"]);
+    // cat is one edit away, the rest two, 日本 included
+    assert_eq!(dict.suggest("ct", 10), ["cat", "bat", "cart", "hat", "日本"]);
+    assert_eq!(dict.suggest("cremes", 5), ["creme"]);
+    // distance counts chars, 語 is one edit and not three bytes
+    assert_eq!(dict.suggest("日本語", 5), ["日本"]);
+    // more than two edits from everything
+    assert!(dict.suggest("xyzzy", 5).is_empty());
+    assert!(dict.suggest("cat", 0).is_empty());
+}
+
+#[test]
+fn load() {
+    let dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("dictionary");
+    fs::create_dir_all(&dir).unwrap();
+    let words = dir.join("words.txt");
+    fs::write(&words, "Éclair\nTarte\n").unwrap();
+    let dict = Dictionary::load(&words).unwrap();
+    assert_eq!(dict.len(), 2);
+    assert!(dict.contains("eclair"));
+
+    let empty = dir.join("empty.txt");
+    fs::write(&empty, "# only a comment\n").unwrap();
+    let e = Dictionary::load(&empty).unwrap_err();
+    assert_eq!(e.kind(), io::ErrorKind::InvalidData);
+    assert!(e.to_string().ends_with("empty.txt: no words found"), "{}", e);
+    assert_eq!(Dictionary::load(dir.join("missing.txt")).unwrap_err().kind(), io::ErrorKind::NotFound);
+}
@@ -0,0 +1,79 @@
+use std::env;
+use std::fs;
+use std::io;
+
+use guessing_game::profile::{difficulty_for, Difficulty, Game, Profile};
+
+#[test]
+fn difficulty_curve() {
+    // plays like binary search: 5 guesses cover 1-16
+    assert_eq!(difficulty_for(1.0), Difficulty { range: 16, limit: 8 });
+
+    // a better (lower) skill never gets a smaller range, and every
+    // game can be won with binary search
+    let skills = [0.4, 0.5, 0.75, 1.0, 1.25, 1.5, 2.0, 3.0, 4.0];
+    for pair in skills.windows(2) {
+        assert!(difficulty_for(pair[0]).range >= difficulty_for(pair[1]).range, "{:?}", pair);
+    }
+    for &skill in &skills {
+        let d = difficulty_for(skill);
+        assert!((5..=2896).contains(&d.range), "{} -> {}", skill, d);
+        assert!(d.limit >= 32 - d.range.leading_zeros(), "{} -> {}", skill, d);
+    }
+
+    // skills outside 0.4-4.0 are clamped
+    assert_eq!(difficulty_for(0.0), difficulty_for(0.4));
+    assert_eq!(difficulty_for(100.0), difficulty_for(4.0));
+    // the widest range there is, for the weakest skill
+    assert_eq!(difficulty_for(0.4).range, 2896);
+    assert_eq!(difficulty_for(f64::NAN).range, 5);
+}
+
+#[test]
+fn lost_game_at_the_largest_limit() {
+    let game = Game { range: 16, limit: u32::MAX, guesses: 3, won: false };
+    assert!(game.effort().is_finite());
+}
+
+// the only test that touches the environment, the others would race
+// with it otherwise
+#[test]
+fn load_and_save() {
+    let home = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("profiles");
+    let _ = fs::remove_dir_all(&home);
+    env::set_var("GUESSING_GAME_HOME", &home);
+
+    // a missing file is a new player
+    let mut profile = Profile::load("alice").unwrap();
+    assert!(profile.games.is_empty());
+    assert_eq!(profile.next_difficulty(), Difficulty { range: 5, limit: 8 });
+
+    profile.record(Game { range: 5, limit: 8, guesses: 2, won: true });
+    profile.record(Game { range: 16, limit: 8, guesses: 8, won: false });
+    profile.save().unwrap();
+    assert_eq!(fs::read_to_string(home.join("alice.txt")).unwrap(), "5 8 2 1\n16 8 8 0\n");
+
+    let loaded = Profile::load("alice").unwrap();
+    assert_eq!(loaded.games.len(), 2);
+    assert_eq!(loaded.skill(), profile.skill());
+    assert_eq!(loaded.next_difficulty(), profile.next_difficulty());
+
+    // blank lines are skipped, anything else that isn't a game is an error
+    fs::write(home.join("bob.txt"), "5 8 2 1\n\n16 8 x 0\n").unwrap();
+    let e = Profile::load("bob").unwrap_err();
+    assert_eq!(e.kind(), io::ErrorKind::InvalidData);
+    assert!(e.to_string().ends_with("bob.txt:3: bad game record"), "{}", e);
+    for bad in &["5 8 2", "5 8 2 2", "5 8 2 1 1", "-5 8 2 1"] {
+        fs::write(home.join("bob.txt"), bad).unwrap();
+        assert!(Profile::load("bob").is_err(), "{:?}", bad);
+    }
+
+    // a limit nobody would play with still loads and scores
+    fs::write(home.join("carol.txt"), "16 4294967295 3 0\n").unwrap();
+    let carol = Profile::load("carol").unwrap();
+    assert!(carol.skill().is_finite());
+
+    for name in &["", "../alice", "a b", "a.txt"] {
+        assert_eq!(Profile::load(name).unwrap_err().kind(), io::ErrorKind::InvalidInput, "{:?}", name);
+    }
+}
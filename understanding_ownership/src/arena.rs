@@ -0,0 +1,113 @@
+// every value has one owner, so a graph is awkward: if node a lists
+// b as a neighbour and b lists a, which one owns the other? with
+// Strings or Boxes neither can, and the usual ways out are Rc with
+// Weak, or indexes into a Vec.
+//
+// an arena is another: it owns all the values, and hands out plain
+// &'a T references that live as long as the arena does. the values
+// can point at each other in any shape, cycles too, since none of
+// them owns another. to link two values after both exist, the link
+// goes in a Cell (or RefCell), which can be changed through a &:
+//
+//   struct Node<'a> { name: &'static str, next: Cell<Option<&'a Node<'a>>> }
+//
+//   let arena = Arena::new();
+//   let a = arena.alloc(Node { name: "a", next: Cell::new(None) });
+//   let b = arena.alloc(Node { name: "b", next: Cell::new(Some(a)) });
+//   a.next.set(Some(b));   // a -> b -> a
+//
+// nothing is freed one at a time. everything goes when the arena is
+// dropped, and the compiler makes sure no reference outlives it.
+//
+// values are kept in chunks. a chunk is a Vec that's never pushed past
+// its capacity, so it never moves and the references stay good. when
+// it's full a new one twice as big is started.
+
+use std::cell::{Cell, RefCell};
+use std::fmt;
+use std::mem;
+
+const FIRST_CHUNK: usize = 8;
+
+pub struct Arena<T> {
+    chunks: RefCell<Vec<Vec<T>>>,
+    len: Cell<usize>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Stats {
+    pub values: usize,
+    pub chunks: usize,
+    // size_of::<T>() times the values
+    pub bytes_used: usize,
+    // the chunks' capacity in bytes, used or not
+    pub bytes_reserved: usize,
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} values in {} chunks, {} of {} bytes used",
+            self.values, self.chunks, self.bytes_used, self.bytes_reserved
+        )
+    }
+}
+
+impl<T> Arena<T> {
+    pub fn new() -> Arena<T> {
+        Arena { chunks: RefCell::new(Vec::new()), len: Cell::new(0) }
+    }
+
+    // moves value into the arena. takes &self, so values can be added
+    // while references to earlier ones are held
+    #[allow(clippy::mut_from_ref)] // each call hands out a different value
+    pub fn alloc(&self, value: T) -> &mut T {
+        let mut chunks = self.chunks.borrow_mut();
+        let full = chunks.last().is_none_or(|chunk| chunk.len() == chunk.capacity());
+        if full {
+            let size = chunks.last().map_or(FIRST_CHUNK, |chunk| chunk.capacity() * 2);
+            chunks.push(Vec::with_capacity(size));
+        }
+        let chunk = chunks.last_mut().unwrap();
+        self.len.set(self.len.get() + 1);
+        // written through the chunk's pointer, not found again with
+        // last_mut: that goes through a &mut [T] over the whole chunk,
+        // which would invalidate the references already handed out.
+        // the chunk is never filled past its capacity, so the value
+        // stays where it is until the arena is dropped, and no other
+        // call returns it
+        unsafe {
+            let len = chunk.len();
+            let slot = chunk.as_mut_ptr().add(len);
+            slot.write(value);
+            chunk.set_len(len + 1);
+            &mut *slot
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len.get()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn stats(&self) -> Stats {
+        let chunks = self.chunks.borrow();
+        let size = mem::size_of::<T>();
+        Stats {
+            values: self.len(),
+            chunks: chunks.len(),
+            bytes_used: self.len() * size,
+            bytes_reserved: chunks.iter().map(|chunk| chunk.capacity() * size).sum(),
+        }
+    }
+}
+
+impl<T> Default for Arena<T> {
+    fn default() -> Arena<T> {
+        Arena::new()
+    }
+}
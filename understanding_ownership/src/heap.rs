@@ -0,0 +1,128 @@
+// every String::from, push_str and drop in the lessons asks the
+// global allocator for heap memory or gives it back. Counting sits in
+// front of the system allocator and counts those calls, so a lesson
+// can be run and its heap traffic looked at afterwards:
+//
+//   allocs     new blocks (String::from, Box::new, vec![..])
+//   reallocs   blocks grown or shrunk in place or by moving,
+//              e.g. push_str on a String that's out of capacity
+//   frees      blocks given back, usually by a drop
+//   bytes      the total asked for by allocs and reallocs
+//   peak       the most bytes in use at once, above what was in use
+//              when measuring started
+//
+// it only counts when it's installed, in main.rs:
+//
+//   #[global_allocator]
+//   static HEAP: heap::Counting = heap::Counting;
+//
+// the counters are global, so measuring with several threads running
+// counts theirs too.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering::Relaxed};
+
+pub struct Counting;
+
+static ALLOCS: AtomicUsize = AtomicUsize::new(0);
+static REALLOCS: AtomicUsize = AtomicUsize::new(0);
+static FREES: AtomicUsize = AtomicUsize::new(0);
+static BYTES: AtomicUsize = AtomicUsize::new(0);
+static IN_USE: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+// no allocating in here, it would call straight back into us
+fn grew(by: usize) {
+    BYTES.fetch_add(by, Relaxed);
+    let now = IN_USE.fetch_add(by, Relaxed) + by;
+    PEAK.fetch_max(now, Relaxed);
+}
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            ALLOCS.fetch_add(1, Relaxed);
+            grew(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            ALLOCS.fetch_add(1, Relaxed);
+            grew(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new = System.realloc(ptr, layout, new_size);
+        if !new.is_null() {
+            REALLOCS.fetch_add(1, Relaxed);
+            if new_size > layout.size() {
+                grew(new_size - layout.size());
+            } else {
+                IN_USE.fetch_sub(layout.size() - new_size, Relaxed);
+            }
+        }
+        new
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        FREES.fetch_add(1, Relaxed);
+        IN_USE.fetch_sub(layout.size(), Relaxed);
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Stats {
+    pub allocs: usize,
+    pub reallocs: usize,
+    pub frees: usize,
+    pub bytes: usize,
+    pub peak: usize,
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} allocs, {} reallocs, {} frees, {} bytes, peak {} bytes",
+            self.allocs, self.reallocs, self.frees, self.bytes, self.peak
+        )
+    }
+}
+
+// bytes currently allocated and not freed
+pub fn in_use() -> usize {
+    IN_USE.load(Relaxed)
+}
+
+// runs f and counts what it did to the heap. a measure() inside
+// another one starts its own peak, and puts the outer one back
+// after, raised to the inner peak if that went higher
+pub fn measure<R>(f: impl FnOnce() -> R) -> (R, Stats) {
+    let allocs = ALLOCS.load(Relaxed);
+    let reallocs = REALLOCS.load(Relaxed);
+    let frees = FREES.load(Relaxed);
+    let bytes = BYTES.load(Relaxed);
+    let base = IN_USE.load(Relaxed);
+    let outer_peak = PEAK.swap(base, Relaxed);
+
+    let result = f();
+
+    let peak = PEAK.fetch_max(outer_peak, Relaxed);
+
+    let stats = Stats {
+        allocs: ALLOCS.load(Relaxed) - allocs,
+        reallocs: REALLOCS.load(Relaxed) - reallocs,
+        frees: FREES.load(Relaxed) - frees,
+        bytes: BYTES.load(Relaxed) - bytes,
+        peak: peak.saturating_sub(base),
+    };
+    (result, stats)
+}
@@ -0,0 +1,147 @@
+// transfer_of_ownership() hands a String to s(), which then owns it.
+// passing text around that way means every holder has its own copy,
+// and comparing two of them compares every byte. an interner keeps
+// one copy of each distinct string and hands out a Symbol instead: a
+// u32 that's Copy, so it's passed around like the i32 in
+// transfer_of_ownership(), and compared and hashed as a number.
+//
+//   let mut names = Interner::new();
+//   let a = names.intern("hello");
+//   let b = names.intern("hello");   // the same Symbol, no new copy
+//   assert_eq!(a, b);
+//   assert_eq!(names.resolve(a), "hello");
+//
+// a Symbol only means something to the interner that made it.
+// resolving it with another one gives another string or panics.
+//
+// Interner needs &mut self to add strings, so it's for one thread.
+// SyncInterner adds through &self behind a lock and can be shared
+// between threads in an Arc.
+
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::sync::{Arc, RwLock};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Symbol(u32);
+
+impl Symbol {
+    // 0, 1, 2, ... in the order the strings were first interned
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+fn next_symbol(len: usize) -> Symbol {
+    assert!(len < u32::MAX as usize, "more than {} interned strings", u32::MAX);
+    Symbol(len as u32)
+}
+
+#[derive(Debug, Default)]
+pub struct Interner {
+    // the map and the list share each string through the Rc
+    map: HashMap<Rc<str>, Symbol>,
+    strings: Vec<Rc<str>>,
+}
+
+impl Interner {
+    pub fn new() -> Interner {
+        Interner::default()
+    }
+
+    pub fn intern(&mut self, s: &str) -> Symbol {
+        if let Some(&sym) = self.map.get(s) {
+            return sym;
+        }
+        let sym = next_symbol(self.strings.len());
+        let s: Rc<str> = Rc::from(s);
+        self.map.insert(Rc::clone(&s), sym);
+        self.strings.push(s);
+        sym
+    }
+
+    // the Symbol for s if it's been interned, without adding it
+    pub fn get(&self, s: &str) -> Option<Symbol> {
+        self.map.get(s).copied()
+    }
+
+    pub fn resolve(&self, sym: Symbol) -> &str {
+        &self.strings[sym.index()]
+    }
+
+    pub fn len(&self) -> usize {
+        self.strings.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.strings.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Symbol, &str)> {
+        self.strings.iter().enumerate().map(|(i, s)| (Symbol(i as u32), &**s))
+    }
+}
+
+// like Interner, with Arc for Rc so it can be shared between threads
+#[derive(Default)]
+struct Shared {
+    map: HashMap<Arc<str>, Symbol>,
+    strings: Vec<Arc<str>>,
+}
+
+#[derive(Default)]
+pub struct SyncInterner {
+    shared: RwLock<Shared>,
+}
+
+impl SyncInterner {
+    pub fn new() -> SyncInterner {
+        SyncInterner::default()
+    }
+
+    pub fn intern(&self, s: &str) -> Symbol {
+        // most strings are already there, and a read lock lets other
+        // threads look too
+        if let Some(sym) = self.get(s) {
+            return sym;
+        }
+        let mut shared = self.shared.write().unwrap();
+        // another thread may have added it between the two locks
+        if let Some(&sym) = shared.map.get(s) {
+            return sym;
+        }
+        let sym = next_symbol(shared.strings.len());
+        let s: Arc<str> = Arc::from(s);
+        shared.map.insert(Arc::clone(&s), sym);
+        shared.strings.push(s);
+        sym
+    }
+
+    pub fn get(&self, s: &str) -> Option<Symbol> {
+        self.shared.read().unwrap().map.get(s).copied()
+    }
+
+    // the lock guard can't be returned, so the &str outlives it. that's
+    // fine because a string is never changed or removed once it's in,
+    // and its bytes are on the heap, behind the Arc, where they stay
+    // when the Vec grows and moves the Arcs. (a Box wouldn't do: moving
+    // a Box says nothing else points into it.) they're freed when the
+    // SyncInterner is dropped, and the &str can't outlive &self
+    pub fn resolve(&self, sym: Symbol) -> &str {
+        let shared = self.shared.read().unwrap();
+        unsafe { extend(&shared.strings[sym.index()]) }
+    }
+
+    pub fn len(&self) -> usize {
+        self.shared.read().unwrap().strings.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+// the caller makes sure the str lives long enough
+unsafe fn extend<'a>(s: &str) -> &'a str {
+    &*(s as *const str)
+}
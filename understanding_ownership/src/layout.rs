@@ -0,0 +1,371 @@
+// "how Rust lays data out in memory". a value has a part of fixed
+// size, which is what a variable holds (on the stack, or inline in
+// whatever contains it), and maybe a buffer somewhere else that it
+// points to:
+//
+//   String       pointer, capacity, length   -> buffer on the heap
+//   &str         pointer, length             -> bytes anywhere
+//   &String      pointer                     -> a String
+//   Box<str>     pointer, length             -> buffer on the heap,
+//                                               no spare capacity
+//
+// std doesn't promise the order of String's fields, so they're found
+// by looking for the pointer, length and capacity in the bytes of a
+// probe, a value made so that all three are different. the fields are
+// where they were in the probe, whatever the value being inspected
+// holds: String::from("abc") has the same length and capacity, and
+// String::new() has 0 for both.
+//
+// a niche is a bit pattern a type never uses, like 0 for a pointer or
+// 2 for a bool. Option<T> can use it to mean None and then needs no
+// extra byte: Option<String> and Option<&str> are the same size as
+// String and &str.
+
+use std::fmt::Write;
+use std::mem;
+use std::ops::Range;
+
+use crate::text::Stats;
+use crate::token::{Kind, Token};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Field {
+    pub name: String,
+    pub offset: usize,
+    pub size: usize,
+}
+
+impl Field {
+    fn new(name: &str, offset: usize, size: usize) -> Field {
+        Field { name: name.to_string(), offset, size }
+    }
+}
+
+// where a pointer in a value points
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Place {
+    Stack,
+    // the program's own data, like a string literal
+    Static,
+    Heap,
+    // an empty String or Vec hasn't allocated anything, its pointer is
+    // only a placeholder that's never read through
+    Nowhere,
+}
+
+// a buffer the value points to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Buffer {
+    pub addr: usize,
+    pub len: usize,
+    pub capacity: usize,
+    pub place: Place,
+    // a borrowed buffer could be anywhere, place() only guessed
+    pub guessed: bool,
+}
+
+pub trait Inspect: Sized {
+    fn type_name() -> String {
+        let name = std::any::type_name::<Self>();
+        ["alloc::string::", "alloc::boxed::", "alloc::vec::", "core::option::"]
+            .iter()
+            .fold(name.to_string(), |name, path| name.replace(path, ""))
+    }
+
+    // the fields in the value's bytes, in any order. bytes not in a
+    // field are padding, and are never read
+    fn fields(&self) -> Vec<Field>;
+
+    fn buffer(&self) -> Option<Buffer> {
+        None
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Report {
+    pub name: String,
+    pub size: usize,
+    pub align: usize,
+    // fields sorted by offset
+    pub fields: Vec<Field>,
+    // the value's bytes, None for padding and unused bytes
+    pub bytes: Vec<Option<u8>>,
+    // Option<T> is no bigger than T
+    pub niche: bool,
+    pub buffer: Option<Buffer>,
+}
+
+pub fn inspect<T: Inspect>(value: &T) -> Report {
+    let mut fields = value.fields();
+    fields.sort_by_key(|f| f.offset);
+    let size = mem::size_of::<T>();
+    let mut bytes = vec![None; size];
+    let base = value as *const T as *const u8;
+    for f in &fields {
+        for (i, byte) in bytes.iter_mut().enumerate().skip(f.offset).take(f.size) {
+            // fields only cover initialized bytes
+            *byte = Some(unsafe { *base.add(i) });
+        }
+    }
+    Report {
+        name: T::type_name(),
+        size,
+        align: mem::align_of::<T>(),
+        fields,
+        bytes,
+        niche: mem::size_of::<Option<T>>() == size,
+        buffer: value.buffer(),
+    }
+}
+
+impl Report {
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        writeln!(out, "{}  size {}, align {}", self.name, self.size, self.align).unwrap();
+        for f in &self.fields {
+            writeln!(out, "  {:>3}..{:<3} {}", f.offset, f.offset + f.size, f.name).unwrap();
+        }
+        // 8 bytes a row, and which field each byte is in
+        for (row, chunk) in self.bytes.chunks(8).enumerate() {
+            let hex: Vec<String> = chunk
+                .iter()
+                .map(|b| b.map_or("..".to_string(), |b| format!("{:02x}", b)))
+                .collect();
+            let start = row * 8;
+            let names: Vec<&str> = self
+                .fields
+                .iter()
+                .filter(|f| overlaps(f.offset..f.offset + f.size, start..start + chunk.len()))
+                .map(|f| f.name.as_str())
+                .collect();
+            let names = if names.is_empty() { "padding or unused".to_string() } else { names.join(", ") };
+            writeln!(out, "  {:>3}: {:<23}  {}", start, hex.join(" "), names).unwrap();
+        }
+        if self.niche {
+            let bytes = if self.size == 1 { "byte" } else { "bytes" };
+            writeln!(out, "  Option<{}> is also {} {}, None fits in a niche", self.name, self.size, bytes).unwrap();
+        } else {
+            let size = self.size.max(1);
+            writeln!(out, "  no niche, Option<{}> needs a tag and is bigger than {}", self.name, size).unwrap();
+        }
+        if let Some(b) = &self.buffer {
+            let place = match b.place {
+                Place::Heap => "on the heap",
+                Place::Stack => "on the stack",
+                Place::Static => "in the program's static data",
+                Place::Nowhere => "nothing allocated",
+            };
+            let guess = if b.guessed { "probably " } else { "" };
+            write!(out, "  points to {:#x}, {}{}: {} bytes used", b.addr, guess, place, b.len).unwrap();
+            if b.capacity != b.len {
+                write!(out, " of {}", b.capacity).unwrap();
+            }
+            writeln!(out).unwrap();
+        }
+        out
+    }
+}
+
+fn overlaps(a: Range<usize>, b: Range<usize>) -> bool {
+    a.start < b.end && b.start < a.end
+}
+
+static MARKER: u8 = 0;
+
+// a guess from the address alone: close to a local variable is the
+// stack, close to a static is the program's data, anything else the
+// heap. only a guess, the heap can be mapped right next to the
+// program's data, so it's only used for what a value borrows
+pub fn place(addr: usize) -> Place {
+    let local = 0u8;
+    let near = |a: usize, b: usize, within: usize| a.abs_diff(b) < within;
+    if near(addr, &local as *const u8 as usize, 8 << 20) {
+        Place::Stack
+    } else if near(addr, &MARKER as *const u8 as usize, 64 << 20) {
+        Place::Static
+    } else {
+        Place::Heap
+    }
+}
+
+// the usize-sized fields of a type whose layout isn't written down,
+// found by looking for the values a probe of that type holds. they
+// must all be different, or it couldn't tell which field is which
+fn find_words<T>(probe: &T, words: &[(&str, usize)]) -> Vec<Field> {
+    for (i, (a, x)) in words.iter().enumerate() {
+        for (b, y) in &words[i + 1..] {
+            assert!(x != y, "the probe's {} and {} are both {}", a, b, x);
+        }
+    }
+    let base = probe as *const T as *const u8;
+    let n = mem::size_of::<T>() / mem::size_of::<usize>();
+    let mut fields = Vec::new();
+    for i in 0..n {
+        let offset = i * mem::size_of::<usize>();
+        let word = unsafe { (base.add(offset) as *const usize).read_unaligned() };
+        if let Some((name, _)) = words.iter().find(|(_, w)| *w == word) {
+            fields.push(Field::new(name, offset, mem::size_of::<usize>()));
+        }
+    }
+    assert_eq!(fields.len(), words.len(), "not every field was found in the probe");
+    fields
+}
+
+fn str_fields<S: AsRef<str>>(probe: S) -> Vec<Field> {
+    let s = probe.as_ref();
+    find_words(&probe, &[("ptr", s.as_ptr() as usize), ("len", s.len())])
+}
+
+// what a &str or a & points to, which could be anywhere
+fn borrowed_buffer(addr: *const u8, len: usize) -> Buffer {
+    let addr = addr as usize;
+    Buffer { addr, len, capacity: len, place: place(addr), guessed: true }
+}
+
+// the buffer of a String, Vec or Box. they allocate it on the heap
+// when they have room for something, and not at all otherwise
+fn owned_buffer(addr: *const u8, len: usize, capacity: usize) -> Buffer {
+    let place = if capacity == 0 { Place::Nowhere } else { Place::Heap };
+    Buffer { addr: addr as usize, len, capacity, place, guessed: false }
+}
+
+impl Inspect for String {
+    // room for 3 and nothing in it, so the pointer, 0 and 3
+    fn fields(&self) -> Vec<Field> {
+        let probe = String::with_capacity(3);
+        let words = [("ptr", probe.as_ptr() as usize), ("len", 0), ("capacity", probe.capacity())];
+        find_words(&probe, &words)
+    }
+
+    fn buffer(&self) -> Option<Buffer> {
+        Some(owned_buffer(self.as_ptr(), self.len(), self.capacity()))
+    }
+}
+
+impl Inspect for &str {
+    fn type_name() -> String {
+        "&str".to_string()
+    }
+
+    fn fields(&self) -> Vec<Field> {
+        str_fields("abc")
+    }
+
+    fn buffer(&self) -> Option<Buffer> {
+        Some(borrowed_buffer(self.as_ptr(), self.len()))
+    }
+}
+
+impl Inspect for &String {
+    fn type_name() -> String {
+        "&String".to_string()
+    }
+
+    fn fields(&self) -> Vec<Field> {
+        vec![Field::new("ptr to the String", 0, mem::size_of::<usize>())]
+    }
+
+    fn buffer(&self) -> Option<Buffer> {
+        let s: &String = self;
+        let size = mem::size_of::<String>();
+        Some(borrowed_buffer(s as *const String as *const u8, size))
+    }
+}
+
+impl Inspect for Box<str> {
+    fn fields(&self) -> Vec<Field> {
+        str_fields(Box::<str>::from("abc"))
+    }
+
+    fn buffer(&self) -> Option<Buffer> {
+        Some(owned_buffer(self.as_ptr(), self.len(), self.len()))
+    }
+}
+
+impl<T> Inspect for Vec<T> {
+    // the same probe as String's. a Vec of zero-sized values never
+    // allocates, and keeps 0 as its capacity (capacity() makes up
+    // usize::MAX), the same as the probe's len. every Vec is laid out
+    // alike whatever it holds, so those get Vec<u8>'s fields
+    fn fields(&self) -> Vec<Field> {
+        if mem::size_of::<T>() == 0 {
+            return Vec::<u8>::new().fields();
+        }
+        let probe: Vec<T> = Vec::with_capacity(3);
+        find_words(&probe, &[("ptr", probe.as_ptr() as usize), ("len", 0), ("capacity", probe.capacity())])
+    }
+
+    fn buffer(&self) -> Option<Buffer> {
+        let size = mem::size_of::<T>();
+        Some(owned_buffer(self.as_ptr() as *const u8, self.len() * size, self.capacity() * size))
+    }
+}
+
+impl Inspect for Option<String> {
+    fn fields(&self) -> Vec<Field> {
+        match self {
+            Some(s) => s.fields(),
+            // only the niche is set, the rest is uninitialized
+            None => vec![],
+        }
+    }
+
+    fn buffer(&self) -> Option<Buffer> {
+        self.as_ref().and_then(Inspect::buffer)
+    }
+}
+
+impl Inspect for Stats {
+    fn type_name() -> String {
+        "text::Stats".to_string()
+    }
+
+    fn fields(&self) -> Vec<Field> {
+        let n = mem::size_of::<usize>();
+        vec![
+            Field::new("bytes", mem::offset_of!(Stats, bytes), n),
+            Field::new("chars", mem::offset_of!(Stats, chars), n),
+            Field::new("graphemes", mem::offset_of!(Stats, graphemes), n),
+            Field::new("words", mem::offset_of!(Stats, words), n),
+            Field::new("lines", mem::offset_of!(Stats, lines), n),
+            Field::new("width", mem::offset_of!(Stats, width), n),
+        ]
+    }
+}
+
+impl Inspect for Kind {
+    fn type_name() -> String {
+        "token::Kind".to_string()
+    }
+
+    // Word, Number, Punct and Quoted's closed: bool all fit in one byte.
+    // closed uses 0 and 1, the other three get values a bool can't have
+    fn fields(&self) -> Vec<Field> {
+        vec![Field::new("tag and closed", 0, 1)]
+    }
+}
+
+impl<'a> Inspect for Token<'a> {
+    fn type_name() -> String {
+        "token::Token".to_string()
+    }
+
+    fn fields(&self) -> Vec<Field> {
+        let n = mem::size_of::<usize>();
+        let text = mem::offset_of!(Token, text);
+        let span = mem::offset_of!(Token, span);
+        let mut fields = str_fields("abc");
+        for f in &mut fields {
+            f.name = format!("text.{}", f.name);
+            f.offset += text;
+        }
+        fields.push(Field::new("span.start", span + mem::offset_of!(Range<usize>, start), n));
+        fields.push(Field::new("span.end", span + mem::offset_of!(Range<usize>, end), n));
+        fields.push(Field::new("kind", mem::offset_of!(Token, kind), mem::size_of::<Kind>()));
+        fields
+    }
+
+    fn buffer(&self) -> Option<Buffer> {
+        self.text.buffer()
+    }
+}
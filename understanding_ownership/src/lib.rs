@@ -0,0 +1,12 @@
+// the lessons live in main.rs. the tools that grew out of them
+// live here so they can be used (and run) on their own
+pub mod arena;
+pub mod heap;
+pub mod intern;
+pub mod layout;
+pub mod normalize;
+pub mod pipeline;
+pub mod rope;
+pub mod text;
+pub mod token;
+pub mod trace;
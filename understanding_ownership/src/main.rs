@@ -10,12 +10,53 @@ slices, and how Rust lays data out in memory.
 2. There can only be one owner at a time.
 3. When the owner goes out of scope, the value will be dropped.
 */
+// the lessons keep the book's code as it is: fn1 borrows a &String,
+// s2 is only there for the comment under it, and fn2 ends with a ;
+#![allow(unused_variables, redundant_semicolons, clippy::ptr_arg)]
+
+use std::borrow::Cow;
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+use std::env;
+use std::fs;
+use std::thread;
+use std::time::Instant;
+
+use understanding_ownership::arena::Arena;
+use understanding_ownership::heap;
+use understanding_ownership::intern::{Interner, SyncInterner};
+use understanding_ownership::layout::{self, Inspect};
+use understanding_ownership::normalize;
+use understanding_ownership::pipeline::{self, Config};
+use understanding_ownership::rope::Rope;
+use understanding_ownership::text::{self, Stats};
+use understanding_ownership::token::{self, TokenizeExt};
+use understanding_ownership::trace::{Log, Tracked};
+
+// counts every heap allocation, see heap.rs
+#[global_allocator]
+static HEAP: heap::Counting = heap::Counting;
+
+// the lessons, in order. a new one goes here and the allocs tool
+// picks it up too
+const SECTIONS: [(&str, fn()); 3] = [
+    ("what_is_ownership", what_is_ownership),
+    ("references_and_borrowing", references_and_borrowing),
+    ("slices", slices),
+];
+
 fn main() {
-    println!("\nwhat_is_ownership()");
-    what_is_ownership();
+    // with no arguments, run the lessons. otherwise run one of the tools
+    let args: Vec<String> = env::args().skip(1).collect();
+    if !args.is_empty() {
+        run_tool(&args);
+        return;
+    }
 
-    println!("\nreferences_and_borrowing()");
-    references_and_borrowing();
+    for (name, section) in SECTIONS.iter() {
+        println!("\n{}()", name);
+        section();
+    }
 }
 
 fn references_and_borrowing() {
@@ -27,6 +68,11 @@ fn references_and_borrowing() {
     println!("{}", fn1(&s1)); // 5
     println!("{}", s1); // hello
 
+    // len() counts bytes, not letters. see text.rs
+    let s4 = String::from("héllo");
+    println!("{}", fn1(&s4)); // 6
+    println!("{}", s4.chars().count()); // 5
+
     // also, you cannot modify what you borrowed
     let s2 = String::from("hello");
     // fn fn2(s: &String) { s.push_str("test"); } compile error. really nice!
@@ -45,6 +91,315 @@ fn references_and_borrowing() {
     // data in a particular scope.
 }
 
+fn slices() {
+    // a slice borrows part of a String. no copy, just a pointer
+    // and a length
+    let s = String::from("hello world");
+    let hello = &s[0..5];
+    let world = &s[6..];
+    println!("{} {}", hello, world); // hello world
+
+    // the same idea, for a whole sentence. every token is a &str
+    // pointing into s2, see token.rs
+    let s2 = String::from("let x = \"hi\"; // 42");
+    for t in token::tokenize(&s2) {
+        println!("{:?} {:?} at {:?}", t.kind, t.text, t.span);
+    }
+
+    // and across lines
+    let s3 = String::from("one two\nthree");
+    let words: Vec<(usize, &str)> = s3.lines().tokens().map(|(line, t)| (line, t.text)).collect();
+    println!("{:?}", words); // [(0, "one"), (0, "two"), (1, "three")]
+}
+
+fn run_tool(args: &[String]) {
+    match (args[0].as_str(), &args[1..]) {
+        ("allocs", []) => print_allocs(),
+        ("graph", [from, to]) => print_route(from, to),
+        ("intern", [path]) => print_interned(path),
+        ("layout", []) => print_layouts(),
+        ("normalize", [s]) => print_normalized(s),
+        ("wordcount", [path]) => print_word_count(path, Config::default()),
+        ("wordcount", [path, flag, n]) if flag == "--workers" => {
+            let workers = n.parse().ok().filter(|&n| n > 0).unwrap_or_else(|| usage());
+            print_word_count(path, Config { workers, ..Config::default() });
+        }
+        ("rope", [n]) => compare_rope(n.parse().unwrap_or_else(|_| usage())),
+        ("text", [s]) => println!("{}", Stats::new(s)),
+        ("tokens", [s]) => {
+            for t in token::tokenize(s) {
+                println!("{:>3}..{:<3} {:<24} {:?}", t.span.start, t.span.end, format!("{:?}", t.kind), t.text);
+            }
+        }
+        ("fit", [s, n]) => {
+            let n = n.parse().unwrap_or_else(|_| usage());
+            println!("{:?}", text::truncate_width(s, n));
+        }
+        ("slice", [how, s, start, end]) => {
+            let number = |n: &String| n.parse().unwrap_or_else(|_| usage());
+            let range = number(start)..number(end);
+            let slice = match how.as_str() {
+                "bytes" => text::slice_bytes(s, range),
+                "chars" => text::slice_chars(s, range),
+                "graphemes" => text::slice_graphemes(s, range),
+                _ => usage(),
+            };
+            println!("{:?}", slice);
+        }
+        _ => usage(),
+    }
+}
+
+fn print_allocs() {
+    // the lessons print too. stdout's buffer is allocated on first
+    // use, so get that out of the way before measuring
+    println!("lesson output:");
+    let mut results = Vec::new();
+    for (name, section) in SECTIONS.iter() {
+        let ((), stats) = heap::measure(section);
+        results.push((name, stats));
+    }
+
+    println!();
+    println!("{:<26} {:>6} {:>8} {:>6} {:>7} {:>6}", "section", "allocs", "reallocs", "frees", "bytes", "peak");
+    for (name, s) in results {
+        println!(
+            "{:<26} {:>6} {:>8} {:>6} {:>7} {:>6}",
+            name, s.allocs, s.reallocs, s.frees, s.bytes, s.peak
+        );
+    }
+}
+
+fn print_route(from: &str, to: &str) {
+    // every station lists the ones it connects to, and they list it
+    // back, so no station can own its neighbours. the arena owns them
+    // all and the lists hold plain references. came_from is filled in
+    // by the search, through a shared reference, so it's a Cell
+    struct Station<'a> {
+        name: &'static str,
+        lines: RefCell<Vec<&'a Station<'a>>>,
+        came_from: Cell<Option<&'a Station<'a>>>,
+    }
+
+    const TRACKS: [(&str, &str); 9] = [
+        ("harbor", "market"),
+        ("market", "museum"),
+        ("museum", "park"),
+        ("park", "harbor"),
+        ("market", "central"),
+        ("central", "university"),
+        ("university", "stadium"),
+        ("stadium", "central"),
+        ("park", "airport"),
+    ];
+
+    // the stations borrow the arena for as long as it lives, so it
+    // can't be moved out to be dropped somewhere else. it goes at the
+    // end of this block, all the stations and their lists at once
+    let in_use = {
+        let arena = Arena::new();
+        let mut stations: Vec<&Station> = Vec::new();
+        let mut station = |name: &'static str| match stations.iter().find(|s| s.name == name) {
+            Some(s) => *s,
+            None => {
+                let s = &*arena.alloc(Station { name, lines: RefCell::new(Vec::new()), came_from: Cell::new(None) });
+                stations.push(s);
+                s
+            }
+        };
+        for (a, b) in TRACKS.iter() {
+            let (a, b) = (station(a), station(b));
+            a.lines.borrow_mut().push(b);
+            b.lines.borrow_mut().push(a);
+        }
+
+        let find = |name: &str| {
+            stations.iter().copied().find(|s| s.name == name).unwrap_or_else(|| {
+                let names: Vec<&str> = stations.iter().map(|s| s.name).collect();
+                eprintln!("error: no station {:?}, there's {}", name, names.join(", "));
+                std::process::exit(1);
+            })
+        };
+        let (start, end) = (find(from), find(to));
+
+        // breadth first, so the first time end is reached is the shortest
+        let mut queue = VecDeque::from(vec![start]);
+        while let Some(s) = queue.pop_front() {
+            for &next in s.lines.borrow().iter() {
+                if next.came_from.get().is_none() && !std::ptr::eq(next, start) {
+                    next.came_from.set(Some(s));
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        let mut route = vec![end.name];
+        let mut at = end;
+        while let Some(prev) = at.came_from.get() {
+            route.push(prev.name);
+            at = prev;
+        }
+        route.reverse();
+        println!("{}", route.join(" -> "));
+        println!("arena: {}", arena.stats());
+
+        // only the arena's memory is left to count
+        drop((stations, queue, route));
+        heap::in_use()
+    };
+    println!("dropping the arena freed {} bytes", in_use - heap::in_use());
+}
+
+fn print_interned(path: &str) {
+    let text = fs::read_to_string(path).unwrap_or_else(|e| {
+        eprintln!("error: {}: {}", path, e);
+        std::process::exit(1);
+    });
+    let words = || token::tokenize(&text).filter(|t| t.kind == token::Kind::Word).map(|t| t.text);
+
+    let mut interner = Interner::new();
+    let symbols: Vec<_> = words().map(|w| interner.intern(w)).collect();
+    let as_strings: usize = words().map(|w| w.len()).sum();
+    let interned: usize = interner.iter().map(|(_, s)| s.len()).sum();
+    println!("{} words, {} different", symbols.len(), interner.len());
+    let header = std::mem::size_of::<String>();
+    println!("as Strings   {} bytes of text, and {} bytes of Strings", as_strings, symbols.len() * header);
+    println!("interned     {} bytes of text, and {} bytes of symbols", interned, symbols.len() * 4);
+
+    // the same with the lines split between four threads sharing one
+    // SyncInterner. the symbols differ, the strings don't
+    let shared = SyncInterner::new();
+    let lines: Vec<&str> = text.lines().collect();
+    thread::scope(|scope| {
+        for part in lines.chunks(lines.len() / 4 + 1) {
+            let shared = &shared;
+            scope.spawn(move || {
+                for t in part.iter().flat_map(|line| token::tokenize(line)) {
+                    if t.kind == token::Kind::Word {
+                        shared.intern(t.text);
+                    }
+                }
+            });
+        }
+    });
+    println!("4 threads    {} different", shared.len());
+    assert!(interner.iter().all(|(_, s)| shared.resolve(shared.get(s).unwrap()) == s));
+}
+
+fn print_word_count(path: &str, config: Config) {
+    let counts = fs::File::open(path)
+        .and_then(|file| pipeline::word_count(std::io::BufReader::new(file), config))
+        .unwrap_or_else(|e| {
+            eprintln!("error: {}: {}", path, e);
+            std::process::exit(1);
+        });
+    println!("{} lines, {} words, {} different", counts.lines, counts.words, counts.counts.len());
+    for (word, n) in counts.counts.iter().take(20) {
+        println!("{:>7}  {}", n, word);
+    }
+}
+
+fn print_normalized(s: &str) {
+    type Step = for<'a> fn(&'a str) -> Cow<'a, str>;
+    let steps: [(&str, Step); 7] = [
+        ("trim", normalize::trim),
+        ("collapse_whitespace", normalize::collapse_whitespace),
+        ("fold_case", normalize::fold_case),
+        ("nfc", normalize::nfc),
+        ("escape_html", normalize::escape_html),
+        ("escape_debug", normalize::escape_debug),
+        ("normalize", normalize::normalize),
+    ];
+    for (name, step) in steps.iter() {
+        let out = step(s);
+        let how = match out {
+            Cow::Borrowed(_) => "borrowed",
+            Cow::Owned(_) => "copied",
+        };
+        println!("{:<20} {:<9} {:?}", name, how, out);
+    }
+}
+
+fn print_layouts() {
+    fn show<T: Inspect>(value: &T) {
+        println!("{}", layout::inspect(value).render());
+    }
+
+    let mut s = String::from("hello");
+    show(&s);
+    // pushing makes room for more than it needs
+    s.push('!');
+    show(&s);
+    show(&"hello");
+    show(&&s[1..]);
+    show(&&s);
+    show(&Some(s));
+    show(&None::<String>);
+    show(&String::new());
+    show(&String::from("hello").into_boxed_str());
+    show(&vec![1u16, 2, 3]);
+    show(&Vec::<u8>::new());
+
+    show(&Stats::new("héllo"));
+    let tokens: Vec<_> = token::tokenize("x = \"hi\"").collect();
+    show(&tokens[2]);
+    show(&tokens[2].kind);
+}
+
+fn compare_rope(n: usize) {
+    // 1 MB, the last line cut short
+    let sentence = "the quick brown fox jumps over the lazy dog\n";
+    let mut text = sentence.repeat((1 << 20) / sentence.len() + 1);
+    text.truncate(1 << 20);
+    let line = "hello world\n";
+
+    let start = Instant::now();
+    let mut s = text.clone();
+    for _ in 0..n {
+        // every insert moves the half of s that comes after it
+        let mid = s.len() / 2;
+        let mid = (mid..).find(|&i| s.is_char_boundary(i)).unwrap();
+        s.insert_str(mid, line);
+    }
+    let string_time = start.elapsed();
+
+    let start = Instant::now();
+    let mut rope = Rope::from(text.as_str());
+    for _ in 0..n {
+        let mid = rope.len_chars() / 2;
+        rope.insert(mid, line);
+    }
+    let rope_time = start.elapsed();
+
+    assert_eq!(rope.len_bytes(), s.len());
+    println!("{} lines typed into {} bytes of text", n, text.len());
+    println!("String  {:?}", string_time);
+    println!("Rope    {:?}  ({} lines, tree depth {})", rope_time, rope.len_lines(), rope.depth());
+}
+
+fn usage() -> ! {
+    eprintln!("usage: understanding_ownership [TOOL ARGS...]");
+    eprintln!();
+    eprintln!("tools:");
+    eprintln!("  allocs          run each lesson and count its heap allocations");
+    eprintln!("  graph FROM TO   the shortest route between two stations of a small rail map");
+    eprintln!("  intern FILE     intern every word of FILE, on one thread and on four");
+    eprintln!("  layout          size, alignment, fields and bytes of String, &str and friends");
+    eprintln!("  normalize TEXT  each cleanup of TEXT, and whether it had to copy it");
+    eprintln!("  wordcount FILE [--workers N]");
+    eprintln!("                  count the words of FILE with a reader thread, N workers");
+    eprintln!("                  (default 4) and a merge thread, and show the top 20");
+    eprintln!("  rope N          type N lines into the middle of a 1 MB text, with a String");
+    eprintln!("                  and with a Rope, and time both");
+    eprintln!("  text TEXT       bytes, chars, graphemes, words, lines and display width");
+    eprintln!("  tokens TEXT     split TEXT into words, numbers, quoted strings and punctuation");
+    eprintln!("  slice bytes|chars|graphemes TEXT START END");
+    eprintln!("                  TEXT[START..END] counting bytes, chars or graphemes.");
+    eprintln!("                  a byte offset inside a char moves to the char's edge");
+    eprintln!("  fit TEXT N      the most of TEXT that fits in N terminal columns");
+    std::process::exit(2);
+}
+
 fn what_is_ownership(){
     fn transfer_of_ownership(){
         let a = String::from("test");
@@ -73,4 +428,29 @@ fn what_is_ownership(){
         println!("{}", a); // test
     }
     return_of_ownership();
+
+    // the same two, with the moves and drops written down. see trace.rs
+    fn traced_ownership() {
+        let log = Log::new("traced_ownership");
+
+        let a = log.track("a", String::from("test"));
+        fn s(s: Tracked<String>) -> Tracked<String> {
+            println!("{}", *s.borrow()); // test
+            s
+        }
+        let mut a = s(a.moved("s()"));
+        a.borrow_mut().push('!');
+
+        // a clone is a second String. it's dropped at the end of
+        // this block, a lives on
+        {
+            let b = a.clone_as("b");
+            println!("{}", *b.borrow()); // test!
+        }
+
+        fn t(t: Tracked<String>) { println!("{}", *t.borrow()); } // test!
+        t(a.moved("t()"));
+        // a was dropped at the end of t(). log goes last and prints
+    }
+    traced_ownership();
 }
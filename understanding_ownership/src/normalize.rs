@@ -0,0 +1,149 @@
+// fn1(s: &String) only borrows, and fn2(s: &mut String) changes the
+// caller's String in place. a cleanup function like "collapse the
+// spaces" sits in between: usually the text is fine as it is and a
+// borrow would do, sometimes it needs a new String. Cow<str> (clone
+// on write) is either one:
+//
+//   Cow::Borrowed(&str)   the input was already clean, nothing copied
+//   Cow::Owned(String)    the cleaned up copy
+//
+// every function here returns Cow::Borrowed, pointing into the input,
+// unless the text actually changes. owned() counts the times it had
+// to allocate, per thread, so a test can check that clean input
+// didn't:
+//
+//   let before = normalize::allocations();
+//   collapse_whitespace("already fine");
+//   assert_eq!(normalize::allocations(), before);
+
+use std::borrow::Cow;
+use std::cell::Cell;
+
+use caseless::Caseless;
+use unicode_normalization::{is_nfc_quick, IsNormalized, UnicodeNormalization};
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+// how many times a function here returned a new String on this thread
+pub fn allocations() -> usize {
+    ALLOCATIONS.with(Cell::get)
+}
+
+fn owned<'a>(s: String) -> Cow<'a, str> {
+    ALLOCATIONS.with(|n| n.set(n.get() + 1));
+    Cow::Owned(s)
+}
+
+// whitespace off both ends. a &str can be narrowed without a copy, so
+// this never allocates
+pub fn trim(s: &str) -> Cow<'_, str> {
+    Cow::Borrowed(s.trim())
+}
+
+// every run of whitespace, tabs and newlines too, becomes one space.
+// the ends are left alone, trim() does those
+pub fn collapse_whitespace(s: &str) -> Cow<'_, str> {
+    let mut prev_space = false;
+    let clean = s.chars().all(|c| {
+        let ok = if c.is_whitespace() { c == ' ' && !prev_space } else { true };
+        prev_space = c.is_whitespace();
+        ok
+    });
+    if clean {
+        return Cow::Borrowed(s);
+    }
+    let mut out = String::with_capacity(s.len());
+    let mut prev_space = false;
+    for c in s.chars() {
+        if !c.is_whitespace() {
+            out.push(c);
+        } else if !prev_space {
+            out.push(' ');
+        }
+        prev_space = c.is_whitespace();
+    }
+    owned(out)
+}
+
+// Unicode case folding, for comparing text without caring about case.
+// it's more than lowercase: "Straße" and "STRASSE" both fold to
+// "strasse"
+pub fn fold_case(s: &str) -> Cow<'_, str> {
+    if s.chars().eq(s.chars().default_case_fold()) {
+        return Cow::Borrowed(s);
+    }
+    owned(s.chars().default_case_fold().collect())
+}
+
+// the same text can be different chars: "é" is U+00E9, or 'e' and
+// U+0301 (a combining accent). NFC is the form that uses the single
+// char where there is one, so equal looking text is equal bytes
+pub fn nfc(s: &str) -> Cow<'_, str> {
+    let already = match is_nfc_quick(s.chars()) {
+        IsNormalized::Yes => true,
+        IsNormalized::No => false,
+        // only a full check can tell
+        IsNormalized::Maybe => s.chars().eq(s.nfc()),
+    };
+    if already {
+        return Cow::Borrowed(s);
+    }
+    owned(s.nfc().collect())
+}
+
+// & < > " and ' as HTML entities
+pub fn escape_html(s: &str) -> Cow<'_, str> {
+    let entity = |c: char| match c {
+        '&' => Some("&amp;"),
+        '<' => Some("&lt;"),
+        '>' => Some("&gt;"),
+        '"' => Some("&quot;"),
+        '\'' => Some("&#39;"),
+        _ => None,
+    };
+    // everything before the first char that needs escaping is copied
+    // as it is
+    let first = match s.char_indices().find(|&(_, c)| entity(c).is_some()) {
+        Some((i, _)) => i,
+        None => return Cow::Borrowed(s),
+    };
+    let mut out = String::with_capacity(s.len() + 16);
+    out.push_str(&s[..first]);
+    for c in s[first..].chars() {
+        match entity(c) {
+            Some(e) => out.push_str(e),
+            None => out.push(c),
+        }
+    }
+    owned(out)
+}
+
+// quotes, backslashes and control chars escaped like Rust writes them
+// in a string literal, the same as str::escape_debug
+pub fn escape_debug(s: &str) -> Cow<'_, str> {
+    if s.escape_debug().eq(s.chars()) {
+        return Cow::Borrowed(s);
+    }
+    owned(s.escape_debug().to_string())
+}
+
+// f applied to what a Cow holds. borrowed stays borrowed from the
+// original input as long as f doesn't change anything
+fn then<'a>(s: Cow<'a, str>, f: impl Fn(&str) -> Cow<'_, str>) -> Cow<'a, str> {
+    match s {
+        Cow::Borrowed(s) => f(s),
+        Cow::Owned(s) => match f(&s) {
+            Cow::Owned(changed) => Cow::Owned(changed),
+            // unchanged, keep the String we already have
+            Cow::Borrowed(_) => Cow::Owned(s),
+        },
+    }
+}
+
+// trimmed, whitespace collapsed and NFC. what to do with text from a
+// user before storing or comparing it
+pub fn normalize(s: &str) -> Cow<'_, str> {
+    then(then(trim(s), collapse_whitespace), nfc)
+}
@@ -0,0 +1,156 @@
+// the ownership rules work the same across threads. sending a value
+// over a channel moves it: the sender can't use it anymore and the
+// receiver owns it, and only the String's pointer, length and
+// capacity are copied, not its text. so a word count can be a
+// pipeline where each line is owned by one stage at a time:
+//
+//   reader ──lines──> worker 1 ──counts──┐
+//          ──lines──> worker 2 ──counts──┼──> merge
+//          ──lines──> ...      ──counts──┘
+//
+//   reader   owns the input, reads it into Strings, and moves them
+//            to the workers in batches, taking turns
+//   workers  own a batch at a time, count its words, and drop it
+//   merge    adds up the counts in the order the batches were read
+//
+// the channels are bounded: when a worker falls behind the reader
+// waits instead of reading the whole input into memory (backpressure).
+// and the output doesn't depend on which thread finished first: the
+// merge puts the batches back in order, and the words are sorted by
+// count and then alphabetically.
+
+use std::collections::{BTreeMap, HashMap};
+use std::io::{self, BufRead};
+use std::sync::mpsc::{sync_channel, Receiver, SyncSender};
+use std::thread;
+
+use crate::token::{self, Kind};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Config {
+    pub workers: usize,
+    // lines a batch
+    pub batch: usize,
+    // batches that can wait in each channel before the sender blocks
+    pub capacity: usize,
+}
+
+impl Default for Config {
+    fn default() -> Config {
+        Config { workers: 4, batch: 256, capacity: 4 }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Counts {
+    pub lines: usize,
+    pub words: usize,
+    // most common first, ties in alphabetical order
+    pub counts: Vec<(String, usize)>,
+}
+
+struct Batch {
+    // where it was in the input, for putting the results back in order
+    seq: usize,
+    lines: Vec<String>,
+}
+
+struct Tally {
+    seq: usize,
+    lines: usize,
+    counts: HashMap<String, usize>,
+}
+
+// words are lowercased so "The" and "the" count together
+fn count(batch: Batch) -> Tally {
+    let mut counts = HashMap::new();
+    for line in &batch.lines {
+        for t in token::tokenize(line).filter(|t| t.kind == Kind::Word) {
+            *counts.entry(t.text.to_lowercase()).or_insert(0) += 1;
+        }
+    }
+    Tally { seq: batch.seq, lines: batch.lines.len(), counts }
+    // batch.lines are dropped here, on the worker's thread
+}
+
+fn read<R: BufRead>(input: R, workers: Vec<SyncSender<Batch>>, batch_size: usize) -> io::Result<()> {
+    let mut lines = Vec::with_capacity(batch_size);
+    let mut seq = 0;
+    let send = |lines: Vec<String>, seq: &mut usize| {
+        let worker = &workers[*seq % workers.len()];
+        // only fails when the worker panicked, word_count reports that
+        let _ = worker.send(Batch { seq: *seq, lines });
+        *seq += 1;
+    };
+    for line in input.lines() {
+        lines.push(line?);
+        if lines.len() == batch_size {
+            send(std::mem::replace(&mut lines, Vec::with_capacity(batch_size)), &mut seq);
+        }
+    }
+    if !lines.is_empty() {
+        send(lines, &mut seq);
+    }
+    Ok(())
+    // dropping the senders tells the workers there's no more
+}
+
+fn merge(tallies: Receiver<Tally>) -> Counts {
+    let mut totals: HashMap<String, usize> = HashMap::new();
+    let mut lines = 0;
+    // tallies that arrived before the ones they come after
+    let mut waiting = BTreeMap::new();
+    let mut next = 0;
+    for tally in tallies {
+        waiting.insert(tally.seq, tally);
+        while let Some(tally) = waiting.remove(&next) {
+            lines += tally.lines;
+            for (word, n) in tally.counts {
+                *totals.entry(word).or_insert(0) += n;
+            }
+            next += 1;
+        }
+    }
+    // only a worker that panicked loses a batch, and word_count reports
+    // that when it joins the workers
+    debug_assert!(waiting.is_empty(), "batch {} never arrived", next);
+
+    let mut counts: Vec<(String, usize)> = totals.into_iter().collect();
+    counts.sort_by(|(a, x), (b, y)| y.cmp(x).then_with(|| a.cmp(b)));
+    Counts { lines, words: counts.iter().map(|(_, n)| n).sum(), counts }
+}
+
+// reads input to the end and counts its words. input is moved to the
+// reader thread, so it has to be Send and own what it reads from
+pub fn word_count<R: BufRead + Send + 'static>(input: R, config: Config) -> io::Result<Counts> {
+    assert!(config.workers > 0 && config.batch > 0, "need at least one worker and one line a batch");
+    let (tally_tx, tally_rx) = sync_channel(config.capacity);
+
+    let mut senders = Vec::new();
+    let mut workers = Vec::new();
+    for _ in 0..config.workers {
+        let (tx, rx) = sync_channel::<Batch>(config.capacity);
+        let tally_tx = tally_tx.clone();
+        senders.push(tx);
+        workers.push(thread::spawn(move || {
+            for batch in rx {
+                if tally_tx.send(count(batch)).is_err() {
+                    break;
+                }
+            }
+        }));
+    }
+    // the workers hold the only senders now, so merge stops when
+    // they're all done
+    drop(tally_tx);
+
+    let reader = thread::spawn(move || read(input, senders, config.batch));
+    let merger = thread::spawn(move || merge(tally_rx));
+
+    let read_result = reader.join().expect("reader panicked");
+    for worker in workers {
+        worker.join().expect("worker panicked");
+    }
+    let counts = merger.join().expect("merge panicked");
+    read_result.map(|()| counts)
+}
@@ -0,0 +1,406 @@
+// fn2 in references_and_borrowing() appends to a String. appending is
+// cheap, but inserting or deleting in the middle of a String moves
+// every byte after that point, so an editor holding a big file in
+// one String does a lot of copying per keystroke.
+//
+// a rope keeps the text in small chunks at the leaves of a balanced
+// tree. every node knows how many chars, bytes and newlines are
+// below it, so finding char n or line n walks one path down. an edit
+// splits the tree at a position and joins pieces back together,
+// which touches O(log n) nodes and copies at most a chunk or two.
+//
+// the nodes are shared through Rc and never changed once built: an
+// edit builds new nodes along its path and reuses everything else.
+// so cloning a Rope is one Rc clone, and the clone and the original
+// share all the nodes the later edits don't touch.
+//
+// positions are char indexes, like s.chars().nth(i), and panic when
+// they're past the end, like &s[i..] does.
+
+use std::fmt;
+use std::ops::Range;
+use std::rc::Rc;
+
+// chunks are at most this many bytes, smaller ones are merged when
+// two meet
+const MAX_LEAF: usize = 1024;
+
+enum Node {
+    // the chars and newlines are counted once, when it's made
+    Leaf { text: String, chars: usize, newlines: usize },
+    Branch {
+        left: Rc<Node>,
+        right: Rc<Node>,
+        chars: usize,
+        bytes: usize,
+        newlines: usize,
+        height: usize,
+    },
+}
+
+impl Node {
+    fn chars(&self) -> usize {
+        match self {
+            Node::Leaf { chars, .. } | Node::Branch { chars, .. } => *chars,
+        }
+    }
+
+    fn bytes(&self) -> usize {
+        match self {
+            Node::Leaf { text, .. } => text.len(),
+            Node::Branch { bytes, .. } => *bytes,
+        }
+    }
+
+    fn newlines(&self) -> usize {
+        match self {
+            Node::Leaf { newlines, .. } | Node::Branch { newlines, .. } => *newlines,
+        }
+    }
+
+    fn height(&self) -> usize {
+        match self {
+            Node::Leaf { .. } => 0,
+            Node::Branch { height, .. } => *height,
+        }
+    }
+}
+
+fn leaf(text: String) -> Rc<Node> {
+    let chars = text.chars().count();
+    let newlines = text.bytes().filter(|&b| b == b'\n').count();
+    Rc::new(Node::Leaf { text, chars, newlines })
+}
+
+// a branch as it is, without rebalancing
+fn branch(left: Rc<Node>, right: Rc<Node>) -> Rc<Node> {
+    Rc::new(Node::Branch {
+        chars: left.chars() + right.chars(),
+        bytes: left.bytes() + right.bytes(),
+        newlines: left.newlines() + right.newlines(),
+        height: left.height().max(right.height()) + 1,
+        left,
+        right,
+    })
+}
+
+fn children(node: &Rc<Node>) -> (Rc<Node>, Rc<Node>) {
+    match &**node {
+        Node::Branch { left, right, .. } => (Rc::clone(left), Rc::clone(right)),
+        Node::Leaf { .. } => unreachable!("a leaf has no children"),
+    }
+}
+
+// a branch of two nodes whose heights differ by at most 2, rotated
+// so they differ by at most 1 again, like an AVL tree
+fn balance(left: Rc<Node>, right: Rc<Node>) -> Rc<Node> {
+    let (l, r) = (left.height(), right.height());
+    if r > l + 1 {
+        let (rl, rr) = children(&right);
+        if rl.height() > rr.height() {
+            let (rll, rlr) = children(&rl);
+            return branch(branch(left, rll), branch(rlr, rr));
+        }
+        return branch(branch(left, rl), rr);
+    }
+    if l > r + 1 {
+        let (ll, lr) = children(&left);
+        if lr.height() > ll.height() {
+            let (lrl, lrr) = children(&lr);
+            return branch(branch(ll, lrl), branch(lrr, right));
+        }
+        return branch(ll, branch(lr, right));
+    }
+    branch(left, right)
+}
+
+// left followed by right, balanced. goes down the taller side until
+// the heights are close, so it's O(difference in height)
+fn join(left: Rc<Node>, right: Rc<Node>) -> Rc<Node> {
+    if left.bytes() == 0 {
+        return right;
+    }
+    if right.bytes() == 0 {
+        return left;
+    }
+    if let (Node::Leaf { text: a, .. }, Node::Leaf { text: b, .. }) = (&*left, &*right) {
+        if a.len() + b.len() <= MAX_LEAF {
+            return leaf(format!("{}{}", a, b));
+        }
+    }
+    let (l, r) = (left.height(), right.height());
+    if l > r + 1 {
+        let (ll, lr) = children(&left);
+        return balance(ll, join(lr, right));
+    }
+    if r > l + 1 {
+        let (rl, rr) = children(&right);
+        return balance(join(left, rl), rr);
+    }
+    branch(left, right)
+}
+
+// the first `at` chars and the rest
+fn split(node: &Rc<Node>, at: usize) -> (Rc<Node>, Rc<Node>) {
+    match &**node {
+        Node::Leaf { text, .. } => {
+            let i = text.char_indices().nth(at).map_or(text.len(), |(i, _)| i);
+            (leaf(text[..i].to_string()), leaf(text[i..].to_string()))
+        }
+        Node::Branch { left, right, .. } => {
+            let n = left.chars();
+            if at == n {
+                (Rc::clone(left), Rc::clone(right))
+            } else if at < n {
+                let (a, b) = split(left, at);
+                (a, join(b, Rc::clone(right)))
+            } else {
+                let (a, b) = split(right, at - n);
+                (join(Rc::clone(left), a), b)
+            }
+        }
+    }
+}
+
+// a balanced tree of MAX_LEAF sized chunks of text
+fn build(text: &str) -> Rc<Node> {
+    if text.len() <= MAX_LEAF {
+        return leaf(text.to_string());
+    }
+    let mut mid = text.len() / 2;
+    while !text.is_char_boundary(mid) {
+        mid += 1;
+    }
+    branch(build(&text[..mid]), build(&text[mid..]))
+}
+
+#[derive(Clone)]
+pub struct Rope {
+    root: Rc<Node>,
+}
+
+impl Rope {
+    pub fn new() -> Rope {
+        Rope { root: leaf(String::new()) }
+    }
+
+    pub fn len_chars(&self) -> usize {
+        self.root.chars()
+    }
+
+    pub fn len_bytes(&self) -> usize {
+        self.root.bytes()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len_bytes() == 0
+    }
+
+    // like str::lines() plus one for a trailing newline: "a\nb" and
+    // "a\n" both have 2 lines, "" has 1
+    pub fn len_lines(&self) -> usize {
+        self.root.newlines() + 1
+    }
+
+    fn check(&self, at: usize) {
+        let len = self.len_chars();
+        assert!(at <= len, "char index {} is past the end of the rope ({})", at, len);
+    }
+
+    fn check_range(&self, range: &Range<usize>) {
+        assert!(range.start <= range.end, "range {:?} starts after it ends", range);
+        self.check(range.end);
+    }
+
+    pub fn insert(&mut self, at: usize, text: &str) {
+        self.check(at);
+        let (a, b) = split(&self.root, at);
+        self.root = join(join(a, build(text)), b);
+    }
+
+    pub fn remove(&mut self, range: Range<usize>) {
+        self.check_range(&range);
+        let (a, rest) = split(&self.root, range.start);
+        let (_, b) = split(&rest, range.end - range.start);
+        self.root = join(a, b);
+    }
+
+    pub fn push_str(&mut self, text: &str) {
+        self.root = join(Rc::clone(&self.root), build(text));
+    }
+
+    // the chars in range, sharing nodes with self
+    pub fn slice(&self, range: Range<usize>) -> Rope {
+        self.check_range(&range);
+        let (_, rest) = split(&self.root, range.start);
+        let (middle, _) = split(&rest, range.end - range.start);
+        Rope { root: middle }
+    }
+
+    pub fn char(&self, at: usize) -> char {
+        let len = self.len_chars();
+        assert!(at < len, "char index {} is past the end of the rope ({})", at, len);
+        let (mut node, mut at) = (&self.root, at);
+        loop {
+            match &**node {
+                Node::Leaf { text, .. } => return text.chars().nth(at).unwrap(),
+                Node::Branch { left, right, .. } => {
+                    if at < left.chars() {
+                        node = left;
+                    } else {
+                        at -= left.chars();
+                        node = right;
+                    }
+                }
+            }
+        }
+    }
+
+    // the char index where line n starts, counting from 0
+    pub fn line_to_char(&self, line: usize) -> usize {
+        let lines = self.len_lines();
+        assert!(line < lines, "line {} is past the end of the rope ({} lines)", line, lines);
+        // the char just after the line-th newline
+        let (mut node, mut line, mut chars) = (&self.root, line, 0);
+        if line == 0 {
+            return 0;
+        }
+        loop {
+            match &**node {
+                Node::Leaf { text, .. } => {
+                    let (i, _) = text.chars().enumerate().filter(|&(_, c)| c == '\n').nth(line - 1).unwrap();
+                    return chars + i + 1;
+                }
+                Node::Branch { left, right, .. } => {
+                    if line <= left.newlines() {
+                        node = left;
+                    } else {
+                        line -= left.newlines();
+                        chars += left.chars();
+                        node = right;
+                    }
+                }
+            }
+        }
+    }
+
+    // the line char `at` is on
+    pub fn char_to_line(&self, at: usize) -> usize {
+        self.check(at);
+        let (mut node, mut at, mut line) = (&self.root, at, 0);
+        loop {
+            match &**node {
+                Node::Leaf { text, .. } => {
+                    return line + text.chars().take(at).filter(|&c| c == '\n').count();
+                }
+                Node::Branch { left, right, .. } => {
+                    if at < left.chars() {
+                        node = left;
+                    } else {
+                        at -= left.chars();
+                        line += left.newlines();
+                        node = right;
+                    }
+                }
+            }
+        }
+    }
+
+    // line n with its newline, if it has one
+    pub fn line(&self, line: usize) -> Rope {
+        let start = self.line_to_char(line);
+        let end = if line + 1 < self.len_lines() { self.line_to_char(line + 1) } else { self.len_chars() };
+        self.slice(start..end)
+    }
+
+    // the text in order, a borrowed chunk at a time
+    pub fn chunks(&self) -> Chunks<'_> {
+        Chunks { stack: vec![&self.root] }
+    }
+
+    // how tall the tree is, for checking it stays balanced
+    pub fn depth(&self) -> usize {
+        self.root.height()
+    }
+}
+
+impl Default for Rope {
+    fn default() -> Rope {
+        Rope::new()
+    }
+}
+
+pub struct Chunks<'a> {
+    // nodes still to visit, the next one last
+    stack: Vec<&'a Rc<Node>>,
+}
+
+impl<'a> Iterator for Chunks<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        loop {
+            match &***self.stack.last()? {
+                Node::Leaf { text, .. } => {
+                    self.stack.pop();
+                    if !text.is_empty() {
+                        return Some(text);
+                    }
+                }
+                Node::Branch { left, right, .. } => {
+                    self.stack.pop();
+                    self.stack.push(right);
+                    self.stack.push(left);
+                }
+            }
+        }
+    }
+}
+
+impl From<&str> for Rope {
+    fn from(text: &str) -> Rope {
+        Rope { root: build(text) }
+    }
+}
+
+impl From<String> for Rope {
+    fn from(text: String) -> Rope {
+        Rope::from(text.as_str())
+    }
+}
+
+impl From<&Rope> for String {
+    fn from(rope: &Rope) -> String {
+        let mut s = String::with_capacity(rope.len_bytes());
+        rope.chunks().for_each(|chunk| s.push_str(chunk));
+        s
+    }
+}
+
+impl From<Rope> for String {
+    fn from(rope: Rope) -> String {
+        String::from(&rope)
+    }
+}
+
+impl fmt::Display for Rope {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.chunks().try_for_each(|chunk| f.write_str(chunk))
+    }
+}
+
+impl fmt::Debug for Rope {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Rope({:?})", String::from(self))
+    }
+}
+
+impl PartialEq for Rope {
+    // the chunks can be split in different places, so compare bytes
+    fn eq(&self, other: &Rope) -> bool {
+        let bytes = |rope: &Rope| rope.chunks().flat_map(str::bytes).collect::<Vec<u8>>();
+        self.len_bytes() == other.len_bytes() && bytes(self) == bytes(other)
+    }
+}
+
+impl Eq for Rope {}
@@ -0,0 +1,127 @@
+// fn1 in references_and_borrowing() returns s.len(), and for
+// "hello" that's 5. but len() is the number of UTF-8 bytes, not
+// letters: "héllo" is 6 and "👋" is 4. there are several honest
+// answers to "how long is this text":
+//
+//   bytes      s.len(), what the String owns on the heap
+//   chars      s.chars().count(), Unicode scalar values
+//   graphemes  what a reader calls a character: "é" written as
+//              'e' + U+0301 is 2 chars but 1 grapheme
+//   width      terminal columns: "日本" is 2 chars but 4 columns
+//
+// and slicing a &str takes byte offsets. &s[0..1] on "héllo" is
+// fine, &s[0..2] panics because byte 2 is in the middle of 'é'. the
+// slice functions here move the offsets to the nearest char (or
+// grapheme) boundary instead of panicking.
+
+use std::fmt;
+use std::ops::Range;
+
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Stats {
+    pub bytes: usize,
+    pub chars: usize,
+    pub graphemes: usize,
+    // runs of letters and digits, so "don't stop!" is 2 words and
+    // "--" is none
+    pub words: usize,
+    // like str::lines(): a trailing newline doesn't start a new line
+    pub lines: usize,
+    // terminal columns
+    pub width: usize,
+}
+
+impl Stats {
+    pub fn new(s: &str) -> Stats {
+        Stats {
+            bytes: s.len(),
+            chars: s.chars().count(),
+            graphemes: s.graphemes(true).count(),
+            words: s.unicode_words().count(),
+            lines: s.lines().count(),
+            // per line, a newline has no width
+            width: s.lines().map(|line| line.width()).max().unwrap_or(0),
+        }
+    }
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "bytes      {}", self.bytes)?;
+        writeln!(f, "chars      {}", self.chars)?;
+        writeln!(f, "graphemes  {}", self.graphemes)?;
+        writeln!(f, "words      {}", self.words)?;
+        writeln!(f, "lines      {}", self.lines)?;
+        write!(f, "width      {}", self.width)
+    }
+}
+
+// the char boundary at or before byte i. past the end is the end
+pub fn floor_boundary(s: &str, i: usize) -> usize {
+    if i >= s.len() {
+        return s.len();
+    }
+    // a UTF-8 char is at most 4 bytes, so this loops at most 3 times
+    (0..=i).rev().find(|&j| s.is_char_boundary(j)).unwrap()
+}
+
+// the char boundary at or after byte i
+pub fn ceil_boundary(s: &str, i: usize) -> usize {
+    if i >= s.len() {
+        return s.len();
+    }
+    (i..=s.len()).find(|&j| s.is_char_boundary(j)).unwrap()
+}
+
+// &s[range], but a start or end in the middle of a char moves
+// inward so the partial char is left out, an end past the string is
+// the end, and a start after the end is "". never panics
+pub fn slice_bytes(s: &str, range: Range<usize>) -> &str {
+    let start = ceil_boundary(s, range.start);
+    let end = floor_boundary(s, range.end);
+    if start >= end {
+        return "";
+    }
+    &s[start..end]
+}
+
+// the chars in range, counting chars instead of bytes
+pub fn slice_chars(s: &str, range: Range<usize>) -> &str {
+    let mut offsets = s.char_indices().map(|(i, _)| i).chain(Some(s.len()));
+    slice_by(s, &mut offsets, range)
+}
+
+// the same for graphemes, so an accent is never cut off its letter
+pub fn slice_graphemes(s: &str, range: Range<usize>) -> &str {
+    let mut offsets = s.grapheme_indices(true).map(|(i, _)| i).chain(Some(s.len()));
+    slice_by(s, &mut offsets, range)
+}
+
+// offsets are the byte offsets where each piece starts, then s.len()
+fn slice_by<'a>(s: &'a str, offsets: &mut dyn Iterator<Item = usize>, range: Range<usize>) -> &'a str {
+    if range.start >= range.end {
+        return "";
+    }
+    let start = match offsets.nth(range.start) {
+        Some(start) => start,
+        None => return "",
+    };
+    let end = offsets.nth(range.end - range.start - 1).unwrap_or(s.len());
+    &s[start..end]
+}
+
+// at most n terminal columns of s, without splitting a grapheme. a
+// wide grapheme that doesn't fit is left out
+pub fn truncate_width(s: &str, n: usize) -> &str {
+    let mut width = 0;
+    for (i, g) in s.grapheme_indices(true) {
+        width += g.width();
+        if width > n {
+            return &s[..i];
+        }
+    }
+    s
+}
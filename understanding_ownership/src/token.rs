@@ -0,0 +1,155 @@
+// a &str is a pointer and a length into bytes someone else owns, so
+// splitting text into pieces doesn't need to copy it: every Token
+// here is a slice of the input plus where it was found. the input
+// has to outlive the tokens, which is what the 'a says:
+//
+//   let text = String::from("x = 1");
+//   let first = tokenize(&text).next();  // borrows text
+//   drop(text);                          // error: text is still borrowed
+//   println!("{:?}", first);
+//
+// the kinds of token:
+//
+//   word     a letter or _ and then letters, digits, _ and ' (don't)
+//   number   digits and _, with an optional .digits part (1_000.5)
+//   quoted   "..." with \" and \\ escapes left as they are
+//   punct    any other single char
+//
+// whitespace separates tokens and is skipped.
+
+use std::ops::Range;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    Word,
+    Number,
+    // closed is false when the text ended before the closing "
+    Quoted { closed: bool },
+    Punct,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Token<'a> {
+    pub kind: Kind,
+    pub text: &'a str,
+    // byte offsets, &input[span] == text
+    pub span: Range<usize>,
+}
+
+impl<'a> Token<'a> {
+    // a quoted token without its quotes. escapes are still escaped,
+    // unescaping would need a new String
+    pub fn unquoted(&self) -> &'a str {
+        match self.kind {
+            Kind::Quoted { closed: true } => &self.text[1..self.text.len() - 1],
+            Kind::Quoted { closed: false } => &self.text[1..],
+            _ => self.text,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Tokenizer<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+pub fn tokenize<'a>(input: &'a str) -> Tokenizer<'a> {
+    Tokenizer { input, pos: 0 }
+}
+
+impl<'a> Tokenizer<'a> {
+    // what's left to tokenize
+    pub fn rest(&self) -> &'a str {
+        &self.input[self.pos..]
+    }
+
+    // the byte offset just after the chars that match, from start
+    fn end_of(&self, start: usize, mut matches: impl FnMut(char) -> bool) -> usize {
+        self.input[start..]
+            .char_indices()
+            .find(|&(_, c)| !matches(c))
+            .map_or(self.input.len(), |(i, _)| start + i)
+    }
+
+    fn number(&self, start: usize) -> usize {
+        let digits = |c: char| c.is_ascii_digit() || c == '_';
+        let end = self.end_of(start, digits);
+        // 1.5 but not 1. or 1.x, so `1..3` and `x.0.y` stay apart
+        let mut rest = self.input[end..].chars();
+        if rest.next() == Some('.') && rest.next().is_some_and(|c| c.is_ascii_digit()) {
+            return self.end_of(end + 1, digits);
+        }
+        end
+    }
+
+    fn quoted(&self, start: usize) -> (usize, bool) {
+        let mut escaped = false;
+        for (i, c) in self.input[start + 1..].char_indices() {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => return (start + 1 + i + 1, true),
+                _ => {}
+            }
+        }
+        (self.input.len(), false)
+    }
+}
+
+impl<'a> Iterator for Tokenizer<'a> {
+    type Item = Token<'a>;
+
+    fn next(&mut self) -> Option<Token<'a>> {
+        let start = self.end_of(self.pos, char::is_whitespace);
+        let c = self.input[start..].chars().next()?;
+        let (kind, end) = match c {
+            '"' => {
+                let (end, closed) = self.quoted(start);
+                (Kind::Quoted { closed }, end)
+            }
+            _ if c.is_ascii_digit() => (Kind::Number, self.number(start)),
+            _ if c.is_alphabetic() || c == '_' => {
+                let end = self.end_of(start, |c| c.is_alphanumeric() || c == '_' || c == '\'');
+                // the ' in don't, not the one in 'quoted'
+                let end = start + self.input[start..end].trim_end_matches('\'').len();
+                (Kind::Word, end)
+            }
+            _ => (Kind::Punct, start + c.len_utf8()),
+        };
+        self.pos = end;
+        Some(Token { kind, text: &self.input[start..end], span: start..end })
+    }
+}
+
+// tokens from every &str an iterator gives, like the lines of a
+// file. each comes with the index of the &str it's from, and its
+// span is within that &str
+pub trait TokenizeExt<'a>: Iterator<Item = &'a str> + Sized {
+    fn tokens(self) -> Tokens<'a, Self> {
+        Tokens { inner: self, index: 0, current: None }
+    }
+}
+
+impl<'a, I: Iterator<Item = &'a str>> TokenizeExt<'a> for I {}
+
+pub struct Tokens<'a, I> {
+    inner: I,
+    // index of the next &str to take from inner
+    index: usize,
+    current: Option<Tokenizer<'a>>,
+}
+
+impl<'a, I: Iterator<Item = &'a str>> Iterator for Tokens<'a, I> {
+    type Item = (usize, Token<'a>);
+
+    fn next(&mut self) -> Option<(usize, Token<'a>)> {
+        loop {
+            if let Some(token) = self.current.as_mut().and_then(Iterator::next) {
+                return Some((self.index - 1, token));
+            }
+            self.current = Some(tokenize(self.inner.next()?));
+            self.index += 1;
+        }
+    }
+}
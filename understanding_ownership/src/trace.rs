@@ -0,0 +1,239 @@
+// transfer_of_ownership() and return_of_ownership() say where a
+// String moves and when it's dropped, but only in comments. wrap the
+// value in a Tracked and it writes those things down instead:
+//
+//   let log = Log::new("return_of_ownership");
+//   let a = log.track("a", String::from("test"));
+//   let a = s(a.moved("s()"));
+//   println!("{}", *a.borrow());
+//   // log is dropped last (it was declared first) and prints:
+//
+//   timeline of return_of_ownership
+//     1  a#1  created            src/main.rs:10:13
+//     2  a#1  moved to s()       src/main.rs:11:15
+//     3  a#1  borrowed (&)       src/main.rs:12:22
+//     4  a#1  borrow ended
+//     5  a#1  dropped
+//
+// creating, cloning, borrowing and dropping are recorded on their
+// own. a move can't be: it's a plain copy of the bytes, no code of
+// ours runs. so moves are written down by calling moved() where the
+// value is handed over. each value gets a number, a clone is a new
+// value with a new number.
+//
+// borrow() and borrow_mut() give guards like RefCell does, and the
+// borrow ends when the guard is dropped. the log lives in an
+// Rc<RefCell<..>> that every Tracked shares, so it's single threaded.
+
+use std::cell::RefCell;
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+use std::panic::Location;
+use std::rc::Rc;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Action {
+    Created,
+    // the number of the value it was cloned from
+    ClonedFrom(usize),
+    MovedTo(String),
+    Borrowed,
+    BorrowEnded,
+    BorrowedMut,
+    BorrowMutEnded,
+    Dropped,
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Action::Created => write!(f, "created"),
+            Action::ClonedFrom(id) => write!(f, "cloned from #{}", id),
+            Action::MovedTo(to) => write!(f, "moved to {}", to),
+            Action::Borrowed => write!(f, "borrowed (&)"),
+            Action::BorrowEnded => write!(f, "borrow ended"),
+            Action::BorrowedMut => write!(f, "borrowed (&mut)"),
+            Action::BorrowMutEnded => write!(f, "&mut borrow ended"),
+            Action::Dropped => write!(f, "dropped"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Event {
+    pub id: usize,
+    pub name: String,
+    pub action: Action,
+    // where in the source it happened. None for drops: Drop::drop
+    // can't know where it was called from
+    pub location: Option<&'static Location<'static>>,
+}
+
+#[derive(Default)]
+struct Inner {
+    events: Vec<Event>,
+    next_id: usize,
+}
+
+// the log a scope's Tracked values write to. prints the timeline
+// when it's dropped
+pub struct Log {
+    title: String,
+    inner: Rc<RefCell<Inner>>,
+}
+
+impl Log {
+    pub fn new(title: &str) -> Log {
+        Log { title: title.to_string(), inner: Rc::default() }
+    }
+
+    #[track_caller]
+    pub fn track<T>(&self, name: &str, value: T) -> Tracked<T> {
+        Tracked::new(&self.inner, name, value, Action::Created)
+    }
+
+    pub fn events(&self) -> Vec<Event> {
+        self.inner.borrow().events.clone()
+    }
+
+    pub fn timeline(&self) -> String {
+        let events = &self.inner.borrow().events;
+        let width = events.iter().map(|e| e.name.len() + e.id.to_string().len() + 1).max().unwrap_or(0);
+        let mut out = format!("timeline of {}\n", self.title);
+        for (i, e) in events.iter().enumerate() {
+            let who = format!("{}#{}", e.name, e.id);
+            let line = match e.location {
+                Some(at) => format!("{:>3}  {:<w$}  {:<18} {}", i + 1, who, e.action.to_string(), at, w = width),
+                None => format!("{:>3}  {:<w$}  {}", i + 1, who, e.action, w = width),
+            };
+            out += line.trim_end();
+            out += "\n";
+        }
+        out
+    }
+}
+
+impl Drop for Log {
+    fn drop(&mut self) {
+        print!("{}", self.timeline());
+    }
+}
+
+pub struct Tracked<T> {
+    id: usize,
+    name: String,
+    value: T,
+    log: Rc<RefCell<Inner>>,
+}
+
+impl<T> Tracked<T> {
+    #[track_caller]
+    fn new(log: &Rc<RefCell<Inner>>, name: &str, value: T, action: Action) -> Tracked<T> {
+        let id = {
+            let mut inner = log.borrow_mut();
+            inner.next_id += 1;
+            inner.next_id
+        };
+        let tracked = Tracked { id, name: name.to_string(), value, log: Rc::clone(log) };
+        tracked.record(action, Some(Location::caller()));
+        tracked
+    }
+
+    pub fn id(&self) -> usize {
+        self.id
+    }
+
+    fn record(&self, action: Action, location: Option<&'static Location<'static>>) {
+        let event = Event { id: self.id, name: self.name.clone(), action, location };
+        self.log.borrow_mut().events.push(event);
+    }
+
+    // hand the value over to `to`, e.g. `s(a.moved("s()"))`
+    #[track_caller]
+    pub fn moved(self, to: &str) -> Tracked<T> {
+        self.record(Action::MovedTo(to.to_string()), Some(Location::caller()));
+        self
+    }
+
+    #[track_caller]
+    pub fn borrow(&self) -> Ref<'_, T> {
+        self.record(Action::Borrowed, Some(Location::caller()));
+        Ref { tracked: self }
+    }
+
+    #[track_caller]
+    pub fn borrow_mut(&mut self) -> RefMut<'_, T> {
+        self.record(Action::BorrowedMut, Some(Location::caller()));
+        RefMut { tracked: self }
+    }
+}
+
+impl<T: Clone> Tracked<T> {
+    // a clone with a name of its own, `let b = a.clone_as("b")`
+    #[track_caller]
+    pub fn clone_as(&self, name: &str) -> Tracked<T> {
+        Tracked::new(&self.log, name, self.value.clone(), Action::ClonedFrom(self.id))
+    }
+}
+
+// clone() keeps the name, the number tells them apart
+impl<T: Clone> Clone for Tracked<T> {
+    #[track_caller]
+    fn clone(&self) -> Tracked<T> {
+        self.clone_as(&self.name)
+    }
+}
+
+impl<T> Drop for Tracked<T> {
+    fn drop(&mut self) {
+        self.record(Action::Dropped, None);
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Tracked<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}#{}({:?})", self.name, self.id, self.value)
+    }
+}
+
+pub struct Ref<'a, T> {
+    tracked: &'a Tracked<T>,
+}
+
+impl<T> Deref for Ref<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.tracked.value
+    }
+}
+
+impl<T> Drop for Ref<'_, T> {
+    fn drop(&mut self) {
+        self.tracked.record(Action::BorrowEnded, None);
+    }
+}
+
+pub struct RefMut<'a, T> {
+    tracked: &'a mut Tracked<T>,
+}
+
+impl<T> Deref for RefMut<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.tracked.value
+    }
+}
+
+impl<T> DerefMut for RefMut<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.tracked.value
+    }
+}
+
+impl<T> Drop for RefMut<'_, T> {
+    fn drop(&mut self) {
+        self.tracked.record(Action::BorrowMutEnded, None);
+    }
+}
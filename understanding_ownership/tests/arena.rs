@@ -0,0 +1,112 @@
+use std::cell::Cell;
+use std::mem;
+
+use understanding_ownership::arena::{Arena, Stats};
+
+// the Node from the comment in arena.rs
+struct Node<'a> {
+    name: &'static str,
+    next: Cell<Option<&'a Node<'a>>>,
+}
+
+#[test]
+fn cycle_through_a_cell() {
+    let arena = Arena::new();
+    let a = &*arena.alloc(Node { name: "a", next: Cell::new(None) });
+    let b = &*arena.alloc(Node { name: "b", next: Cell::new(Some(a)) });
+    a.next.set(Some(b)); // a -> b -> a
+
+    // a and b are still good after the chunk fills up and new ones start
+    let mut prev = b;
+    for _ in 0..100 {
+        prev = arena.alloc(Node { name: "c", next: Cell::new(Some(prev)) });
+    }
+
+    let mut at = a;
+    let mut names = Vec::new();
+    for _ in 0..4 {
+        names.push(at.name);
+        at = at.next.get().unwrap();
+    }
+    assert_eq!(names, ["a", "b", "a", "b"]);
+    assert!(std::ptr::eq(a.next.get().unwrap().next.get().unwrap(), a));
+
+    let mut steps = 0;
+    let mut at = prev;
+    while at.name == "c" {
+        at = at.next.get().unwrap();
+        steps += 1;
+    }
+    assert_eq!(steps, 100);
+    assert!(std::ptr::eq(at, b));
+    assert_eq!(arena.len(), 102);
+}
+
+#[test]
+fn values_stay_put_and_can_change() {
+    let arena = Arena::new();
+    let refs: Vec<&mut u64> = (0..50).map(|i| arena.alloc(i)).collect();
+    for r in refs {
+        *r *= 2;
+    }
+    // and an earlier one is still good after later allocs
+    let first = arena.alloc(1000u64);
+    arena.alloc(2000);
+    *first += 1;
+    assert_eq!(*first, 1001);
+}
+
+#[test]
+fn stats_as_the_chunks_grow() {
+    let size = mem::size_of::<u32>();
+    let arena = Arena::new();
+    assert!(arena.is_empty());
+    assert_eq!(arena.stats(), Stats::default());
+
+    for i in 0..8u32 {
+        arena.alloc(i);
+    }
+    let full = arena.stats();
+    assert_eq!(full, Stats { values: 8, chunks: 1, bytes_used: 8 * size, bytes_reserved: 8 * size });
+
+    // the 9th starts a chunk twice as big, the 25th another
+    arena.alloc(8);
+    assert_eq!(arena.stats(), Stats { values: 9, chunks: 2, bytes_used: 9 * size, bytes_reserved: 24 * size });
+    for i in 9..25 {
+        arena.alloc(i);
+    }
+    let stats = arena.stats();
+    assert_eq!(stats, Stats { values: 25, chunks: 3, bytes_used: 25 * size, bytes_reserved: 56 * size });
+    assert_eq!(stats.to_string(), format!("25 values in 3 chunks, {} of {} bytes used", 25 * size, 56 * size));
+    assert_eq!(arena.len(), 25);
+}
+
+// counts its drops in a Cell outside the arena
+struct Counted<'a>(&'a Cell<usize>);
+
+impl Drop for Counted<'_> {
+    fn drop(&mut self) {
+        self.0.set(self.0.get() + 1);
+    }
+}
+
+#[test]
+fn values_drop_with_the_arena() {
+    let drops = Cell::new(0);
+    let arena = Arena::new();
+    for _ in 0..30 {
+        arena.alloc(Counted(&drops));
+    }
+    // nothing goes one at a time
+    assert_eq!(drops.get(), 0);
+    drop(arena);
+    assert_eq!(drops.get(), 30);
+
+    // an empty arena drops fine, and zero-sized values take no room
+    drop(Arena::<Counted>::new());
+    let zst = Arena::new();
+    for _ in 0..20 {
+        zst.alloc(());
+    }
+    assert_eq!(zst.stats().bytes_used, 0);
+}
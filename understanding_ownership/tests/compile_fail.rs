@@ -0,0 +1,197 @@
+// the lessons keep the code that doesn't compile in comments, with
+// the error it gives. each file in compile_fail/<crate>/ is one of
+// those, made to compile on its own, and starts with the error it
+// must fail with, where the lesson is, and a line quoted from it:
+//
+//   // error[E0382]: borrow of moved value: `a`
+//   // crate-type: bin            (optional, lib by default)
+//   // understanding_ownership/src/main.rs, transfer_of_ownership()
+//   // lesson: // println!("{}", a); borrow of moved value: `a`
+//
+// the test runs rustc on every file and checks that it fails with
+// that code, and a message that contains the text after it. only the
+// code and the message are checked, not the whole output, so a new
+// toolchain rewording a note or a help line doesn't break anything.
+//
+// it also checks the quoted line is still in that lesson, inside the
+// fn or mod named after the comma if there is one, so a fixture can't
+// outlive the lesson comment it was written for.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+struct Expected {
+    // "error[E0382]" or just "error" for a parse error
+    code: String,
+    message: String,
+    crate_type: String,
+    // path from the repo root, and the fn or mod in it
+    lesson: String,
+    item: Option<String>,
+    quote: String,
+}
+
+fn expected(path: &Path, source: &str) -> Expected {
+    let first = source.lines().next().unwrap_or("");
+    let (code, message) = first
+        .strip_prefix("// ")
+        .and_then(|rest| rest.split_once(": "))
+        .filter(|(code, _)| code.starts_with("error"))
+        .unwrap_or_else(|| panic!("{}: the first line must be `// error[CODE]: message`", path.display()));
+    let crate_type = source
+        .lines()
+        .find_map(|line| line.strip_prefix("// crate-type: "))
+        .unwrap_or("lib");
+    let location = source
+        .lines()
+        .skip(1)
+        .find(|line| !line.starts_with("// crate-type: "))
+        .and_then(|line| line.strip_prefix("// "))
+        .filter(|line| line.contains("/src/"))
+        .unwrap_or_else(|| panic!("{}: the error must be followed by `// <crate>/src/<file>.rs, <item>`", path.display()));
+    let (lesson, item) = match location.split_once(", ") {
+        Some((lesson, item)) => (lesson, Some(item.trim_end_matches("()").to_string())),
+        None => (location, None),
+    };
+    let quote = source
+        .lines()
+        .find_map(|line| line.strip_prefix("// lesson: "))
+        .unwrap_or_else(|| panic!("{}: there's no `// lesson: <line from the lesson>`", path.display()));
+    Expected {
+        code: code.to_string(),
+        message: message.to_string(),
+        crate_type: crate_type.to_string(),
+        lesson: lesson.to_string(),
+        item,
+        quote: quote.to_string(),
+    }
+}
+
+// the lines of `fn item` or `mod item`, up to the } at its indent.
+// the whole file if there's no item
+fn item_lines<'a>(source: &'a str, item: Option<&str>) -> Option<Vec<&'a str>> {
+    let item = match item {
+        Some(item) => item,
+        None => return Some(source.lines().collect()),
+    };
+    let mut lines = source.lines();
+    let start = lines.by_ref().find(|line| {
+        let line = line.trim_start().trim_start_matches("pub ");
+        ["fn ", "mod "].iter().any(|kw| {
+            line.strip_prefix(kw)
+                .and_then(|rest| rest.strip_prefix(item))
+                .is_some_and(|rest| rest.starts_with(['(', '<', ' ', '{']))
+        })
+    })?;
+    let end = format!("{}}}", &start[..start.len() - start.trim_start().len()]);
+    let mut body = vec![start];
+    body.extend(lines.take_while(|line| line.trim_end() != end));
+    Some(body)
+}
+
+// None if the quoted line is still where the fixture says it is
+fn lesson_moved(expected: &Expected) -> Option<String> {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).parent().unwrap();
+    let place = match &expected.item {
+        Some(item) => format!("{}, {}", expected.lesson, item),
+        None => expected.lesson.clone(),
+    };
+    let source = match fs::read_to_string(root.join(&expected.lesson)) {
+        Ok(source) => source,
+        Err(e) => return Some(format!("{}: {}", expected.lesson, e)),
+    };
+    match item_lines(&source, expected.item.as_deref()) {
+        None => Some(format!("no {} in {}", expected.item.as_deref().unwrap(), expected.lesson)),
+        Some(lines) if lines.iter().any(|line| line.contains(&expected.quote)) => None,
+        Some(_) => Some(format!("{} doesn't have `{}` any more", place, expected.quote)),
+    }
+}
+
+// the `error...` lines rustc printed, without the file:line:col
+fn errors(path: &Path, crate_type: &str) -> Vec<String> {
+    // one directory per crate, the tests run in parallel
+    let crate_dir = path.parent().unwrap().file_name().unwrap();
+    let out_dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("compile_fail").join(crate_dir);
+    fs::create_dir_all(&out_dir).unwrap();
+    let rustc = std::env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string());
+    let output = Command::new(rustc)
+        .args(["--edition", "2018", "--error-format", "short", "--emit", "metadata"])
+        .args(["--crate-type", crate_type, "--out-dir"])
+        .arg(&out_dir)
+        .arg(path)
+        .output()
+        .expect("couldn't run rustc");
+    assert!(!output.status.success(), "{} compiled", path.display());
+    String::from_utf8_lossy(&output.stderr)
+        .lines()
+        .filter_map(|line| line.find("error").map(|i| line[i..].to_string()))
+        .filter(|line| !line.starts_with("error: aborting"))
+        .collect()
+}
+
+fn check_dir(dir: &str) {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/compile_fail").join(dir);
+    let mut files: Vec<PathBuf> = fs::read_dir(&dir)
+        .unwrap_or_else(|e| panic!("{}: {}", dir.display(), e))
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "rs"))
+        .collect();
+    files.sort();
+    assert!(!files.is_empty(), "no tests in {}", dir.display());
+
+    let mut failures = Vec::new();
+    for path in &files {
+        let source = fs::read_to_string(path).unwrap();
+        let expected = expected(path, &source);
+        if let Some(moved) = lesson_moved(&expected) {
+            failures.push(format!("{}\n  {}", path.display(), moved));
+        }
+        let errors = errors(path, &expected.crate_type);
+        let found = errors.iter().any(|e| {
+            e.strip_prefix(&expected.code)
+                .and_then(|rest| rest.strip_prefix(": "))
+                .is_some_and(|message| message.contains(&expected.message))
+        });
+        if !found {
+            failures.push(format!(
+                "{}\n  expected {}: {}\n  got\n    {}",
+                path.display(),
+                expected.code,
+                expected.message,
+                errors.join("\n    ")
+            ));
+        }
+    }
+    assert!(failures.is_empty(), "\n{}", failures.join("\n"));
+}
+
+#[test]
+fn common_programming_concepts() {
+    check_dir("common_programming_concepts");
+}
+
+#[test]
+fn enums_and_pattern_matching() {
+    check_dir("enums_and_pattern_matching");
+}
+
+#[test]
+fn guessing_game() {
+    check_dir("guessing_game");
+}
+
+#[test]
+fn managing_growing_projects() {
+    check_dir("managing_growing_projects");
+}
+
+#[test]
+fn understanding_ownership() {
+    check_dir("understanding_ownership");
+}
+
+#[test]
+fn using_structs_to_structure_related_data() {
+    check_dir("using_structs_to_structure_related_data");
+}
@@ -0,0 +1,10 @@
+// error[E0384]: cannot assign twice to immutable variable `x`
+// common_programming_concepts/src/main.rs, variables()
+// lesson: // x = 3; cannot assign twice to immutable variable `x`
+
+pub fn variables() {
+    // once a variable is declared it is immutable by default
+    let x = 5;
+    x = 3;
+    println!("x is {}", x);
+}
@@ -0,0 +1,9 @@
+// error[E0308]: `if` and `else` have incompatible types
+// common_programming_concepts/src/main.rs, control_flow()
+// lesson: // let b = if is_good_looking { 5 } else { "no" };
+
+pub fn control_flow() {
+    let is_good_looking = true;
+    let b = if is_good_looking { 5 } else { "no" };
+    println!("b is {}", b);
+}
@@ -0,0 +1,11 @@
+// error[E0308]: mismatched types
+// common_programming_concepts/src/main.rs, control_flow()
+// lesson: // parenthesis is not required and must be a bool
+
+pub fn control_flow() {
+    // the condition must be a bool, there's no truthiness
+    let number = 3;
+    if number {
+        println!("number was three");
+    }
+}
@@ -0,0 +1,8 @@
+// error: expected expression, found `let` statement
+// common_programming_concepts/src/main.rs, functions()
+// lesson: // let x = (let y = 6);; compile error
+
+pub fn functions() {
+    // let is a statement, it has no value for x
+    let x = (let y = 6);
+}
@@ -0,0 +1,9 @@
+// error[E0609]: no field `3` on type `(i32, f64, u8)`
+// common_programming_concepts/src/main.rs, data_types()
+// lesson: // Tuples have a fixed length: once declared, they cannot
+
+pub fn data_types() {
+    // tuples have a fixed length, there's no tup.3
+    let tup: (i32, f64, u8) = (500, 6.4, 1);
+    println!("{}", tup.3);
+}
@@ -0,0 +1,19 @@
+// error[E0004]: non-exhaustive patterns: `&Coin::Quarter` not covered
+// enums_and_pattern_matching/src/main.rs, the_match_control_flow_operator()
+// lesson: Coin::Quarter =>  {
+
+pub enum Coin {
+    Penny,
+    Nickel,
+    Dime,
+    Quarter,
+}
+
+// a match has to handle every variant
+pub fn value_i_cennts(coin: &Coin) -> u32 {
+    match coin {
+        Coin::Penny => 1,
+        Coin::Nickel => 5,
+        Coin::Dime => 10,
+    }
+}
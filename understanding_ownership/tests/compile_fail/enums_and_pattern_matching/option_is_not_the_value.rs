@@ -0,0 +1,11 @@
+// error[E0277]: cannot add `Option<u32>` to `u32`
+// enums_and_pattern_matching/src/main.rs, defining_an_enum()
+// lesson: // yo yo yo Rust do not ave null! Options to the rescue!
+
+pub fn defining_an_enum() {
+    // no null, so an Option<u32> can't be used as a u32 until it's
+    // known to be Some
+    let op1: Option<u32> = Some(5);
+    let sum = 1u32 + op1;
+    println!("{}", sum);
+}
@@ -0,0 +1,14 @@
+// error[E0308]: mismatched types
+// guessing_game/src/main.rs, play()
+// lesson: // convert to integer. Rust allow you to "shadow" guess
+
+pub fn main() {
+    // without a new `let` it's the same String variable, so it can't
+    // take the u32
+    let mut guess = String::from("42\n");
+    guess = match guess.trim().parse::<u32>() {
+        Ok(num) => num,
+        Err(_) => 0,
+    };
+    println!("{}", guess);
+}
@@ -0,0 +1,11 @@
+// error[E0596]: cannot borrow `guess` as mutable, as it is not declared as mutable
+// guessing_game/src/main.rs, guess_loop()
+// lesson: // mut means mutable. by default in Rust, variables
+
+use std::io;
+
+pub fn main() {
+    // by default in Rust, variables are immutable
+    let guess = String::new();
+    io::stdin().read_line(&mut guess).expect("Failed to read line");
+}
@@ -0,0 +1,10 @@
+// error[E0601]: `main` function not found
+// crate-type: bin
+// managing_growing_projects/packaging/src/main.rs
+// lesson: // main.rs means this package contains a binary crate
+
+// main.rs means this package contains a binary crate, and a binary
+// crate starts at main
+pub fn hello() {
+    println!("Hello, world!");
+}
@@ -0,0 +1,15 @@
+// error[E0451]: field `b` of struct `A` is private
+// managing_growing_projects/restaurant/src/lib.rs, eat_at_restaurant()
+// lesson: // by default it's private
+
+mod front_of_house {
+    // by default it's private
+    pub struct A {
+        pub a: String,
+        b: String,
+    }
+}
+
+pub fn eat_at_restaurant() {
+    let _ = front_of_house::A { a: String::new(), b: String::new() };
+}
@@ -0,0 +1,16 @@
+// error[E0603]: function `fix_incorrect_order` is private
+// managing_growing_projects/restaurant/src/lib.rs, back_of_house
+// lesson: fn fix_incorrect_order() {
+
+fn serve_order() {}
+
+mod back_of_house {
+    // a child can use its parent's private items, not the other way
+    fn fix_incorrect_order() {
+        super::serve_order();
+    }
+}
+
+pub fn eat_at_restaurant() {
+    back_of_house::fix_incorrect_order();
+}
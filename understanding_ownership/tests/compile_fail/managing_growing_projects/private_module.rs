@@ -0,0 +1,16 @@
+// error[E0603]: module `mod0_0` is private
+// managing_growing_projects/modules/src/my.rs
+// lesson: pub mod mod0_0 {
+
+mod my {
+    pub mod mod0 {
+        // without the pub here, main.rs can't reach it
+        mod mod0_0 {
+            pub fn fn0() {}
+        }
+    }
+}
+
+pub fn main() {
+    my::mod0::mod0_0::fn0();
+}
@@ -0,0 +1,9 @@
+// error[E0596]: cannot borrow `*s` as mutable, as it is behind a `&` reference
+// understanding_ownership/src/main.rs, references_and_borrowing()
+// lesson: // fn fn2(s: &String) { s.push_str("test"); } compile error. really nice!
+
+pub fn references_and_borrowing() {
+    // you can't modify what you borrowed
+    fn fn2(s: &String) { s.push_str("test"); }
+    fn2(&String::from("hello"));
+}
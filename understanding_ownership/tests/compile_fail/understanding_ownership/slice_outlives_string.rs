@@ -0,0 +1,11 @@
+// error[E0505]: cannot move out of `s` because it is borrowed
+// understanding_ownership/src/main.rs, slices()
+// lesson: // a slice borrows part of a String. no copy, just a pointer
+
+pub fn slices() {
+    // a slice borrows the String, so the String has to outlive it
+    let s = String::from("hello world");
+    let hello = &s[0..5];
+    drop(s);
+    println!("{}", hello);
+}
@@ -0,0 +1,12 @@
+// error[E0499]: cannot borrow `s3` as mutable more than once at a time
+// understanding_ownership/src/main.rs, references_and_borrowing()
+// lesson: // have only one mutable reference to a particular piece of
+
+pub fn references_and_borrowing() {
+    // only one mutable reference to a piece of data in a scope
+    let mut s3 = String::from("hello");
+    let r1 = &mut s3;
+    let r2 = &mut s3;
+    r1.push_str("test");
+    r2.push_str("test");
+}
@@ -0,0 +1,12 @@
+// error[E0382]: borrow of moved value: `a`
+// understanding_ownership/src/main.rs, transfer_of_ownership()
+// lesson: // println!("{}", a); borrow of moved value: `a`
+
+pub fn transfer_of_ownership() {
+    let a = String::from("test");
+    fn s(s: String) { println!("{}", s); }
+    s(a);
+
+    // a is now invalid since the String was moved to s()
+    println!("{}", a);
+}
@@ -0,0 +1,18 @@
+// error[E0594]: cannot assign to `user.email`, as `user` is not declared as mutable
+// using_structs_to_structure_related_data/src/main.rs, defining_and_instantiating_structs()
+// lesson: user.email = String::from("qgervacio@gmail.com"); // user have to be mut
+
+struct User {
+    user_name: String,
+    email: String,
+}
+
+pub fn defining_and_instantiating_structs() {
+    let user = User {
+        user_name: String::from("qgervacio"),
+        email: String::from("email@email.com"),
+    };
+    // user have to be mut
+    user.email = String::from("qgervacio@gmail.com");
+    println!("{} {}", user.user_name, user.email);
+}
@@ -0,0 +1,13 @@
+// error[E0277]: `Dime1` doesn't implement `Debug`
+// using_structs_to_structure_related_data/src/main.rs, an_example_program_using_structs()
+// lesson: #[derive(Debug)] // this allows you to print out debugging information
+
+pub fn an_example_program_using_structs() {
+    // {:?} only works because of #[derive(Debug)]
+    struct Dime1 {
+        width: u32,
+        height: u32,
+    }
+    let dime2 = Dime1 { width: 40, height: 40 };
+    println!("{:?}", dime2);
+}
@@ -0,0 +1,15 @@
+// error[E0425]: cannot find type `Rectangl` in this scope
+// using_structs_to_structure_related_data/src/main.rs, method_syntax()
+// lesson: impl Rectangle { // must be same name as the struct
+
+pub struct Rectangle {
+    width: u32,
+    height: u32,
+}
+
+// must be same name as the struct
+impl Rectangl {
+    fn calculate(&self) -> u32 {
+        self.width * self.height
+    }
+}
@@ -0,0 +1,46 @@
+use std::hint::black_box;
+
+use understanding_ownership::heap::{self, Counting};
+
+#[global_allocator]
+static HEAP: Counting = Counting;
+
+// the counters are global, so everything is in one test: no other
+// test thread allocating at the same time
+#[test]
+fn measure() {
+    // the push_str("test") from references_and_borrowing: "hello"
+    // fits exactly, so the String has to grow
+    let mut s3 = String::from("hello");
+    let ((), grown) = heap::measure(|| s3.push_str("test"));
+    assert_eq!((grown.allocs, grown.reallocs, grown.frees), (0, 1, 0));
+    assert_eq!(s3, "hellotest");
+
+    let (len, stats) = heap::measure(|| black_box(vec![0u8; 1000]).len());
+    assert_eq!(len, 1000);
+    assert_eq!((stats.allocs, stats.frees, stats.bytes), (1, 1, 1000));
+    assert_eq!(stats.peak, 1000);
+
+    // a big block freed before the inner measure starts still counts
+    // for the outer peak, and the inner one only sees its own
+    let (inner, outer) = heap::measure(|| {
+        drop(black_box(vec![0u8; 100_000]));
+        let kept = black_box(vec![0u8; 500]);
+        let ((), inner) = heap::measure(|| drop(black_box(vec![0u8; 2000])));
+        drop(kept);
+        inner
+    });
+    assert_eq!(inner.peak, 2000);
+    assert_eq!(outer.peak, 100_000);
+    assert_eq!((outer.allocs, outer.frees), (3, 3));
+
+    // and the other way round, an inner peak above the outer one raises it
+    let (inner, outer) = heap::measure(|| {
+        let kept = black_box(vec![0u8; 500]);
+        let ((), inner) = heap::measure(|| drop(black_box(vec![0u8; 50_000])));
+        drop(kept);
+        inner
+    });
+    assert_eq!(inner.peak, 50_000);
+    assert_eq!(outer.peak, 50_500);
+}
@@ -0,0 +1,83 @@
+use std::thread;
+
+use understanding_ownership::intern::{Interner, SyncInterner};
+
+const WORDS: [&str; 8] = ["the", "quick", "brown", "fox", "", "the", "héllo", "fox"];
+
+#[test]
+fn interner() {
+    let mut names = Interner::new();
+    assert!(names.is_empty());
+    let symbols: Vec<_> = WORDS.iter().map(|w| names.intern(w)).collect();
+    assert_eq!(names.len(), 6);
+    // the same string, the same symbol, numbered in order of first use
+    assert_eq!(symbols[0], symbols[5]);
+    assert_eq!(symbols[3], symbols[7]);
+    assert_eq!(symbols.iter().map(|s| s.index()).collect::<Vec<_>>(), [0, 1, 2, 3, 4, 0, 5, 3]);
+    for (sym, word) in symbols.iter().zip(WORDS.iter()) {
+        assert_eq!(names.resolve(*sym), *word);
+    }
+    assert_eq!(names.get("brown"), Some(symbols[2]));
+    assert_eq!(names.get("lazy"), None);
+    let all: Vec<&str> = names.iter().map(|(_, s)| s).collect();
+    assert_eq!(all, ["the", "quick", "brown", "fox", "", "héllo"]);
+}
+
+#[test]
+fn sync_interner_on_one_thread() {
+    let names = SyncInterner::new();
+    assert!(names.is_empty());
+    let symbols: Vec<_> = WORDS.iter().map(|w| names.intern(w)).collect();
+    assert_eq!(names.len(), 6);
+    assert_eq!(symbols[0], symbols[5]);
+    assert_eq!(names.get("fox"), Some(symbols[3]));
+    assert_eq!(names.get("lazy"), None);
+
+    // a resolved &str stays good while more strings are added and the
+    // list of them grows
+    let first = names.resolve(symbols[0]);
+    for i in 0..100 {
+        names.intern(&i.to_string());
+    }
+    assert_eq!(first, "the");
+    for (sym, word) in symbols.iter().zip(WORDS.iter()) {
+        assert_eq!(names.resolve(*sym), *word);
+    }
+}
+
+#[test]
+fn sync_interner_on_many_threads() {
+    let names = SyncInterner::new();
+    // every thread interns the same words, in a different order
+    let words: Vec<String> = (0..20).map(|i| format!("w{}", i)).collect();
+    let per_thread: Vec<Vec<(String, usize, &str)>> = thread::scope(|scope| {
+        let handles: Vec<_> = (0..4)
+            .map(|t| {
+                let (names, words) = (&names, &words);
+                scope.spawn(move || {
+                    let mut seen = Vec::new();
+                    for i in 0..words.len() {
+                        let word = &words[(i * 7 + t * 3) % words.len()];
+                        let sym = names.intern(word);
+                        // resolved while the others keep adding
+                        seen.push((word.clone(), sym.index(), names.resolve(sym)));
+                    }
+                    seen
+                })
+            })
+            .collect();
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
+    });
+
+    assert_eq!(names.len(), words.len());
+    for seen in &per_thread {
+        for (word, index, resolved) in seen {
+            assert_eq!(word, resolved);
+            // every thread got the same symbol for the same word
+            assert_eq!(names.get(word).unwrap().index(), *index);
+        }
+    }
+    let mut indexes: Vec<usize> = words.iter().map(|w| names.get(w).unwrap().index()).collect();
+    indexes.sort_unstable();
+    assert_eq!(indexes, (0..words.len()).collect::<Vec<_>>());
+}
@@ -0,0 +1,128 @@
+use std::convert::TryInto;
+use std::mem;
+
+use understanding_ownership::layout::{inspect, Inspect, Place, Report};
+
+// the field names by offset, and each field's bytes read back as a usize
+fn words(report: &Report) -> Vec<(String, usize)> {
+    report
+        .fields
+        .iter()
+        .map(|f| {
+            assert_eq!(f.size, mem::size_of::<usize>(), "{}", f.name);
+            let bytes: Vec<u8> = report.bytes[f.offset..f.offset + f.size].iter().map(|b| b.unwrap()).collect();
+            (f.name.clone(), usize::from_ne_bytes(bytes.try_into().unwrap()))
+        })
+        .collect()
+}
+
+fn value_of(report: &Report, name: &str) -> usize {
+    let words = words(report);
+    let found: Vec<usize> = words.iter().filter(|(n, _)| n == name).map(|&(_, v)| v).collect();
+    assert_eq!(found.len(), 1, "{} in {:?}", name, words);
+    found[0]
+}
+
+// every field is there once, and the fields don't overlap
+fn check_fields<T: Inspect>(value: &T, names: &[&str]) -> Report {
+    let report = inspect(value);
+    let mut found: Vec<&str> = report.fields.iter().map(|f| f.name.as_str()).collect();
+    found.sort_unstable();
+    let mut expected = names.to_vec();
+    expected.sort_unstable();
+    assert_eq!(found, expected, "{}", report.name);
+    for pair in report.fields.windows(2) {
+        assert!(pair[0].offset + pair[0].size <= pair[1].offset, "{:?}", report.fields);
+    }
+    report
+}
+
+#[test]
+fn string_with_len_equal_to_capacity() {
+    let s = String::from("abc");
+    assert_eq!(s.len(), s.capacity());
+    let report = check_fields(&s, &["ptr", "len", "capacity"]);
+    assert_eq!(value_of(&report, "ptr"), s.as_ptr() as usize);
+    assert_eq!(value_of(&report, "len"), 3);
+    assert_eq!(value_of(&report, "capacity"), 3);
+    // an owned buffer is on the heap whatever its address looks like
+    let buffer = report.buffer.unwrap();
+    assert_eq!((buffer.place, buffer.guessed), (Place::Heap, false));
+
+    // the same offsets as one where they differ
+    let mut t = String::with_capacity(16);
+    t.push_str("abc");
+    assert_eq!(inspect(&t).fields, report.fields);
+    assert_eq!(value_of(&inspect(&t), "capacity"), 16);
+}
+
+#[test]
+fn empty_string() {
+    let s = String::new();
+    let report = check_fields(&s, &["ptr", "len", "capacity"]);
+    assert_eq!(value_of(&report, "len"), 0);
+    assert_eq!(value_of(&report, "capacity"), 0);
+    assert_eq!(report.buffer.unwrap().place, Place::Nowhere);
+}
+
+#[test]
+fn vecs() {
+    let v: Vec<u8> = Vec::new();
+    let report = check_fields(&v, &["ptr", "len", "capacity"]);
+    assert_eq!(value_of(&report, "len"), 0);
+    assert_eq!(value_of(&report, "capacity"), 0);
+    assert_eq!(report.buffer.unwrap().place, Place::Nowhere);
+
+    let v = vec![1u32, 2];
+    let report = check_fields(&v, &["ptr", "len", "capacity"]);
+    assert_eq!(value_of(&report, "ptr"), v.as_ptr() as usize);
+    assert_eq!(value_of(&report, "len"), 2);
+    assert_eq!(value_of(&report, "capacity"), 2);
+    let buffer = report.buffer.unwrap();
+    assert_eq!((buffer.len, buffer.capacity, buffer.place), (8, 8, Place::Heap));
+
+    // zero-sized values, capacity() is usize::MAX but the field is 0
+    let v = vec![(); 5];
+    let report = check_fields(&v, &["ptr", "len", "capacity"]);
+    assert_eq!(value_of(&report, "len"), 5);
+    assert_eq!(value_of(&report, "capacity"), 0);
+    assert_eq!(report.buffer.unwrap().place, Place::Nowhere);
+}
+
+#[test]
+fn strs() {
+    // where a &str points is only guessed from its address, which
+    // depends on how memory happens to be mapped, so only check that
+    // it says so
+    let s = "abc";
+    let report = check_fields(&s, &["ptr", "len"]);
+    assert_eq!(value_of(&report, "ptr"), s.as_ptr() as usize);
+    assert_eq!(value_of(&report, "len"), 3);
+    let buffer = report.buffer.as_ref().unwrap();
+    assert_eq!((buffer.addr, buffer.len, buffer.guessed), (s.as_ptr() as usize, 3, true));
+    assert!(report.render().contains(", probably "));
+
+    let b: Box<str> = Box::from("abc");
+    let report = check_fields(&b, &["ptr", "len"]);
+    let buffer = report.buffer.as_ref().unwrap();
+    assert_eq!((buffer.place, buffer.guessed), (Place::Heap, false));
+    assert!(report.render().contains(", on the heap: 3 bytes used"));
+
+    let b: Box<str> = Box::from("");
+    let report = check_fields(&b, &["ptr", "len"]);
+    assert_eq!(value_of(&report, "len"), 0);
+    assert_eq!(report.buffer.unwrap().place, Place::Nowhere);
+}
+
+#[test]
+fn options() {
+    let some = Some(String::from("abc"));
+    let report = check_fields(&some, &["ptr", "len", "capacity"]);
+    assert_eq!(value_of(&report, "len"), 3);
+    assert_eq!(value_of(&report, "capacity"), 3);
+    assert!(report.niche);
+
+    let report = check_fields(&None::<String>, &[]);
+    assert!(report.bytes.iter().all(Option::is_none));
+    assert_eq!(report.buffer, None);
+}
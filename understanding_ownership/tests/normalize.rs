@@ -0,0 +1,76 @@
+use std::borrow::Cow;
+
+use understanding_ownership::normalize::{
+    allocations, collapse_whitespace, escape_debug, escape_html, fold_case, nfc, normalize, trim,
+};
+
+// runs f and checks whether it allocated, by the counter and by the
+// kind of Cow it gave back
+fn check<'a>(f: impl Fn(&'a str) -> Cow<'a, str>, input: &'a str, expected: &str, allocates: bool) {
+    let before = allocations();
+    let out = f(input);
+    assert_eq!(out, expected, "input {:?}", input);
+    assert_eq!(allocations() - before, allocates as usize, "allocations for {:?}", input);
+    assert_eq!(matches!(out, Cow::Owned(_)), allocates, "Cow kind for {:?}", input);
+    // a borrowed result points into the input
+    if let Cow::Borrowed(s) = out {
+        let range = input.as_ptr() as usize..=input.as_ptr() as usize + input.len();
+        assert!(range.contains(&(s.as_ptr() as usize)), "{:?} borrows from elsewhere", input);
+    }
+}
+
+#[test]
+fn trim_never_allocates() {
+    check(trim, "  hello \n", "hello", false);
+    check(trim, "hello", "hello", false);
+    check(trim, "", "", false);
+}
+
+#[test]
+fn collapse() {
+    check(collapse_whitespace, "hello world", "hello world", false);
+    check(collapse_whitespace, " hello world ", " hello world ", false);
+    check(collapse_whitespace, "hello  world", "hello world", true);
+    check(collapse_whitespace, "hello\tworld", "hello world", true);
+    check(collapse_whitespace, "a \n\t b", "a b", true);
+    check(collapse_whitespace, "", "", false);
+}
+
+#[test]
+fn fold() {
+    check(fold_case, "hello", "hello", false);
+    check(fold_case, "héllo wörld", "héllo wörld", false);
+    check(fold_case, "Hello", "hello", true);
+    check(fold_case, "Straße", "strasse", true);
+    check(fold_case, "STRASSE", "strasse", true);
+}
+
+#[test]
+fn nfc_composes() {
+    check(nfc, "caf\u{e9}", "caf\u{e9}", false);
+    check(nfc, "cafe\u{301}", "caf\u{e9}", true);
+    check(nfc, "plain ascii", "plain ascii", false);
+    // no single char for q with an acute, so it stays two
+    check(nfc, "q\u{301}", "q\u{301}", false);
+}
+
+#[test]
+fn escapes() {
+    check(escape_html, "a < b", "a &lt; b", true);
+    check(escape_html, "Tom & \"Jerry's\"", "Tom &amp; &quot;Jerry&#39;s&quot;", true);
+    check(escape_html, "nothing to do", "nothing to do", false);
+    check(escape_debug, "tab\there", "tab\\there", true);
+    check(escape_debug, "say \"hi\"", "say \\\"hi\\\"", true);
+    check(escape_debug, "héllo", "héllo", false);
+}
+
+#[test]
+fn normalize_chains_without_extra_copies() {
+    check(normalize, "already clean", "already clean", false);
+    check(normalize, "  only the ends  ", "only the ends", false);
+    // one copy for the spaces, and the NFC step reuses it
+    check(normalize, "  a   b  ", "a b", true);
+    let before = allocations();
+    assert_eq!(normalize(" cafe\u{301}  au  lait "), "caf\u{e9} au lait");
+    assert_eq!(allocations() - before, 2);
+}
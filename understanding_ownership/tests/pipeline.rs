@@ -0,0 +1,109 @@
+use std::io::{self, BufReader, Cursor, Read};
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+use understanding_ownership::pipeline::{word_count, Config, Counts};
+
+fn count(text: String, config: Config) -> Counts {
+    word_count(Cursor::new(text), config).unwrap()
+}
+
+fn words(counts: &Counts) -> Vec<(&str, usize)> {
+    counts.counts.iter().map(|(w, n)| (w.as_str(), *n)).collect()
+}
+
+// a few thousand lines of a few different words, so every batch size
+// splits it differently
+fn sample() -> String {
+    let words = ["apple", "banana", "cherry", "date", "elder", "fig", "grape"];
+    (0..3000)
+        .map(|i| {
+            let line: Vec<&str> = (0..i % 9).map(|j| words[(i * 3 + j * j) % words.len()]).collect();
+            line.join(" ") + "\n"
+        })
+        .collect()
+}
+
+#[test]
+fn counts_and_order() {
+    let counts = count("the cat\nThe dog, the END\n\nend 42 cat\n".to_string(), Config::default());
+    assert_eq!(counts.lines, 4);
+    assert_eq!(counts.words, 8);
+    // most common first, ties alphabetical. words are lowercased
+    assert_eq!(words(&counts), [("the", 3), ("cat", 2), ("end", 2), ("dog", 1)]);
+
+    let empty = count(String::new(), Config::default());
+    assert_eq!(empty, Counts::default());
+}
+
+#[test]
+fn same_output_for_any_number_of_workers() {
+    let text = sample();
+    let expected = count(text.clone(), Config { workers: 1, ..Config::default() });
+    assert_eq!(expected.lines, 3000);
+    for &workers in &[1, 2, 8] {
+        for &batch in &[1, 7, 256, 10_000] {
+            let config = Config { workers, batch, capacity: 2 };
+            assert_eq!(count(text.clone(), config), expected, "{:?}", config);
+        }
+    }
+
+    let counts = words(&expected);
+    for pair in counts.windows(2) {
+        let ((a, x), (b, y)) = (pair[0], pair[1]);
+        assert!(x > y || x == y && a < b, "{:?} before {:?}", pair[0], pair[1]);
+    }
+}
+
+// runs word_count on another thread and fails instead of hanging
+fn within(secs: u64, text: String, config: Config) -> Counts {
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || tx.send(count(text, config)).unwrap());
+    rx.recv_timeout(Duration::from_secs(secs)).expect("word_count deadlocked")
+}
+
+#[test]
+fn tiny_channels_and_a_slow_consumer() {
+    // a batch is one long line, so the workers take much longer per
+    // batch than the reader does, and no channel holds anything: every
+    // send waits for a receive
+    let line = "slow ".repeat(20_000) + "\n";
+    let text = line.repeat(50);
+    for &workers in &[1, 3] {
+        let config = Config { workers, batch: 1, capacity: 0 };
+        let counts = within(60, text.clone(), config);
+        assert_eq!(counts.lines, 50);
+        assert_eq!(words(&counts), [("slow", 1_000_000)]);
+    }
+    let config = Config { workers: 8, batch: 1, capacity: 0 };
+    assert_eq!(within(60, sample(), config), count(sample(), Config::default()));
+}
+
+// the text, and then an error where the rest would be
+struct Failing {
+    text: Cursor<String>,
+}
+
+impl Read for Failing {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self.text.read(buf)? {
+            0 => Err(io::Error::new(io::ErrorKind::ConnectionReset, "gone")),
+            n => Ok(n),
+        }
+    }
+}
+
+#[test]
+fn reader_error_mid_stream() {
+    let input = BufReader::with_capacity(64, Failing { text: Cursor::new(sample()) });
+    let config = Config { workers: 2, batch: 10, capacity: 1 };
+    let e = word_count(input, config).unwrap_err();
+    assert_eq!(e.kind(), io::ErrorKind::ConnectionReset);
+
+    // a line that isn't UTF-8 is an error too
+    let mut bytes = sample().into_bytes();
+    bytes.extend_from_slice(b"ok \xff\xfe\nmore\n");
+    let e = word_count(Cursor::new(bytes), Config::default()).unwrap_err();
+    assert_eq!(e.kind(), io::ErrorKind::InvalidData);
+}
@@ -0,0 +1,171 @@
+use proptest::prelude::*;
+use understanding_ownership::rope::Rope;
+
+// multi-byte chars and newlines, so chunk edges land inside a char
+// and lines span chunks
+fn text(max: usize) -> impl Strategy<Value = String> {
+    proptest::collection::vec(prop::sample::select(vec!['a', 'b', ' ', '\n', 'é', '日', '🦀']), 0..max)
+        .prop_map(|chars| chars.into_iter().collect())
+}
+
+#[derive(Debug, Clone)]
+enum Op {
+    // positions are taken modulo the length at the time
+    Insert(usize, String),
+    Remove(usize, usize),
+    Slice(usize, usize),
+    Push(String),
+}
+
+fn op() -> impl Strategy<Value = Op> {
+    prop_oneof![
+        3 => (any::<usize>(), text(1500)).prop_map(|(at, s)| Op::Insert(at, s)),
+        2 => (any::<usize>(), any::<usize>()).prop_map(|(a, b)| Op::Remove(a, b)),
+        1 => (any::<usize>(), any::<usize>()).prop_map(|(a, b)| Op::Slice(a, b)),
+        1 => text(300).prop_map(Op::Push),
+    ]
+}
+
+fn byte_index(s: &str, chars: usize) -> usize {
+    s.char_indices().nth(chars).map_or(s.len(), |(i, _)| i)
+}
+
+fn range(a: usize, b: usize, len: usize) -> std::ops::Range<usize> {
+    let (a, b) = (a % (len + 1), b % (len + 1));
+    a.min(b)..a.max(b)
+}
+
+// everything a rope says about itself, against the String it should be
+fn check(rope: &Rope, s: &str) -> Result<(), TestCaseError> {
+    prop_assert_eq!(rope.to_string(), s);
+    prop_assert_eq!(String::from(rope), s);
+    let chars: Vec<char> = s.chars().collect();
+    prop_assert_eq!(rope.len_chars(), chars.len());
+    prop_assert_eq!(rope.len_bytes(), s.len());
+    prop_assert_eq!(rope.len_lines(), s.matches('\n').count() + 1);
+    prop_assert_eq!(rope.is_empty(), s.is_empty());
+
+    // where every line starts
+    let mut starts = vec![0];
+    starts.extend(chars.iter().enumerate().filter(|&(_, &c)| c == '\n').map(|(i, _)| i + 1));
+
+    // the chars on both sides of every chunk edge, and a few more
+    let mut positions = vec![0, chars.len() / 2, chars.len().saturating_sub(1)];
+    let mut edge = 0;
+    for chunk in rope.chunks() {
+        prop_assert!(!chunk.is_empty());
+        edge += chunk.chars().count();
+        positions.extend([edge.saturating_sub(1), edge, edge + 1]);
+    }
+    for &at in positions.iter().filter(|&&at| at < chars.len()) {
+        prop_assert_eq!(rope.char(at), chars[at], "char {}", at);
+        let line = starts.partition_point(|&start| start <= at) - 1;
+        prop_assert_eq!(rope.char_to_line(at), line, "char_to_line {}", at);
+    }
+    prop_assert_eq!(rope.char_to_line(chars.len()), rope.len_lines() - 1);
+
+    // and back
+    for (line, &start) in starts.iter().enumerate() {
+        prop_assert_eq!(rope.line_to_char(line), start, "line_to_char {}", line);
+        prop_assert_eq!(rope.char_to_line(start), line, "char_to_line(line_to_char({}))", line);
+    }
+
+    // an AVL tree with n leaves is at most 1.44 log2(n + 1) high
+    let leaves = rope.chunks().count().max(1) as f64;
+    let limit = 1.4405 * (leaves + 1.0).log2() - 0.3277;
+    prop_assert!((rope.depth() as f64) < limit.max(1.0), "depth {} for {} leaves", rope.depth(), leaves);
+    Ok(())
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    #[test]
+    fn edits_match_a_string(start in text(3000), ops in proptest::collection::vec(op(), 0..24)) {
+        let mut rope = Rope::from(start.as_str());
+        let mut s = start;
+        check(&rope, &s)?;
+        for op in ops {
+            let len = s.chars().count();
+            match op {
+                Op::Insert(at, text) => {
+                    let at = at % (len + 1);
+                    rope.insert(at, &text);
+                    s.insert_str(byte_index(&s, at), &text);
+                }
+                Op::Remove(a, b) => {
+                    let r = range(a, b, len);
+                    rope.remove(r.clone());
+                    s.replace_range(byte_index(&s, r.start)..byte_index(&s, r.end), "");
+                }
+                Op::Slice(a, b) => {
+                    let r = range(a, b, len);
+                    let expected = &s[byte_index(&s, r.start)..byte_index(&s, r.end)];
+                    let slice = rope.slice(r);
+                    check(&slice, expected)?;
+                    // the rope it came from is untouched
+                    check(&rope, &s)?;
+                    continue;
+                }
+                Op::Push(text) => {
+                    rope.push_str(&text);
+                    s.push_str(&text);
+                }
+            }
+            check(&rope, &s)?;
+        }
+    }
+
+    #[test]
+    fn lines_match_str_lines(s in text(2000)) {
+        let rope = Rope::from(s.as_str());
+        let lines: Vec<&str> = s.split_inclusive('\n').collect();
+        for (i, line) in lines.iter().enumerate() {
+            prop_assert_eq!(rope.line(i).to_string(), *line);
+        }
+    }
+}
+
+#[test]
+fn multibyte_chars_at_the_chunk_size() {
+    // 1023 bytes of ascii and then a 4 byte char, so the chunk edge at
+    // 1024 would be in the middle of it
+    for fill in 1020..1026 {
+        let s = format!("{}🦀é\n日", "a".repeat(fill));
+        let rope = Rope::from(s.as_str());
+        check(&rope, &s).unwrap();
+        let mut edited = rope.clone();
+        edited.insert(fill + 1, "🦀");
+        edited.remove(fill..fill + 1);
+        check(&edited, &format!("{}🦀é\n日", "a".repeat(fill))).unwrap();
+        // the clone shares nodes but didn't change
+        check(&rope, &s).unwrap();
+    }
+}
+
+#[test]
+fn stays_balanced_over_many_edits() {
+    // a few thousand edits at scattered places, big enough that the
+    // chunks don't merge
+    let mut rope = Rope::new();
+    let mut s = String::new();
+    let mut seed: u64 = 1;
+    let mut len = 0;
+    for i in 0..1500 {
+        seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        let at = (seed >> 33) as usize % (len + 1);
+        if i % 4 == 3 {
+            let end = (at + 100).min(len);
+            rope.remove(at..end);
+            s.replace_range(byte_index(&s, at)..byte_index(&s, end), "");
+            len -= end - at;
+        } else {
+            let text = format!("{}\n{}", i % 10, "日".repeat(200));
+            rope.insert(at, &text);
+            s.insert_str(byte_index(&s, at), &text);
+            len += 202;
+        }
+    }
+    assert!(rope.chunks().count() > 500, "{} chunks", rope.chunks().count());
+    check(&rope, &s).unwrap();
+}
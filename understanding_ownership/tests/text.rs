@@ -0,0 +1,83 @@
+use understanding_ownership::text::{
+    ceil_boundary, floor_boundary, slice_bytes, slice_chars, slice_graphemes, truncate_width, Stats,
+};
+
+// h é  l l o 日    🦀       !
+// 0 1  3 4 5 6     9        13, 14 bytes
+const S: &str = "héllo日🦀!";
+
+// the slice is part of S, not a copy
+fn points_into(slice: &str, s: &str) -> bool {
+    let range = s.as_bytes().as_ptr_range();
+    let start = slice.as_ptr();
+    range.start <= start && start as usize + slice.len() <= range.end as usize
+}
+
+#[test]
+fn boundaries() {
+    assert_eq!(S.len(), 14);
+    assert_eq!((floor_boundary(S, 2), ceil_boundary(S, 2)), (1, 3));
+    assert_eq!((floor_boundary(S, 7), ceil_boundary(S, 7)), (6, 9));
+    assert_eq!((floor_boundary(S, 11), ceil_boundary(S, 11)), (9, 13));
+    assert_eq!((floor_boundary(S, 9), ceil_boundary(S, 9)), (9, 9));
+    assert_eq!((floor_boundary(S, 100), ceil_boundary(S, 100)), (14, 14));
+}
+
+#[test]
+fn slicing_bytes_mid_char() {
+    // S[0..2] would panic, the end is inside é
+    assert_eq!(slice_bytes(S, 0..2), "h");
+    assert_eq!(slice_bytes(S, 2..6), "llo");
+    assert_eq!(slice_bytes(S, 0..7), "héllo");
+    assert_eq!(slice_bytes(S, 6..9), "日");
+    assert_eq!(slice_bytes(S, 9..13), "🦀");
+    assert_eq!(slice_bytes(S, 10..14), "!");
+    // both ends inside the crab
+    assert_eq!(slice_bytes(S, 10..12), "");
+    assert_eq!(slice_bytes(S, 0..100), S);
+    // a start after the end
+    let (start, end) = (5, 3);
+    assert_eq!(slice_bytes(S, start..end), "");
+    assert_eq!(slice_bytes("", 0..1), "");
+    for range in [0..2, 2..6, 6..9, 9..13, 0..100] {
+        assert!(points_into(slice_bytes(S, range.clone()), S), "{:?}", range);
+    }
+}
+
+#[test]
+fn slicing_chars_and_graphemes() {
+    assert_eq!(slice_chars(S, 1..2), "é");
+    assert_eq!(slice_chars(S, 5..7), "日🦀");
+    assert_eq!(slice_chars(S, 6..100), "🦀!");
+    assert_eq!(slice_chars(S, 8..10), "");
+    assert_eq!(slice_chars(S, 3..3), "");
+    assert!(points_into(slice_chars(S, 5..7), S));
+
+    // é as e + a combining accent is 2 chars but 1 grapheme
+    let s = "ce\u{301}a";
+    assert_eq!(slice_chars(s, 0..2), "ce");
+    assert_eq!(slice_graphemes(s, 0..2), "ce\u{301}");
+    assert_eq!(slice_graphemes(s, 1..2), "e\u{301}");
+    assert_eq!(slice_graphemes("👋🏽!", 0..1), "👋🏽");
+    assert!(points_into(slice_graphemes(s, 1..3), s));
+}
+
+#[test]
+fn width() {
+    // 日 and 本 are 2 columns each, a half-fitting one is left out
+    assert_eq!(truncate_width("日本語", 5), "日本");
+    assert_eq!(truncate_width("日本語", 6), "日本語");
+    assert_eq!(truncate_width("héllo", 2), "hé");
+    assert_eq!(truncate_width("🦀🦀", 1), "");
+}
+
+#[test]
+fn stats() {
+    let s = Stats::new("héllo");
+    assert_eq!((s.bytes, s.chars, s.graphemes, s.width), (6, 5, 5, 5));
+    let s = Stats::new("e\u{301}");
+    assert_eq!((s.bytes, s.chars, s.graphemes), (3, 2, 1));
+    let s = Stats::new("🦀 日本\ndon't stop!\n");
+    assert_eq!((s.chars, s.words, s.lines, s.width), (17, 4, 2, 11));
+    assert_eq!(Stats::new(""), Stats::default());
+}
@@ -0,0 +1,97 @@
+use understanding_ownership::token::{tokenize, Kind, Token, TokenizeExt};
+
+// every token's text is the input at its span, the same bytes and
+// not a copy
+fn check<'a>(input: &'a str, tokens: &[Token<'a>]) {
+    for t in tokens {
+        assert_eq!(&input[t.span.clone()], t.text, "{:?}", t);
+        assert_eq!(t.text.as_ptr(), input[t.span.start..].as_ptr(), "{:?} is a copy", t);
+    }
+}
+
+fn kinds_and_texts(input: &str) -> Vec<(Kind, &str)> {
+    let tokens: Vec<Token> = tokenize(input).collect();
+    check(input, &tokens);
+    tokens.iter().map(|t| (t.kind, t.text)).collect()
+}
+
+#[test]
+fn kinds() {
+    use Kind::*;
+    let closed = Quoted { closed: true };
+    assert_eq!(
+        kinds_and_texts(r#"let x = "hi \"you\""; // 42"#),
+        [
+            (Word, "let"),
+            (Word, "x"),
+            (Punct, "="),
+            (closed, r#""hi \"you\"""#),
+            (Punct, ";"),
+            (Punct, "/"),
+            (Punct, "/"),
+            (Number, "42"),
+        ]
+    );
+    // ' inside a word but not around it, and only digits after a .
+    assert_eq!(
+        kinds_and_texts("don't 'q' 1_000.5 1..3 x.0 _a1"),
+        [
+            (Word, "don't"),
+            (Punct, "'"),
+            (Word, "q"),
+            (Punct, "'"),
+            (Number, "1_000.5"),
+            (Number, "1"),
+            (Punct, "."),
+            (Punct, "."),
+            (Number, "3"),
+            (Word, "x"),
+            (Punct, "."),
+            (Number, "0"),
+            (Word, "_a1"),
+        ]
+    );
+    assert_eq!(kinds_and_texts(r#"say "open"#), [(Word, "say"), (Quoted { closed: false }, r#""open"#)]);
+    assert_eq!(kinds_and_texts(r#""a\\" b"#), [(closed, r#""a\\""#), (Word, "b")]);
+    assert_eq!(kinds_and_texts(" \t\n "), []);
+}
+
+#[test]
+fn multibyte_spans() {
+    let input = "héllo 日本 🦀!";
+    let tokens: Vec<Token> = tokenize(input).collect();
+    check(input, &tokens);
+    let spans: Vec<_> = tokens.iter().map(|t| (t.kind, t.span.clone())).collect();
+    assert_eq!(
+        spans,
+        [(Kind::Word, 0..6), (Kind::Word, 7..13), (Kind::Punct, 14..18), (Kind::Punct, 18..19)]
+    );
+}
+
+#[test]
+fn unquoted_and_rest() {
+    let input = r#""a \"b\"" "open"#;
+    let mut tokens = tokenize(input);
+    assert_eq!(tokens.next().unwrap().unquoted(), r#"a \"b\""#);
+    assert_eq!(tokens.rest(), r#" "open"#);
+    let open = tokens.next().unwrap();
+    assert_eq!(open.unquoted(), "open");
+    // still a slice of the input
+    assert_eq!(open.unquoted().as_ptr(), input[input.len() - 4..].as_ptr());
+    assert_eq!(tokens.rest(), "");
+    assert_eq!(tokens.next(), None);
+}
+
+#[test]
+fn tokens_of_many_strs() {
+    let text = String::from("one two\n\n3 \"x\"\n");
+    let lines: Vec<&str> = text.lines().collect();
+    let found: Vec<(usize, &str)> = text.lines().tokens().map(|(i, t)| (i, t.text)).collect();
+    assert_eq!(found, [(0, "one"), (0, "two"), (2, "3"), (2, "\"x\"")]);
+    // spans are within each line, and the text is still text's bytes
+    for (i, t) in text.lines().tokens() {
+        assert_eq!(&lines[i][t.span.clone()], t.text);
+        let range = text.as_bytes().as_ptr_range();
+        assert!(range.contains(&t.text.as_ptr()));
+    }
+}